@@ -6,6 +6,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         "../../contracts/protobuf/v1"
     };
 
+    // Client for the verifier services
     tonic_prost_build::configure()
         .build_server(false)
         .build_client(true)
@@ -13,5 +14,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
             &[format!("{}/verifier.proto", proto_path)],
             &[proto_path.to_string()],
         )?;
+
+    // Server for the public gateway API
+    tonic_prost_build::configure()
+        .build_server(true)
+        .build_client(false)
+        .compile_protos(
+            &[format!("{}/gateway.proto", proto_path)],
+            &[proto_path.to_string()],
+        )?;
     Ok(())
 }
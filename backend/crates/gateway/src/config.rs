@@ -1,6 +1,14 @@
+use std::collections::HashMap;
 use std::env;
+use std::str::FromStr;
+use std::time::Duration;
 use tower_http::cors::AllowOrigin;
 
+use crate::services::{
+    AnchorConfig, AnchorWorkerConfig, BlobBackend, ChainFormat, IntegrityPolicy, ProofStoreConfig, VerifierPolicy, VerifierTls,
+    WebhookConfig,
+};
+
 #[derive(Debug, thiserror::Error)]
 pub enum ConfigError {
     #[error("Missing required environment variable: {0}")]
@@ -16,10 +24,27 @@ pub struct Config {
     pub internal_api_secret: String,
     pub host: String,
     pub port: u16,
+    /// Port of the public gRPC API (None when GRPC_PORT=0)
+    pub grpc_port: Option<u16>,
+    pub grpc_max_message_bytes: usize,
+    /// Largest proof accepted on any verify path
+    pub max_proof_bytes: u64,
     pub cors_origins: AllowOrigin,
     pub rate_limit_requests: u32,
     pub rate_limit_window_secs: u64,
     pub max_db_connections: u32,
+    pub verifier_policy: VerifierPolicy,
+    /// TLS for verifier connections; plaintext when None
+    pub verifier_tls: Option<VerifierTls>,
+    /// Proof storage; proofs are not kept when None
+    pub proof_store: Option<ProofStoreConfig>,
+    /// Accepted chains, their explorers and the anchor integrity policy
+    pub anchor: AnchorConfig,
+    /// Built-in anchoring with the mock submitter; disabled when None
+    pub anchor_worker: Option<AnchorWorkerConfig>,
+    pub webhooks: WebhookConfig,
+    /// How often scheduled VK deprecations and revocations are applied
+    pub vk_schedule_interval: Duration,
 }
 
 impl Config {
@@ -61,6 +86,48 @@ impl Config {
             .parse()
             .map_err(|_| ConfigError::InvalidValue("MAX_DB_CONNECTIONS".into(), "must be a number".into()))?;
 
+        let verifier_policy = VerifierPolicy {
+            request_timeout: Duration::from_secs(parsed_var("VERIFIER_TIMEOUT_SECS", 310)?),
+            max_retries: parsed_var("VERIFIER_MAX_RETRIES", 2)?,
+            retry_backoff: Duration::from_millis(parsed_var("VERIFIER_RETRY_BACKOFF_MS", 200)?),
+            breaker_threshold: parsed_var("VERIFIER_BREAKER_THRESHOLD", 5)?,
+            breaker_cooldown: Duration::from_secs(parsed_var("VERIFIER_BREAKER_COOLDOWN_SECS", 30)?),
+            health_interval: Duration::from_secs(parsed_var("VERIFIER_HEALTH_INTERVAL_SECS", 15)?),
+        };
+
+        let verifier_tls = verifier_tls()?;
+
+        let grpc_port = Some(parsed_var("GRPC_PORT", 50050u16)?).filter(|p| *p != 0);
+        let grpc_max_message_bytes = parsed_var("GRPC_MAX_MESSAGE_BYTES", 32 * 1024 * 1024)?;
+        let max_proof_bytes = parsed_var("MAX_PROOF_BYTES", 256 * 1024 * 1024)?;
+        let proof_store = proof_store()?;
+        let anchor_worker = anchor_worker()?;
+        let chain_formats = chain_formats()?;
+        let anchor = AnchorConfig {
+            explorer_urls: explorer_urls()?,
+            finality_depths: finality_depths(&chain_formats)?,
+            chain_formats,
+            integrity_policy: optional_var("ANCHOR_INTEGRITY")
+                .map(|v| v.parse())
+                .transpose()
+                .map_err(|e| ConfigError::InvalidValue("ANCHOR_INTEGRITY".into(), e))?
+                .unwrap_or(IntegrityPolicy::Reject),
+        };
+
+        let webhooks = WebhookConfig {
+            poll_interval: Duration::from_millis(nonzero_var("WEBHOOK_POLL_INTERVAL_MS", 1000)?),
+            batch_size: parsed_var("WEBHOOK_BATCH_SIZE", 50)?,
+            request_timeout: Duration::from_secs(parsed_var("WEBHOOK_TIMEOUT_SECS", 10)?),
+            max_attempts: parsed_var("WEBHOOK_MAX_ATTEMPTS", 8)?,
+            retry_base: Duration::from_secs(parsed_var("WEBHOOK_RETRY_BASE_SECS", 30)?),
+            retry_max: Duration::from_secs(parsed_var("WEBHOOK_RETRY_MAX_SECS", 6 * 3600)?),
+            allow_http: env::var("WEBHOOK_ALLOW_HTTP").is_ok_and(|v| v == "true"),
+            allow_private_targets: env::var("WEBHOOK_ALLOW_PRIVATE_TARGETS")
+                .is_ok_and(|v| v == "true"),
+        };
+
+        let vk_schedule_interval = Duration::from_secs(parsed_var("VK_SCHEDULE_INTERVAL_SECS", 60)?);
+
         Ok(Self {
             database_url,
             zisk_service_url: env::var("ZISK_SERVICE_URL")
@@ -69,14 +136,241 @@ impl Config {
                 .unwrap_or_else(|_| "dev-internal-secret".to_string()),
             host: env::var("HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
             port,
+            grpc_port,
+            grpc_max_message_bytes,
+            max_proof_bytes,
             cors_origins,
             rate_limit_requests,
             rate_limit_window_secs,
             max_db_connections,
+            verifier_policy,
+            verifier_tls,
+            proof_store,
+            anchor,
+            anchor_worker,
+            webhooks,
+            vk_schedule_interval,
         })
     }
 }
 
+/// TLS is enabled as soon as a CA or client certificate is configured
+fn verifier_tls() -> Result<Option<VerifierTls>, ConfigError> {
+    let ca_cert = optional_var("VERIFIER_TLS_CA_CERT");
+    let client_cert = optional_var("VERIFIER_TLS_CLIENT_CERT");
+    let client_key = optional_var("VERIFIER_TLS_CLIENT_KEY");
+    let domain = optional_var("VERIFIER_TLS_DOMAIN");
+    let enabled = env::var("VERIFIER_TLS").is_ok_and(|v| v == "true");
+
+    let client_identity = match (client_cert, client_key) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        (None, None) => None,
+        _ => {
+            return Err(ConfigError::InvalidValue(
+                "VERIFIER_TLS_CLIENT_CERT".into(),
+                "VERIFIER_TLS_CLIENT_CERT and VERIFIER_TLS_CLIENT_KEY must be set together".into(),
+            ));
+        }
+    };
+
+    if !enabled && ca_cert.is_none() && client_identity.is_none() {
+        return Ok(None);
+    }
+
+    Ok(Some(VerifierTls {
+        ca_cert,
+        client_identity,
+        domain,
+    }))
+}
+
+/// PROOF_STORE selects the blob backend: `fs`, `s3`, or unset to disable storage
+fn proof_store() -> Result<Option<ProofStoreConfig>, ConfigError> {
+    let backend = match optional_var("PROOF_STORE").as_deref() {
+        None | Some("none") => return Ok(None),
+        Some("fs") => BlobBackend::Filesystem {
+            root: optional_var("PROOF_STORE_PATH")
+                .unwrap_or_else(|| "/var/lib/prova/proofs".to_string())
+                .into(),
+        },
+        Some("s3") => BlobBackend::S3 {
+            bucket: required_var("PROOF_STORE_S3_BUCKET")?,
+            region: optional_var("PROOF_STORE_S3_REGION").unwrap_or_else(|| "us-east-1".to_string()),
+            endpoint: optional_var("PROOF_STORE_S3_ENDPOINT"),
+            access_key_id: optional_var("PROOF_STORE_S3_ACCESS_KEY_ID"),
+            secret_access_key: optional_var("PROOF_STORE_S3_SECRET_ACCESS_KEY"),
+            allow_http: env::var("PROOF_STORE_S3_ALLOW_HTTP").is_ok_and(|v| v == "true"),
+        },
+        Some(other) => {
+            return Err(ConfigError::InvalidValue(
+                "PROOF_STORE".into(),
+                format!("unknown backend '{}', expected fs or s3", other),
+            ));
+        }
+    };
+
+    Ok(Some(ProofStoreConfig {
+        backend,
+        default_retention_days: parsed_var("PROOF_RETENTION_DAYS", 30)?,
+        purge_interval: Duration::from_secs(parsed_var("PROOF_STORE_PURGE_INTERVAL_SECS", 3600)?),
+    }))
+}
+
+const DEFAULT_EXPLORER_URLS: &[(&str, &str)] = &[
+    ("ethereum", "https://etherscan.io/tx/{tx}"),
+    ("sepolia", "https://sepolia.etherscan.io/tx/{tx}"),
+    ("base", "https://basescan.org/tx/{tx}"),
+    ("arbitrum", "https://arbiscan.io/tx/{tx}"),
+    ("optimism", "https://optimistic.etherscan.io/tx/{tx}"),
+    ("polygon", "https://polygonscan.com/tx/{tx}"),
+    ("bitcoin", "https://mempool.space/tx/{tx}"),
+    ("solana", "https://explorer.solana.com/tx/{tx}"),
+];
+
+const DEFAULT_CHAIN_FORMATS: &[(&str, ChainFormat)] = &[
+    ("ethereum", ChainFormat::Evm),
+    ("sepolia", ChainFormat::Evm),
+    ("base", ChainFormat::Evm),
+    ("arbitrum", ChainFormat::Evm),
+    ("optimism", ChainFormat::Evm),
+    ("polygon", ChainFormat::Evm),
+    ("bitcoin", ChainFormat::Bitcoin),
+    ("solana", ChainFormat::Solana),
+    // Roots published by the mock submitter
+    ("local", ChainFormat::Evm),
+];
+
+/// Confirmations after which a reorg is not expected
+const DEFAULT_FINALITY_DEPTHS: &[(&str, i64)] = &[
+    ("ethereum", 64),
+    ("sepolia", 64),
+    ("base", 64),
+    ("arbitrum", 64),
+    ("optimism", 64),
+    ("polygon", 128),
+    ("bitcoin", 6),
+    ("solana", 32),
+    // The mock submitter's blocks are final at once
+    ("local", 1),
+];
+
+/// EXPLORER_URLS adds or overrides entries as `chain=https://.../tx/{tx},...`
+fn explorer_urls() -> Result<HashMap<String, String>, ConfigError> {
+    let mut urls: HashMap<String, String> = DEFAULT_EXPLORER_URLS
+        .iter()
+        .map(|(chain, url)| (chain.to_string(), url.to_string()))
+        .collect();
+
+    for entry in optional_var("EXPLORER_URLS").iter().flat_map(|v| v.split(',')) {
+        let (chain, url) = entry
+            .split_once('=')
+            .filter(|(_, url)| url.contains("{tx}"))
+            .ok_or_else(|| {
+                ConfigError::InvalidValue(
+                    "EXPLORER_URLS".into(),
+                    format!("'{}' is not chain=url with a {{tx}} placeholder", entry.trim()),
+                )
+            })?;
+        urls.insert(chain.trim().to_string(), url.trim().to_string());
+    }
+
+    Ok(urls)
+}
+
+/// ANCHOR_WORKER selects the submitter of the built-in anchoring worker: `mock`, or unset
+/// to leave anchoring to external chain clients
+fn anchor_worker() -> Result<Option<AnchorWorkerConfig>, ConfigError> {
+    match optional_var("ANCHOR_WORKER").as_deref() {
+        None | Some("none") => Ok(None),
+        Some("mock") => Ok(Some(AnchorWorkerConfig {
+            chain: optional_var("ANCHOR_WORKER_CHAIN").unwrap_or_else(|| "local".to_string()),
+            interval: Duration::from_secs(nonzero_var("ANCHOR_WORKER_INTERVAL_SECS", 60)?),
+            max_batch_size: parsed_var("ANCHOR_BATCH_SIZE", 1024)?,
+        })),
+        Some(other) => Err(ConfigError::InvalidValue(
+            "ANCHOR_WORKER".into(),
+            format!("unknown submitter '{}', expected mock", other),
+        )),
+    }
+}
+
+/// ANCHOR_CHAINS adds or overrides accepted chains as `chain=evm|bitcoin|solana,...`
+fn chain_formats() -> Result<HashMap<String, ChainFormat>, ConfigError> {
+    let mut formats: HashMap<String, ChainFormat> = DEFAULT_CHAIN_FORMATS
+        .iter()
+        .map(|(chain, format)| (chain.to_string(), *format))
+        .collect();
+
+    for entry in optional_var("ANCHOR_CHAINS").iter().flat_map(|v| v.split(',')) {
+        let (chain, format) = entry.split_once('=').ok_or_else(|| {
+            ConfigError::InvalidValue(
+                "ANCHOR_CHAINS".into(),
+                format!("'{}' is not chain=format", entry.trim()),
+            )
+        })?;
+        let format = format
+            .trim()
+            .parse()
+            .map_err(|e| ConfigError::InvalidValue("ANCHOR_CHAINS".into(), e))?;
+        formats.insert(chain.trim().to_string(), format);
+    }
+
+    Ok(formats)
+}
+
+/// ANCHOR_FINALITY adds or overrides finality depths as `chain=confirmations,...`.
+/// Accepted chains without a depth use ANCHOR_FINALITY_DEFAULT (12).
+fn finality_depths(
+    chain_formats: &HashMap<String, ChainFormat>,
+) -> Result<HashMap<String, i64>, ConfigError> {
+    let mut depths: HashMap<String, i64> = DEFAULT_FINALITY_DEPTHS
+        .iter()
+        .map(|(chain, depth)| (chain.to_string(), *depth))
+        .collect();
+
+    for entry in optional_var("ANCHOR_FINALITY").iter().flat_map(|v| v.split(',')) {
+        let (chain, depth) = entry
+            .split_once('=')
+            .and_then(|(chain, depth)| Some((chain, depth.trim().parse::<i64>().ok()?)))
+            .filter(|(_, depth)| *depth > 0)
+            .ok_or_else(|| {
+                ConfigError::InvalidValue(
+                    "ANCHOR_FINALITY".into(),
+                    format!("'{}' is not chain=confirmations", entry.trim()),
+                )
+            })?;
+        depths.insert(chain.trim().to_string(), depth);
+    }
+
+    let default_depth: i64 = parsed_var("ANCHOR_FINALITY_DEFAULT", 12)?;
+    for chain in chain_formats.keys() {
+        depths.entry(chain.clone()).or_insert(default_depth);
+    }
+
+    Ok(depths)
+}
+
+fn optional_var(name: &str) -> Option<String> {
+    env::var(name).ok().filter(|v| !v.is_empty())
+}
+
 fn required_var(name: &str) -> Result<String, ConfigError> {
     env::var(name).map_err(|_| ConfigError::MissingVar(name.into()))
 }
+
+fn parsed_var<T: FromStr>(name: &str, default: T) -> Result<T, ConfigError> {
+    match env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| ConfigError::InvalidValue(name.into(), "must be a number".into())),
+        Err(_) => Ok(default),
+    }
+}
+
+/// Interval of a background task that has no off switch; tokio intervals cannot be zero
+fn nonzero_var(name: &str, default: u64) -> Result<u64, ConfigError> {
+    match parsed_var(name, default)? {
+        0 => Err(ConfigError::InvalidValue(name.into(), "must be greater than 0".into())),
+        value => Ok(value),
+    }
+}
@@ -0,0 +1,29 @@
+-- Migration 009: Proof store
+-- Submitted proofs and public inputs are kept as content-addressed blobs
+-- (filesystem or S3); these tables track which blobs each user stored and until when
+
+CREATE TABLE IF NOT EXISTS proof_blobs (
+    -- SHA-256 of the blob content, hex encoded
+    digest VARCHAR(64) PRIMARY KEY,
+    size_bytes BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS stored_proofs (
+    proof_hash VARCHAR(66) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    proof_digest VARCHAR(64) NOT NULL REFERENCES proof_blobs(digest),
+    public_inputs_digest VARCHAR(64) REFERENCES proof_blobs(digest),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMP WITH TIME ZONE,
+
+    PRIMARY KEY (proof_hash, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stored_proofs_proof_hash ON stored_proofs(proof_hash);
+CREATE INDEX IF NOT EXISTS idx_stored_proofs_expires_at ON stored_proofs(expires_at);
+CREATE INDEX IF NOT EXISTS idx_stored_proofs_proof_digest ON stored_proofs(proof_digest);
+CREATE INDEX IF NOT EXISTS idx_stored_proofs_public_inputs_digest ON stored_proofs(public_inputs_digest);
+
+-- Days to keep a user's proofs: NULL uses the gateway default, 0 disables storage
+ALTER TABLE users ADD COLUMN IF NOT EXISTS proof_retention_days INTEGER;
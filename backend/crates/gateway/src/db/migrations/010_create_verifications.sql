@@ -0,0 +1,69 @@
+-- Migration 010: Verification records and re-verification jobs
+-- Every verification outcome is recorded so proofs can be re-checked later
+
+CREATE TABLE IF NOT EXISTS reverification_jobs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    triggered_by UUID REFERENCES users(id),
+    reason TEXT,
+    -- Scope: proofs last verified with this VK and/or this prover version
+    vk_id UUID REFERENCES verification_keys(id),
+    prover_version VARCHAR(50),
+    status VARCHAR(20) NOT NULL DEFAULT 'running',
+    total INTEGER NOT NULL DEFAULT 0,
+    processed INTEGER NOT NULL DEFAULT 0,
+    skipped INTEGER NOT NULL DEFAULT 0,
+    discrepancies INTEGER NOT NULL DEFAULT 0,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    finished_at TIMESTAMP WITH TIME ZONE,
+    -- Refreshed while a job runs; a job whose heartbeat stops was lost with its gateway
+    -- process and is marked failed
+    heartbeat_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT chk_reverification_status CHECK (status IN ('running', 'completed', 'failed'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_reverification_jobs_running
+    ON reverification_jobs(heartbeat_at) WHERE status = 'running';
+
+CREATE TABLE IF NOT EXISTS verifications (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    proof_hash VARCHAR(66) NOT NULL,
+    public_inputs_hash VARCHAR(66),
+    prover VARCHAR(100) NOT NULL,
+    proof_system VARCHAR(50) NOT NULL,
+    prover_version VARCHAR(50) NOT NULL,
+    verifier_version VARCHAR(50),
+    vk_id UUID REFERENCES verification_keys(id),
+    vk_hash VARCHAR(66),
+    valid BOOLEAN NOT NULL,
+    error TEXT,
+    -- Set when the record was produced by a re-verification job
+    reverification_job_id UUID REFERENCES reverification_jobs(id),
+    verified_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_verifications_proof_hash ON verifications(proof_hash, verified_at);
+CREATE INDEX IF NOT EXISTS idx_verifications_user ON verifications(user_id, verified_at);
+CREATE INDEX IF NOT EXISTS idx_verifications_vk_id ON verifications(vk_id);
+CREATE INDEX IF NOT EXISTS idx_verifications_prover_version ON verifications(prover, prover_version);
+
+-- One row per re-verified proof; a discrepancy is a proof that was valid and no longer is
+CREATE TABLE IF NOT EXISTS reverification_results (
+    job_id UUID NOT NULL REFERENCES reverification_jobs(id) ON DELETE CASCADE,
+    proof_hash VARCHAR(66) NOT NULL,
+    user_id UUID NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    previous_verification_id UUID NOT NULL REFERENCES verifications(id) ON DELETE CASCADE,
+    verification_id UUID REFERENCES verifications(id) ON DELETE CASCADE,
+    previous_valid BOOLEAN NOT NULL,
+    valid BOOLEAN,
+    -- Why the proof could not be re-verified (for example, no longer stored)
+    skipped_reason TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    PRIMARY KEY (job_id, proof_hash, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_reverification_results_discrepancies
+    ON reverification_results(job_id) WHERE previous_valid AND NOT valid;
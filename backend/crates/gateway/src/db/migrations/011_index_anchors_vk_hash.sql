@@ -0,0 +1,4 @@
+-- Migration 011: Index anchors by VK hash
+-- Supports filtering anchor listings by vk_hash
+
+CREATE INDEX IF NOT EXISTS idx_anchors_vk_hash ON anchors(vk_hash);
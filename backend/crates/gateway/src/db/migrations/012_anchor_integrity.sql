@@ -0,0 +1,62 @@
+-- Migration 012: Anchor integrity checks and revision history
+-- Anchors are checked against verification records, and every change is kept in an
+-- append-only revision table instead of being overwritten silently
+
+-- Room for non-EVM transaction ids (e.g. base58 Solana signatures)
+ALTER TABLE anchors ALTER COLUMN tx_hash TYPE VARCHAR(128);
+ALTER TABLE anchors ALTER COLUMN block_hash TYPE VARCHAR(128);
+
+-- verified: matches the latest verification of the proof with that VK
+-- unverified: Prova has no verification of the proof with that VK
+-- mismatch: the anchored outcome, prover or proof system differs from the record
+-- unchecked: recorded before integrity checks existed
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS integrity_status VARCHAR(20) NOT NULL DEFAULT 'unchecked'
+    CHECK (integrity_status IN ('verified', 'unverified', 'mismatch', 'unchecked'));
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS integrity_note TEXT;
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS verification_id UUID REFERENCES verifications(id);
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS updated_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW();
+
+CREATE INDEX IF NOT EXISTS idx_anchors_integrity ON anchors(integrity_status)
+    WHERE integrity_status <> 'verified';
+
+CREATE TABLE IF NOT EXISTS anchor_revisions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    anchor_id UUID NOT NULL REFERENCES anchors(id),
+    revision INTEGER NOT NULL,
+    vk_hash VARCHAR(66) NOT NULL,
+    valid BOOLEAN NOT NULL,
+    prover VARCHAR(100) NOT NULL,
+    proof_system VARCHAR(50) NOT NULL,
+    block_number BIGINT,
+    block_hash VARCHAR(128),
+    block_timestamp TIMESTAMP WITH TIME ZONE,
+    tx_hash VARCHAR(128),
+    explorer_url TEXT,
+    integrity_status VARCHAR(20) NOT NULL,
+    integrity_note TEXT,
+    verification_id UUID REFERENCES verifications(id),
+    recorded_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+
+    UNIQUE(anchor_id, revision)
+);
+
+-- Existing anchors become their first revision
+INSERT INTO anchor_revisions
+    (anchor_id, revision, vk_hash, valid, prover, proof_system, block_number, block_hash,
+     block_timestamp, tx_hash, explorer_url, integrity_status, recorded_at)
+SELECT id, 1, vk_hash, valid, prover, proof_system, block_number, block_hash,
+       block_timestamp, tx_hash, explorer_url, integrity_status, COALESCE(created_at, NOW())
+FROM anchors
+WHERE NOT EXISTS (SELECT 1 FROM anchor_revisions r WHERE r.anchor_id = anchors.id);
+
+-- Revisions are never changed or removed
+CREATE OR REPLACE FUNCTION reject_anchor_revision_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'anchor_revisions is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS anchor_revisions_append_only ON anchor_revisions;
+CREATE TRIGGER anchor_revisions_append_only
+    BEFORE UPDATE OR DELETE ON anchor_revisions
+    FOR EACH ROW EXECUTE FUNCTION reject_anchor_revision_change();
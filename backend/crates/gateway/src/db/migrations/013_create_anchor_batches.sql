@@ -0,0 +1,48 @@
+-- Migration 013: Anchor batches built by the gateway's anchoring worker
+-- Verification results are committed to a Merkle root per batch; the root goes on chain
+-- and every proof keeps its inclusion path
+
+CREATE TABLE IF NOT EXISTS anchor_batches (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    chain VARCHAR(50) NOT NULL,
+    merkle_root VARCHAR(66) NOT NULL,
+    leaf_count INTEGER NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'submitted', 'failed')),
+    tx_hash VARCHAR(128),
+    block_number BIGINT,
+    block_hash VARCHAR(128),
+    block_timestamp TIMESTAMP WITH TIME ZONE,
+    explorer_url TEXT,
+    error TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    submitted_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_batches_chain ON anchor_batches(chain, created_at);
+
+CREATE TABLE IF NOT EXISTS anchor_batch_items (
+    batch_id UUID NOT NULL REFERENCES anchor_batches(id) ON DELETE CASCADE,
+    leaf_index INTEGER NOT NULL,
+    proof_hash VARCHAR(66) NOT NULL,
+    vk_hash VARCHAR(66) NOT NULL,
+    valid BOOLEAN NOT NULL,
+    verification_id UUID NOT NULL REFERENCES verifications(id),
+    leaf_hash VARCHAR(66) NOT NULL,
+    -- Sibling hashes from the leaf up: [{"side": "left" | "right", "hash": "0x..."}]
+    merkle_path JSONB NOT NULL,
+    -- Why the leaf's anchor could not be recorded; the verification is not batched again
+    anchor_error TEXT,
+
+    PRIMARY KEY (batch_id, leaf_index)
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_batch_items_proof ON anchor_batch_items(proof_hash);
+
+-- Anchors written by the worker point at their batch
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS batch_id UUID REFERENCES anchor_batches(id);
+ALTER TABLE anchor_revisions ADD COLUMN IF NOT EXISTS batch_id UUID REFERENCES anchor_batches(id);
+
+-- Latest verification per proof, used to find results not anchored yet
+CREATE INDEX IF NOT EXISTS idx_verifications_latest_with_vk
+    ON verifications(proof_hash, verified_at DESC) WHERE vk_hash IS NOT NULL;
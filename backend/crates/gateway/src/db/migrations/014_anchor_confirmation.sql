@@ -0,0 +1,13 @@
+-- Migration 014: Confirmation tracking for anchors
+-- Chain clients report how deep an anchor's block is. Anchors move from pending to
+-- confirmed to finalized, or to orphaned when a reorg drops their block
+
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS confirmation_status VARCHAR(20) NOT NULL DEFAULT 'pending'
+    CHECK (confirmation_status IN ('pending', 'confirmed', 'finalized', 'orphaned'));
+-- Blocks on top of and including the anchor's block, as last reported
+ALTER TABLE anchors ADD COLUMN IF NOT EXISTS confirmations BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_anchors_chain_confirmation ON anchors(chain, confirmation_status);
+
+ALTER TABLE anchor_revisions ADD COLUMN IF NOT EXISTS confirmation_status VARCHAR(20) NOT NULL DEFAULT 'pending';
+ALTER TABLE anchor_revisions ADD COLUMN IF NOT EXISTS confirmations BIGINT;
@@ -0,0 +1,14 @@
+-- Migration 016: Log of registry changes streamed by GET /v1/events
+-- Each row is announced on the registry_events channel when its transaction commits;
+-- ids are the SSE event ids clients resume from with Last-Event-ID
+
+CREATE TABLE IF NOT EXISTS registry_events (
+    id BIGSERIAL PRIMARY KEY,
+    -- vk.created, vk.status_changed, vk.alias_changed or anchor.created
+    event_type VARCHAR(50) NOT NULL,
+    prover VARCHAR(100) NOT NULL,
+    data JSONB NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_registry_events_prover ON registry_events(prover, id);
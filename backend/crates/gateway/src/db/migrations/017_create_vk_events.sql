@@ -0,0 +1,40 @@
+-- Migration 017: Audit log of verification key changes
+-- Every create and update of a VK is recorded with its actor and the old and new values
+
+CREATE TABLE IF NOT EXISTS vk_events (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    vk_id UUID NOT NULL REFERENCES verification_keys(id),
+    -- created, updated, or backfilled for VKs that existed before the log
+    action VARCHAR(20) NOT NULL CHECK (action IN ('created', 'updated', 'backfilled')),
+    -- User who made the change; kept when the user is removed
+    actor UUID,
+    old_status VARCHAR(20),
+    new_status VARCHAR(20) NOT NULL,
+    old_deprecation_reason TEXT,
+    new_deprecation_reason TEXT,
+    old_alias VARCHAR(100),
+    new_alias VARCHAR(100),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_vk_events_vk ON vk_events(vk_id, created_at);
+
+-- Existing VKs get a backfilled event with their current values, dated now: earlier
+-- changes were not recorded, so their state before this point is unknown
+INSERT INTO vk_events
+    (vk_id, action, actor, new_status, new_deprecation_reason, new_alias, created_at)
+SELECT id, 'backfilled', NULL, status, deprecation_reason, alias, NOW()
+FROM verification_keys
+WHERE NOT EXISTS (SELECT 1 FROM vk_events e WHERE e.vk_id = verification_keys.id);
+
+-- Events are never changed or removed
+CREATE OR REPLACE FUNCTION reject_vk_event_change() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'vk_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS vk_events_append_only ON vk_events;
+CREATE TRIGGER vk_events_append_only
+    BEFORE UPDATE OR DELETE ON vk_events
+    FOR EACH ROW EXECUTE FUNCTION reject_vk_event_change();
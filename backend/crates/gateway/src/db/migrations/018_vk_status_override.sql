@@ -0,0 +1,4 @@
+-- Migration 018: Justification of admin overrides of the VK state machine
+-- Reactivating a revoked VK needs an admin and a justification, kept in the audit log
+
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS justification TEXT;
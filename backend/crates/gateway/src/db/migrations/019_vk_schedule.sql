@@ -0,0 +1,38 @@
+-- Migration 019: Scheduled VK validity, deprecation and revocation
+-- A VK verifies nothing before valid_from and counts as revoked from revoke_at, whether or
+-- not the scheduler has applied the transition yet
+
+ALTER TABLE verification_keys ADD COLUMN IF NOT EXISTS valid_from TIMESTAMP WITH TIME ZONE;
+ALTER TABLE verification_keys ADD COLUMN IF NOT EXISTS deprecate_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE verification_keys ADD COLUMN IF NOT EXISTS revoke_at TIMESTAMP WITH TIME ZONE;
+
+DO $$
+BEGIN
+  IF NOT EXISTS (
+    SELECT 1 FROM pg_constraint WHERE conname = 'chk_vk_schedule'
+  ) THEN
+    ALTER TABLE verification_keys
+      ADD CONSTRAINT chk_vk_schedule CHECK (
+        (valid_from IS NULL OR revoke_at IS NULL OR valid_from < revoke_at)
+        AND (deprecate_at IS NULL OR revoke_at IS NULL OR deprecate_at <= revoke_at)
+      );
+  END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_vk_deprecate_at ON verification_keys(deprecate_at)
+    WHERE status = 'active' AND deprecate_at IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_vk_revoke_at ON verification_keys(revoke_at)
+    WHERE status <> 'revoked' AND revoke_at IS NOT NULL;
+
+-- The audit log keeps schedule changes, and transitions applied by the scheduler are
+-- recorded as 'scheduled' with no actor
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS old_valid_from TIMESTAMP WITH TIME ZONE;
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS new_valid_from TIMESTAMP WITH TIME ZONE;
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS old_deprecate_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS new_deprecate_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS old_revoke_at TIMESTAMP WITH TIME ZONE;
+ALTER TABLE vk_events ADD COLUMN IF NOT EXISTS new_revoke_at TIMESTAMP WITH TIME ZONE;
+
+ALTER TABLE vk_events DROP CONSTRAINT IF EXISTS vk_events_action_check;
+ALTER TABLE vk_events ADD CONSTRAINT vk_events_action_check
+    CHECK (action IN ('created', 'updated', 'backfilled', 'scheduled'));
@@ -0,0 +1,4 @@
+-- Migration 020: Verifications evaluated against a VK's status at an earlier time
+-- Historical re-checks are kept in the history but never anchored
+
+ALTER TABLE verifications ADD COLUMN IF NOT EXISTS as_of TIMESTAMP WITH TIME ZONE;
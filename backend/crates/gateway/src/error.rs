@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -25,6 +25,41 @@ pub enum ApiError {
     #[error("Proof not found")]
     ProofNotFound,
 
+    #[error("User not found")]
+    UserNotFound,
+
+    #[error("Re-verification job not found")]
+    JobNotFound,
+
+    #[error("Anchor not found")]
+    AnchorNotFound,
+
+    #[error("Webhook not found")]
+    WebhookNotFound,
+
+    #[error("Inclusion proof not found")]
+    InclusionProofNotFound,
+
+    /// A status change the resource's state machine does not allow
+    #[error("Invalid state transition: {0}")]
+    InvalidTransition(String),
+
+    #[error("Anchor does not match verification records: {0}")]
+    AnchorIntegrity(String),
+
+    /// `as_of` predates what a VK's audit log recorded
+    #[error("VK status at as_of is unknown: {0}")]
+    VkHistoryUnknown(String),
+
+    #[error("Chain submission failed: {0}")]
+    Anchoring(String),
+
+    #[error("Proof store error: {0}")]
+    ProofStore(String),
+
+    #[error("Proof storage is not enabled")]
+    ProofStorageDisabled,
+
     #[error("Unsupported prover: {0}")]
     UnsupportedProver(String),
 
@@ -40,6 +75,10 @@ pub enum ApiError {
     #[error("Verifier service error: {0}")]
     VerifierService(String),
 
+    /// Circuit breaker is open; the value is the Retry-After in seconds
+    #[error("Verifier service unavailable, retry after {0}s")]
+    VerifierUnavailable(u64),
+
     #[error("Invalid request: {0}")]
     BadRequest(String),
 
@@ -47,9 +86,10 @@ pub enum ApiError {
     Internal,
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status, message, details) = match &self {
+impl ApiError {
+    /// HTTP status, public message and optional details, shared by REST and gRPC responses
+    pub fn parts(&self) -> (StatusCode, &'static str, Option<String>) {
+        match self {
             ApiError::Database(e) => {
                 tracing::error!("Database error: {}", e);
                 (StatusCode::INTERNAL_SERVER_ERROR, "Database error", None)
@@ -59,6 +99,23 @@ impl IntoResponse for ApiError {
             ApiError::Forbidden => (StatusCode::FORBIDDEN, "Forbidden", None),
             ApiError::RateLimitExceeded => (StatusCode::TOO_MANY_REQUESTS, "Rate limit exceeded", None),
             ApiError::ProofNotFound => (StatusCode::NOT_FOUND, "Proof not found", None),
+            ApiError::UserNotFound => (StatusCode::NOT_FOUND, "User not found", None),
+            ApiError::JobNotFound => (StatusCode::NOT_FOUND, "Re-verification job not found", None),
+            ApiError::AnchorNotFound => (StatusCode::NOT_FOUND, "Anchor not found", None),
+            ApiError::WebhookNotFound => (StatusCode::NOT_FOUND, "Webhook not found", None),
+            ApiError::InclusionProofNotFound => (StatusCode::NOT_FOUND, "Inclusion proof not found", None),
+            ApiError::InvalidTransition(msg) => (StatusCode::CONFLICT, "Invalid state transition", Some(msg.clone())),
+            ApiError::AnchorIntegrity(msg) => (StatusCode::UNPROCESSABLE_ENTITY, "Anchor does not match verification records", Some(msg.clone())),
+            ApiError::VkHistoryUnknown(msg) => (StatusCode::UNPROCESSABLE_ENTITY, "VK status at as_of is unknown", Some(msg.clone())),
+            ApiError::Anchoring(e) => {
+                tracing::error!("Chain submission failed: {}", e);
+                (StatusCode::BAD_GATEWAY, "Chain submission failed", None)
+            }
+            ApiError::ProofStore(e) => {
+                tracing::error!("Proof store error: {}", e);
+                (StatusCode::INTERNAL_SERVER_ERROR, "Proof store error", None)
+            }
+            ApiError::ProofStorageDisabled => (StatusCode::NOT_IMPLEMENTED, "Proof storage is not enabled", None),
             ApiError::UnsupportedProver(p) => (StatusCode::BAD_REQUEST, "Unsupported prover", Some(p.clone())),
             ApiError::UnsupportedProofSystem(s) => (StatusCode::BAD_REQUEST, "Unsupported proof system", Some(s.clone())),
             ApiError::VkNotFound => (StatusCode::NOT_FOUND, "Verification key not found", None),
@@ -67,9 +124,16 @@ impl IntoResponse for ApiError {
                 tracing::error!("Verifier service error: {}", e);
                 (StatusCode::BAD_GATEWAY, "Verifier service unavailable", None)
             }
+            ApiError::VerifierUnavailable(_) => (StatusCode::SERVICE_UNAVAILABLE, "Verifier service temporarily unavailable", None),
             ApiError::BadRequest(msg) => (StatusCode::BAD_REQUEST, "Invalid request", Some(msg.clone())),
             ApiError::Internal => (StatusCode::INTERNAL_SERVER_ERROR, "Internal server error", None),
-        };
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, message, details) = self.parts();
 
         let body = if let Some(d) = details {
             json!({ "error": message, "details": d })
@@ -77,6 +141,42 @@ impl IntoResponse for ApiError {
             json!({ "error": message })
         };
 
-        (status, Json(body)).into_response()
+        let mut response = (status, Json(body)).into_response();
+        if let ApiError::VerifierUnavailable(retry_after) = self {
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+        response
+    }
+}
+
+impl From<ApiError> for tonic::Status {
+    fn from(error: ApiError) -> Self {
+        let (status, message, details) = error.parts();
+        let code = match status {
+            StatusCode::BAD_REQUEST => tonic::Code::InvalidArgument,
+            StatusCode::UNAUTHORIZED => tonic::Code::Unauthenticated,
+            StatusCode::FORBIDDEN => tonic::Code::PermissionDenied,
+            StatusCode::NOT_FOUND => tonic::Code::NotFound,
+            StatusCode::CONFLICT => tonic::Code::AlreadyExists,
+            StatusCode::TOO_MANY_REQUESTS => tonic::Code::ResourceExhausted,
+            StatusCode::UNPROCESSABLE_ENTITY => tonic::Code::FailedPrecondition,
+            StatusCode::NOT_IMPLEMENTED => tonic::Code::Unimplemented,
+            StatusCode::BAD_GATEWAY | StatusCode::SERVICE_UNAVAILABLE => tonic::Code::Unavailable,
+            _ => tonic::Code::Internal,
+        };
+        let message = match details {
+            Some(d) => format!("{}: {}", message, d),
+            None => message.to_string(),
+        };
+
+        let mut grpc_status = tonic::Status::new(code, message);
+        if let ApiError::VerifierUnavailable(retry_after) = error {
+            grpc_status
+                .metadata_mut()
+                .insert("retry-after", retry_after.into());
+        }
+        grpc_status
     }
 }
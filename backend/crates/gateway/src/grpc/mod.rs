@@ -0,0 +1,9 @@
+// Generated code: the batch result oneof holds a full response next to a small error
+#[allow(clippy::large_enum_variant)]
+pub mod proto {
+    tonic::include_proto!("prova.gateway.v1");
+}
+
+pub mod service;
+
+pub use service::*;
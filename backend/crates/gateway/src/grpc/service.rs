@@ -0,0 +1,285 @@
+use tokio_stream::StreamExt;
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::AppState;
+use crate::error::ApiError;
+use crate::grpc::proto::{self, prova_server::Prova, verify_stream_request::Payload};
+use crate::middleware::{AuthenticatedUser, authenticate_api_key, client_key};
+use crate::models::{VerificationKey, VerificationResult, VkInfo};
+use crate::routes::{supported_provers, vk_page_limit};
+use crate::services::{ProofSubmission, StreamSubmission, batch_cost};
+
+pub use proto::prova_server::ProvaServer;
+
+/// Public gRPC API. Shares auth, rate limiting and business logic with the REST routes.
+#[derive(Clone)]
+pub struct ProvaGrpc {
+    state: AppState,
+}
+
+impl ProvaGrpc {
+    pub fn new(state: AppState) -> Self {
+        Self { state }
+    }
+
+    fn api_key<T>(request: &Request<T>) -> Option<&str> {
+        request
+            .metadata()
+            .get("x-api-key")
+            .and_then(|v| v.to_str().ok())
+    }
+
+    async fn authenticate<T>(&self, request: &Request<T>) -> Result<AuthenticatedUser, Status> {
+        let api_key = Self::api_key(request).ok_or(ApiError::Unauthorized)?;
+        Ok(authenticate_api_key(&self.state.db, api_key).await?)
+    }
+
+    fn check_rate_limit<T>(&self, request: &Request<T>) -> Result<(), Status> {
+        self.check_rate_limit_cost(request, 1)
+    }
+
+    fn check_rate_limit_cost<T>(&self, request: &Request<T>, cost: u32) -> Result<(), Status> {
+        let peer = request.remote_addr().map(|addr| addr.ip().to_string());
+        let forwarded_for = request
+            .metadata()
+            .get("x-forwarded-for")
+            .and_then(|v| v.to_str().ok())
+            .or(peer.as_deref());
+
+        let key = client_key(Self::api_key(request), forwarded_for);
+        if !self.state.rate_limiter.check_cost(&key, cost) {
+            return Err(ApiError::RateLimitExceeded.into());
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl Prova for ProvaGrpc {
+    async fn verify(
+        &self,
+        request: Request<proto::VerifyRequest>,
+    ) -> Result<Response<proto::VerifyResponse>, Status> {
+        self.check_rate_limit(&request)?;
+        let user = self.authenticate(&request).await?;
+
+        let result = self
+            .state
+            .verification_service
+            .verify(user.user_id, request.into_inner().into())
+            .await?;
+
+        Ok(Response::new(result.into()))
+    }
+
+    async fn verify_batch(
+        &self,
+        request: Request<proto::VerifyBatchRequest>,
+    ) -> Result<Response<proto::VerifyBatchResponse>, Status> {
+        // Every proof counts as a request
+        self.check_rate_limit_cost(&request, batch_cost(request.get_ref().requests.len()))?;
+        let user = self.authenticate(&request).await?;
+
+        let submissions = request
+            .into_inner()
+            .requests
+            .into_iter()
+            .map(ProofSubmission::from)
+            .collect();
+
+        let results = self
+            .state
+            .verification_service
+            .verify_batch(user.user_id, submissions)
+            .await?
+            .into_iter()
+            .map(|outcome| proto::VerifyBatchResult {
+                outcome: Some(match outcome {
+                    Ok(result) => proto::verify_batch_result::Outcome::Response(result.into()),
+                    Err(e) => {
+                        let status = Status::from(e);
+                        proto::verify_batch_result::Outcome::Error(proto::ItemError {
+                            code: status.code() as i32,
+                            message: status.message().to_string(),
+                        })
+                    }
+                }),
+            })
+            .collect();
+
+        Ok(Response::new(proto::VerifyBatchResponse { results }))
+    }
+
+    async fn verify_stream(
+        &self,
+        request: Request<Streaming<proto::VerifyStreamRequest>>,
+    ) -> Result<Response<proto::VerifyResponse>, Status> {
+        self.check_rate_limit(&request)?;
+        let user = self.authenticate(&request).await?;
+
+        let mut stream = request.into_inner();
+        let metadata = match stream.message().await?.and_then(|m| m.payload) {
+            Some(Payload::Metadata(metadata)) => metadata,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "first VerifyStream message must carry the metadata",
+                ));
+            }
+        };
+
+        let chunks = stream.map(|message| {
+            let message = message
+                .map_err(|e| ApiError::BadRequest(format!("Proof upload failed: {}", e.message())))?;
+            match message.payload {
+                Some(Payload::Chunk(chunk)) => Ok(chunk.into()),
+                Some(Payload::Metadata(_)) => Err(ApiError::BadRequest(
+                    "metadata sent more than once".to_string(),
+                )),
+                None => Ok(Default::default()),
+            }
+        });
+
+        let result = self
+            .state
+            .verification_service
+            .verify_stream(user.user_id, metadata.into(), chunks)
+            .await?;
+
+        Ok(Response::new(result.into()))
+    }
+
+    async fn get_vk(
+        &self,
+        request: Request<proto::GetVkRequest>,
+    ) -> Result<Response<proto::Vk>, Status> {
+        self.check_rate_limit(&request)?;
+
+        let request = request.into_inner();
+        let vk = self
+            .state
+            .vk_service
+            .get_vk(&request.id, request.prover.as_deref())
+            .await?
+            .ok_or(ApiError::VkNotFound)?;
+
+        Ok(Response::new(vk.into()))
+    }
+
+    async fn list_vks(
+        &self,
+        request: Request<proto::ListVksRequest>,
+    ) -> Result<Response<proto::ListVksResponse>, Status> {
+        self.check_rate_limit(&request)?;
+
+        let request = request.into_inner();
+        let (vks, total) = self
+            .state
+            .vk_service
+            .list_vks(
+                request.prover.as_deref(),
+                request.status.as_deref(),
+                vk_page_limit(request.limit),
+                request.offset.unwrap_or(0),
+            )
+            .await?;
+
+        Ok(Response::new(proto::ListVksResponse {
+            vks: vks.into_iter().map(proto::Vk::from).collect(),
+            total: total as u64,
+        }))
+    }
+
+    async fn list_provers(
+        &self,
+        request: Request<proto::ListProversRequest>,
+    ) -> Result<Response<proto::ListProversResponse>, Status> {
+        self.check_rate_limit(&request)?;
+
+        let provers = supported_provers()
+            .into_iter()
+            .map(|p| proto::ProverInfo {
+                name: p.name,
+                proof_systems: p.proof_systems,
+            })
+            .collect();
+
+        Ok(Response::new(proto::ListProversResponse { provers }))
+    }
+}
+
+impl From<proto::VerifyRequest> for ProofSubmission {
+    fn from(request: proto::VerifyRequest) -> Self {
+        ProofSubmission {
+            proof: request.proof,
+            // proto3 cannot tell an empty list from a missing one
+            public_inputs: Some(request.public_inputs).filter(|inputs| !inputs.is_empty()),
+            prover: request.prover,
+            proof_system: request.proof_system,
+            vk_id: request.vk_id,
+            as_of: None,
+        }
+    }
+}
+
+impl From<proto::VerifyStreamMetadata> for StreamSubmission {
+    fn from(metadata: proto::VerifyStreamMetadata) -> Self {
+        StreamSubmission {
+            public_inputs: Some(metadata.public_inputs).filter(|inputs| !inputs.is_empty()),
+            prover: metadata.prover,
+            proof_system: metadata.proof_system,
+            vk_id: metadata.vk_id,
+            as_of: None,
+        }
+    }
+}
+
+impl From<VerificationResult> for proto::VerifyResponse {
+    fn from(result: VerificationResult) -> Self {
+        proto::VerifyResponse {
+            valid: result.valid,
+            prover: result.prover,
+            proof_system: result.proof_system,
+            proof_type: result.proof_type,
+            prover_version: result.prover_version,
+            proof_hash: result.proof_hash,
+            public_inputs_hash: result.public_inputs_hash,
+            vk: result.vk.map(proto::VkInfo::from),
+            verified_at: result.verified_at,
+            error: result.error,
+            public_values: result.public_values,
+            program_id: result.program_id,
+            cycles: result.cycles,
+            verifier_version: result.verifier_version,
+            diagnostics: result.diagnostics,
+        }
+    }
+}
+
+impl From<VkInfo> for proto::VkInfo {
+    fn from(vk: VkInfo) -> Self {
+        proto::VkInfo {
+            id: vk.id.to_string(),
+            hash: vk.hash,
+            alias: vk.alias,
+            status: vk.status,
+            deprecation_reason: vk.deprecation_reason,
+        }
+    }
+}
+
+impl From<VerificationKey> for proto::Vk {
+    fn from(vk: VerificationKey) -> Self {
+        proto::Vk {
+            id: vk.id.to_string(),
+            prover: vk.prover,
+            version: vk.version,
+            proof_system: vk.proof_system,
+            proof_type: vk.proof_type,
+            hash: vk.vk_hash,
+            alias: vk.alias,
+            status: vk.status,
+            deprecation_reason: vk.deprecation_reason,
+            created_at: vk.created_at.to_rfc3339(),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 mod config;
 mod db;
 mod error;
+mod grpc;
 mod middleware;
 mod models;
 mod routes;
@@ -8,25 +9,34 @@ mod services;
 
 use sqlx::PgPool;
 use std::net::SocketAddr;
+use std::sync::Arc;
 use std::time::Duration;
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use config::Config;
+use grpc::{ProvaGrpc, ProvaServer};
 use middleware::RateLimiter;
-use services::{AnchorService, VerifierClient, VkService};
+use services::{
+    AnchorService, AnchorWorker, MockSubmitter, ProofStore, RegistryEvents, ReverificationService, VerificationHistory, VerificationService,
+    VerifierClient, VkService, WebhookService,
+};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db: PgPool,
     pub verifier_client: VerifierClient,
     pub vk_service: VkService,
+    pub verification_service: VerificationService,
+    pub reverification_service: ReverificationService,
+    pub verification_history: VerificationHistory,
     pub anchor_service: AnchorService,
+    pub proof_store: Option<ProofStore>,
+    pub webhook_service: WebhookService,
+    pub registry_events: RegistryEvents,
     pub rate_limiter: RateLimiter,
 }
 
-
-
 #[tokio::main]
 async fn main() {
     tracing_subscriber::registry()
@@ -47,20 +57,90 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
     db::run_migrations(&db_pool).await?;
     tracing::info!("Database migrations completed");
 
-    let verifier_client = VerifierClient::new(config.zisk_service_url.clone()).await?;
+    let verifier_client = VerifierClient::new(
+        &config.zisk_service_url,
+        config.verifier_policy.clone(),
+        config.verifier_tls.as_ref(),
+    )
+    .await?;
 
     // Health check verifier with retries
     check_verifier_health(&verifier_client).await;
 
+    // Keep checking in the background so the circuit breaker tracks verifier health
+    verifier_client.spawn_health_checks();
+
+    let proof_store = config
+        .proof_store
+        .as_ref()
+        .map(|store_config| ProofStore::new(db_pool.clone(), store_config))
+        .transpose()?;
+    if let Some(store) = &proof_store {
+        tracing::info!("Proof storage enabled");
+        store.spawn_purge();
+    }
+
     let vk_service = VkService::new(db_pool.clone());
-    let anchor_service = AnchorService::new(db_pool.clone());
+    vk_service.spawn_scheduler(config.vk_schedule_interval);
+    let verification_history = VerificationHistory::new(db_pool.clone());
+    let verification_service = VerificationService::new(
+        vk_service.clone(),
+        verifier_client.clone(),
+        config.max_proof_bytes,
+        proof_store.clone(),
+        verification_history.clone(),
+    );
+    let reverification_service = ReverificationService::new(
+        db_pool.clone(),
+        vk_service.clone(),
+        verification_service.clone(),
+        verification_history.clone(),
+        proof_store.clone(),
+    );
+    reverification_service.spawn_stale_job_check();
+    let anchor_service = AnchorService::new(
+        db_pool.clone(),
+        verification_history.clone(),
+        config.anchor.clone(),
+    );
+    if let Some(worker_config) = &config.anchor_worker {
+        if !config.anchor.chain_formats.contains_key(&worker_config.chain) {
+            return Err(format!(
+                "Anchoring worker chain '{}' is not in ANCHOR_CHAINS",
+                worker_config.chain
+            )
+            .into());
+        }
+        tracing::info!(chain = %worker_config.chain, "Built-in anchoring enabled (mock submitter)");
+        AnchorWorker::new(
+            db_pool.clone(),
+            verification_history.clone(),
+            anchor_service.clone(),
+            Arc::new(MockSubmitter::new(worker_config.chain.clone())),
+            worker_config,
+        )
+        .spawn();
+    }
+
+    let webhook_service = WebhookService::new(db_pool.clone(), config.webhooks.clone())?;
+    webhook_service.spawn_dispatcher();
+
+    let registry_events = RegistryEvents::new(db_pool.clone());
+    registry_events.spawn_listener();
+
     let rate_limiter = RateLimiter::new(config.rate_limit_requests, config.rate_limit_window_secs);
 
     let state = AppState {
         db: db_pool.clone(),
         verifier_client,
         vk_service,
+        verification_service,
+        reverification_service,
+        verification_history,
         anchor_service,
+        proof_store,
+        webhook_service,
+        registry_events,
         rate_limiter,
     };
 
@@ -69,6 +149,24 @@ async fn run() -> Result<(), Box<dyn std::error::Error>> {
         .allow_methods(Any)
         .allow_headers(Any);
 
+    if let Some(grpc_port) = config.grpc_port {
+        let grpc_addr: SocketAddr = format!("{}:{}", config.host, grpc_port).parse()?;
+        let grpc_service = ProvaServer::new(ProvaGrpc::new(state.clone()))
+            .max_decoding_message_size(config.grpc_max_message_bytes);
+
+        tracing::info!("Starting gRPC server on {}", grpc_addr);
+        tokio::spawn(async move {
+            if let Err(e) = tonic::transport::Server::builder()
+                .add_service(grpc_service)
+                .serve(grpc_addr)
+                .await
+            {
+                tracing::error!("gRPC server error: {}", e);
+                std::process::exit(1);
+            }
+        });
+    }
+
     let app = routes::create_router(state, cors);
 
     let addr: SocketAddr = format!("{}:{}", config.host, config.port).parse()?;
@@ -5,9 +5,11 @@ use axum::{
     response::Response,
 };
 use sha2::{Digest, Sha256};
+use sqlx::PgPool;
 use uuid::Uuid;
 
 use crate::AppState;
+use crate::error::ApiError;
 
 /// Routes that are fully public (all methods)
 const PUBLIC_PATHS: &[&str] = &[
@@ -19,6 +21,21 @@ const PUBLIC_PATHS: &[&str] = &[
 /// Routes that are public only for GET requests
 const PUBLIC_GET_PATHS: &[&str] = &[
     "/v1/vks",
+    "/v1/anchors",
+    "/v1/events",
+];
+
+/// Routes under a public GET path that still need an API key, matched like the patterns
+/// below. Audit logs name the users behind changes.
+const PRIVATE_GET_PATTERNS: &[&str] = &[
+    "/v1/vks/*/history",
+];
+
+/// GET-only public routes matched segment by segment, `*` matching any one segment
+const PUBLIC_GET_PATTERNS: &[&str] = &[
+    "/v1/proofs/*",
+    "/v1/proofs/*/anchors",
+    "/v1/proofs/*/inclusion",
 ];
 
 #[derive(Clone)]
@@ -71,8 +88,22 @@ pub async fn auth_middleware(
         .and_then(|h| h.to_str().ok())
         .ok_or(StatusCode::UNAUTHORIZED)?;
 
+    let user = authenticate_api_key(&state.db, api_key)
+        .await
+        .map_err(|e| match e {
+            ApiError::Database(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            _ => StatusCode::UNAUTHORIZED,
+        })?;
+
+    request.extensions_mut().insert(user);
+
+    Ok(next.run(request).await)
+}
+
+/// Look up the user behind an API key, for both the REST middleware and the gRPC API
+pub async fn authenticate_api_key(db: &PgPool, api_key: &str) -> Result<AuthenticatedUser, ApiError> {
     if api_key.len() < 16 {
-        return Err(StatusCode::UNAUTHORIZED);
+        return Err(ApiError::InvalidApiKey);
     }
 
     let mut hasher = Sha256::new();
@@ -86,30 +117,25 @@ pub async fn auth_middleware(
            WHERE ak.key_hash = $1 AND NOT ak.revoked"#
     )
     .bind(&key_hash)
-    .fetch_optional(&state.db)
+    .fetch_optional(db)
     .await
-    .map_err(|e| {
-        tracing::error!("Database error during auth: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    .inspect_err(|e| tracing::error!("Database error during auth: {}", e))?;
 
-    let auth_result = result.ok_or(StatusCode::UNAUTHORIZED)?;
+    let auth_result = result.ok_or(ApiError::InvalidApiKey)?;
 
     if let Err(e) = sqlx::query("UPDATE api_keys SET last_used_at = NOW() WHERE key_hash = $1")
         .bind(&key_hash)
-        .execute(&state.db)
+        .execute(db)
         .await
     {
         tracing::warn!("Failed to update api key last_used_at: {}", e);
     }
 
-    request.extensions_mut().insert(AuthenticatedUser {
+    Ok(AuthenticatedUser {
         user_id: auth_result.user_id,
         role: auth_result.role,
         managed_prover: auth_result.managed_prover,
-    });
-
-    Ok(next.run(request).await)
+    })
 }
 
 fn is_public_path(path: &str, method: &Method) -> bool {
@@ -124,12 +150,38 @@ fn is_public_path(path: &str, method: &Method) -> bool {
 
     // Check GET-only public paths
     if *method == Method::GET {
+        if PRIVATE_GET_PATTERNS
+            .iter()
+            .any(|pattern| matches_pattern(normalized, pattern))
+        {
+            return false;
+        }
+
         for public in PUBLIC_GET_PATHS {
             if normalized == *public || normalized.starts_with(&format!("{}/", public)) {
                 return true;
             }
         }
+
+        if PUBLIC_GET_PATTERNS
+            .iter()
+            .any(|pattern| matches_pattern(normalized, pattern))
+        {
+            return true;
+        }
     }
 
     false
 }
+
+fn matches_pattern(path: &str, pattern: &str) -> bool {
+    let mut segments = path.split('/');
+    let mut expected = pattern.split('/');
+    loop {
+        match (segments.next(), expected.next()) {
+            (None, None) => return true,
+            (Some(segment), Some(part)) if part == "*" || part == segment => {}
+            _ => return false,
+        }
+    }
+}
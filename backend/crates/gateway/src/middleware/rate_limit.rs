@@ -1,6 +1,6 @@
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     middleware::Next,
     response::Response,
 };
@@ -34,6 +34,11 @@ impl RateLimiter {
     }
 
     pub fn check(&self, key: &str) -> bool {
+        self.check_cost(key, 1)
+    }
+
+    /// Take `cost` requests from the caller's bucket at once, or none if fewer are left
+    pub fn check_cost(&self, key: &str, cost: u32) -> bool {
         let now = Instant::now();
 
         let mut buckets = self.buckets.write().unwrap();
@@ -52,8 +57,8 @@ impl RateLimiter {
             bucket.last_refill = now;
         }
 
-        if bucket.tokens > 0 {
-            bucket.tokens -= 1;
+        if bucket.tokens >= cost {
+            bucket.tokens -= cost;
             true
         } else {
             false
@@ -71,25 +76,30 @@ pub async fn rate_limit_middleware(
     request: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    let key = request
-        .headers()
-        .get("X-API-Key")
-        .and_then(|h| h.to_str().ok())
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| {
-            request
-                .headers()
-                .get("X-Forwarded-For")
-                .and_then(|h| h.to_str().ok())
-                .and_then(|s| s.split(',').next())
-                .unwrap_or("unknown")
-                .trim()
-                .to_string()
-        });
-
+    let key = request_key(request.headers());
     if !state.rate_limiter.check(&key) {
         return Err(StatusCode::TOO_MANY_REQUESTS);
     }
 
     Ok(next.run(request).await)
 }
+
+/// Rate limit bucket for an HTTP request
+pub fn request_key(headers: &HeaderMap) -> String {
+    client_key(
+        headers.get("X-API-Key").and_then(|h| h.to_str().ok()),
+        headers.get("X-Forwarded-For").and_then(|h| h.to_str().ok()),
+    )
+}
+
+/// Rate limit bucket for a caller: the API key, else the first forwarded address
+pub fn client_key(api_key: Option<&str>, forwarded_for: Option<&str>) -> String {
+    match api_key {
+        Some(key) => key.to_string(),
+        None => forwarded_for
+            .and_then(|s| s.split(',').next())
+            .unwrap_or("unknown")
+            .trim()
+            .to_string(),
+    }
+}
@@ -1,4 +1,5 @@
 use chrono::{DateTime, Utc};
+use prova_merkle::Sibling;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
@@ -17,7 +18,65 @@ pub struct Anchor {
     pub block_timestamp: Option<DateTime<Utc>>,
     pub tx_hash: Option<String>,
     pub explorer_url: Option<String>,
+    /// verified, unverified, mismatch or unchecked
+    pub integrity_status: String,
+    pub integrity_note: Option<String>,
+    /// Verification record the anchor was checked against
+    pub verification_id: Option<Uuid>,
+    /// Merkle batch the proof was anchored in, for anchors written by the anchoring worker
+    pub batch_id: Option<Uuid>,
+    /// pending, confirmed, finalized or orphaned
+    pub confirmation_status: String,
+    /// Blocks on top of and including the anchor's block, as last reported
+    pub confirmations: Option<i64>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// One immutable state of an anchor; a new revision is added whenever a chain client
+/// reports something different
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AnchorRevision {
+    pub revision: i32,
+    pub vk_hash: String,
+    pub valid: bool,
+    pub prover: String,
+    pub proof_system: String,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub block_timestamp: Option<DateTime<Utc>>,
+    pub tx_hash: Option<String>,
+    pub explorer_url: Option<String>,
+    pub integrity_status: String,
+    pub integrity_note: Option<String>,
+    pub verification_id: Option<Uuid>,
+    pub batch_id: Option<Uuid>,
+    pub confirmation_status: String,
+    pub confirmations: Option<i64>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A proof's leaf in an anchor batch, joined with the batch and the anchor pointing at it
+#[derive(Debug, Clone, FromRow)]
+pub struct BatchInclusion {
+    pub anchor_id: Uuid,
+    pub chain: String,
+    pub confirmation_status: String,
+    pub confirmations: Option<i64>,
+    pub batch_id: Uuid,
+    pub merkle_root: String,
+    pub leaf_count: i32,
+    pub leaf_index: i32,
+    pub proof_hash: String,
+    pub vk_hash: String,
+    pub valid: bool,
+    pub merkle_path: sqlx::types::Json<Vec<Sibling>>,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub block_timestamp: Option<DateTime<Utc>>,
+    pub tx_hash: Option<String>,
+    pub explorer_url: Option<String>,
+    pub submitted_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -33,6 +92,35 @@ pub struct CreateAnchor {
     pub block_timestamp: Option<DateTime<Utc>>,
     pub tx_hash: Option<String>,
     pub explorer_url: Option<String>,
+    pub batch_id: Option<Uuid>,
+    /// Depth of the anchor's block when reported; decides the confirmation status
+    pub confirmations: Option<i64>,
+}
+
+/// Confirmation progress reported by a chain client for an existing anchor
+#[derive(Debug)]
+pub struct UpdateAnchor {
+    /// Explicit status; derived from `confirmations` when absent
+    pub status: Option<String>,
+    pub confirmations: Option<i64>,
+    /// Set when the anchor was re-included in another block after a reorg
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub block_timestamp: Option<DateTime<Utc>>,
+    pub tx_hash: Option<String>,
+}
+
+/// Filters for anchor listings
+#[derive(Debug, Default, Clone)]
+pub struct AnchorFilter {
+    pub proof_hash: Option<String>,
+    pub chain: Option<String>,
+    pub vk_hash: Option<String>,
+    pub confirmation_status: Option<String>,
+    /// Inclusive lower bound on `created_at`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `created_at`
+    pub to: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -44,7 +132,25 @@ pub struct AnchorResponse {
     pub prover: String,
     pub proof_system: String,
     pub chain: ChainInfo,
+    pub integrity: AnchorIntegrity,
+    pub confirmation: AnchorConfirmation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub batch_id: Option<Uuid>,
     pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnchorIntegrity {
+    pub status: String,
+    pub note: Option<String>,
+    pub verification_id: Option<Uuid>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AnchorConfirmation {
+    pub status: String,
+    pub confirmations: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -74,7 +180,18 @@ impl From<Anchor> for AnchorResponse {
                 tx_hash: anchor.tx_hash,
                 explorer_url: anchor.explorer_url,
             },
+            integrity: AnchorIntegrity {
+                status: anchor.integrity_status,
+                note: anchor.integrity_note,
+                verification_id: anchor.verification_id,
+            },
+            confirmation: AnchorConfirmation {
+                status: anchor.confirmation_status,
+                confirmations: anchor.confirmations,
+            },
+            batch_id: anchor.batch_id,
             created_at: anchor.created_at,
+            updated_at: anchor.updated_at,
         }
     }
 }
@@ -1,9 +1,17 @@
 pub mod anchor;
 pub mod api_key;
+pub mod registry_event;
+pub mod reverification;
 pub mod user;
+pub mod verification;
 pub mod verification_key;
+pub mod webhook;
 
 pub use anchor::*;
 pub use api_key::*;
+pub use registry_event::*;
+pub use reverification::*;
 pub use user::*;
+pub use verification::*;
 pub use verification_key::*;
+pub use webhook::*;
@@ -0,0 +1,14 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+
+/// A registry change as streamed by GET /v1/events
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct RegistryEvent {
+    pub id: i64,
+    #[serde(rename = "type")]
+    pub event_type: String,
+    pub prover: String,
+    pub data: serde_json::Value,
+    pub created_at: DateTime<Utc>,
+}
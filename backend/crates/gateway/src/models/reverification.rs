@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReverificationJob {
+    pub id: Uuid,
+    pub triggered_by: Option<Uuid>,
+    pub reason: Option<String>,
+    pub vk_id: Option<Uuid>,
+    pub prover_version: Option<String>,
+    /// running, completed or failed
+    pub status: String,
+    /// Proofs in scope
+    pub total: i32,
+    pub processed: i32,
+    /// Proofs that could not be re-verified
+    pub skipped: i32,
+    /// Proofs that were valid and no longer are
+    pub discrepancies: i32,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateReverificationJob {
+    /// Re-verify proofs last verified with this VK (UUID, hash or alias)
+    pub vk_id: Option<String>,
+    /// Re-verify proofs last verified by this prover version
+    pub prover_version: Option<String>,
+    /// Needed to resolve a VK alias; defaults to zisk
+    pub prover: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Outcome of re-verifying one proof
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ReverificationResult {
+    pub proof_hash: String,
+    pub previous_verification_id: Uuid,
+    pub verification_id: Option<Uuid>,
+    pub previous_valid: bool,
+    pub valid: Option<bool>,
+    pub skipped_reason: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
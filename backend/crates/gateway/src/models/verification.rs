@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::VkInfo;
+
+/// Result of verifying one proof, returned by `/v1/verify` and the gRPC Verify call
+#[derive(Debug, Serialize)]
+pub struct VerificationResult {
+    pub valid: bool,
+    pub prover: String,
+    pub proof_system: String,
+    pub proof_type: Option<String>,
+    pub prover_version: String,
+    pub proof_hash: String,
+    pub public_inputs_hash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vk: Option<VkInfo>,
+    pub verified_at: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Values committed by the proven program, as reported by the verifier
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub public_values: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub program_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cycles: Option<u64>,
+    /// Version reported by the verifier binary (may differ from prover_version)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifier_version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<String>,
+}
+
+/// Error for a single item of a batch, in the same shape as a REST error body
+#[derive(Debug, Serialize)]
+pub struct BatchItemError {
+    pub status: u16,
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<String>,
+}
+
+impl From<&ApiError> for BatchItemError {
+    fn from(error: &ApiError) -> Self {
+        let (status, message, details) = error.parts();
+        BatchItemError {
+            status: status.as_u16(),
+            error: message.to_string(),
+            details,
+        }
+    }
+}
+
+/// One entry of a batch response: either a result or an error
+#[derive(Debug, Serialize)]
+pub struct BatchItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<VerificationResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<BatchItemError>,
+}
+
+impl From<Result<VerificationResult, ApiError>> for BatchItem {
+    fn from(outcome: Result<VerificationResult, ApiError>) -> Self {
+        match outcome {
+            Ok(result) => BatchItem {
+                result: Some(result),
+                error: None,
+            },
+            Err(e) => BatchItem {
+                result: None,
+                error: Some(BatchItemError::from(&e)),
+            },
+        }
+    }
+}
+
+/// A recorded verification outcome
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VerificationRecord {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub user_id: Uuid,
+    pub proof_hash: String,
+    pub public_inputs_hash: Option<String>,
+    pub prover: String,
+    pub proof_system: String,
+    pub prover_version: String,
+    pub verifier_version: Option<String>,
+    pub vk_id: Option<Uuid>,
+    pub vk_hash: Option<String>,
+    pub valid: bool,
+    pub error: Option<String>,
+    /// Set when a re-verification job produced this record
+    pub reverification_job_id: Option<Uuid>,
+    pub verified_at: DateTime<Utc>,
+    /// Set when the VK was evaluated at this earlier time rather than at verified_at
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Outcome of a proof on one prover/verifier version and VK, across all verifications
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VerificationOutcome {
+    pub prover: String,
+    pub proof_system: String,
+    pub prover_version: String,
+    pub verifier_version: Option<String>,
+    #[serde(skip_serializing)]
+    pub vk_id: Option<Uuid>,
+    pub vk_hash: Option<String>,
+    /// Outcome of the most recent verification
+    pub valid: bool,
+    pub error: Option<String>,
+    pub verification_count: i64,
+    pub first_verified_at: DateTime<Utc>,
+    pub last_verified_at: DateTime<Utc>,
+}
@@ -1,5 +1,5 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
@@ -28,6 +28,16 @@ impl VkStatus {
             _ => None,
         }
     }
+
+    /// Whether a VK may move from this status to `next` without an admin override.
+    /// Status only moves forward, active → deprecated → revoked.
+    pub fn can_become(self, next: VkStatus) -> bool {
+        !matches!(
+            (self, next),
+            (VkStatus::Deprecated, VkStatus::Active)
+                | (VkStatus::Revoked, VkStatus::Active | VkStatus::Deprecated)
+        )
+    }
 }
 
 impl std::fmt::Display for VkStatus {
@@ -51,6 +61,65 @@ pub struct VerificationKey {
     pub registered_by: Option<Uuid>,
     pub created_at: DateTime<Utc>,
     pub active: bool,
+    /// Proofs are not accepted against the VK before this time
+    pub valid_from: Option<DateTime<Utc>>,
+    /// When the VK becomes deprecated
+    pub deprecate_at: Option<DateTime<Utc>>,
+    /// When the VK becomes revoked
+    pub revoke_at: Option<DateTime<Utc>>,
+}
+
+/// Payload of the vk.deprecated and vk.revoked webhooks and the vk.status_changed stream event
+#[derive(Debug, Serialize)]
+pub struct VkStatusChange {
+    pub id: Uuid,
+    pub prover: String,
+    pub version: String,
+    pub proof_system: String,
+    pub vk_hash: String,
+    pub alias: Option<String>,
+    pub previous_status: String,
+    pub status: String,
+    pub reason: Option<String>,
+}
+
+/// Payload of the vk.alias_changed stream event
+#[derive(Debug, Serialize)]
+pub struct VkAliasChange {
+    pub id: Uuid,
+    pub prover: String,
+    pub version: String,
+    pub vk_hash: String,
+    pub previous_alias: Option<String>,
+    pub alias: Option<String>,
+}
+
+/// One entry of a VK's audit log
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct VkEvent {
+    pub id: Uuid,
+    #[serde(skip_serializing)]
+    pub vk_id: Uuid,
+    /// created, updated, scheduled for transitions applied on schedule, or backfilled for
+    /// the state of VKs registered before the audit log existed
+    pub action: String,
+    /// User who made the change; none for scheduled transitions
+    pub actor: Option<Uuid>,
+    pub old_status: Option<String>,
+    pub new_status: String,
+    pub old_deprecation_reason: Option<String>,
+    pub new_deprecation_reason: Option<String>,
+    pub old_alias: Option<String>,
+    pub new_alias: Option<String>,
+    pub old_valid_from: Option<DateTime<Utc>>,
+    pub new_valid_from: Option<DateTime<Utc>>,
+    pub old_deprecate_at: Option<DateTime<Utc>>,
+    pub new_deprecate_at: Option<DateTime<Utc>>,
+    pub old_revoke_at: Option<DateTime<Utc>>,
+    pub new_revoke_at: Option<DateTime<Utc>>,
+    /// Why an admin overrode the state machine
+    pub justification: Option<String>,
+    pub created_at: DateTime<Utc>,
 }
 
 impl VerificationKey {
@@ -58,6 +127,24 @@ impl VerificationKey {
         VkStatus::from_str(&self.status).unwrap_or(VkStatus::Active)
     }
 
+    /// Status at `at`, counting scheduled transitions that are due even if the
+    /// scheduler has not applied them yet
+    pub fn status_at(&self, at: DateTime<Utc>) -> VkStatus {
+        let status = self.status_enum();
+        if status == VkStatus::Revoked || self.revoke_at.is_some_and(|t| t <= at) {
+            VkStatus::Revoked
+        } else if status == VkStatus::Deprecated || self.deprecate_at.is_some_and(|t| t <= at) {
+            VkStatus::Deprecated
+        } else {
+            VkStatus::Active
+        }
+    }
+
+    /// Whether `at` is on or after the VK's `valid_from`
+    pub fn is_valid_at(&self, at: DateTime<Utc>) -> bool {
+        self.valid_from.is_none_or(|t| t <= at)
+    }
+
     pub fn is_active(&self) -> bool {
         self.status_enum() == VkStatus::Active
     }
@@ -79,32 +166,88 @@ pub struct CreateVerificationKey {
     pub proof_type: Option<String>,
     pub alias: Option<String>,
     pub vk_data: serde_json::Value,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub deprecate_at: Option<DateTime<Utc>>,
+    pub revoke_at: Option<DateTime<Utc>>,
+    /// Required when a deprecation or revocation is scheduled
+    pub deprecation_reason: Option<String>,
 }
 
+/// Fields left out are unchanged; `null` clears any of the optional fields
 #[derive(Debug, Deserialize)]
 pub struct UpdateVerificationKey {
     pub status: Option<VkStatus>,
-    pub deprecation_reason: Option<String>,
-    pub alias: Option<String>,
+    /// Required when deprecating or revoking, now or on schedule
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub deprecation_reason: Option<Option<String>>,
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub alias: Option<Option<String>>,
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub valid_from: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub deprecate_at: Option<Option<DateTime<Utc>>>,
+    #[serde(default, deserialize_with = "explicit_null")]
+    pub revoke_at: Option<Option<DateTime<Utc>>>,
+    /// Admin override of a transition the state machine forbids
+    pub justification: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+/// Read a present field as `Some`, so an explicit `null` becomes `Some(None)`
+fn explicit_null<'de, D, T>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    Option::<T>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct VkInfo {
     pub id: Uuid,
     pub hash: String,
     pub alias: Option<String>,
+    /// Status now, including scheduled transitions that are due
+    pub status: String,
+    pub deprecation_reason: Option<String>,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub deprecate_at: Option<DateTime<Utc>>,
+    pub revoke_at: Option<DateTime<Utc>>,
+    /// Status at the time a verification asked about with `as_of`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub historical: Option<HistoricalVkStatus>,
+}
+
+/// A VK's status at an earlier time, as recorded in its audit log then
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoricalVkStatus {
+    pub as_of: DateTime<Utc>,
     pub status: String,
     pub deprecation_reason: Option<String>,
 }
 
+impl HistoricalVkStatus {
+    /// Status of `vk` at `as_of`, where `vk` holds the state recorded at that time
+    pub fn new(vk: &VerificationKey, as_of: DateTime<Utc>) -> Self {
+        HistoricalVkStatus {
+            as_of,
+            status: vk.status_at(as_of).as_str().to_string(),
+            deprecation_reason: vk.deprecation_reason.clone(),
+        }
+    }
+}
+
 impl From<&VerificationKey> for VkInfo {
     fn from(vk: &VerificationKey) -> Self {
         VkInfo {
             id: vk.id,
             hash: vk.vk_hash.clone(),
             alias: vk.alias.clone(),
-            status: vk.status.clone(),
+            status: vk.status_at(Utc::now()).as_str().to_string(),
             deprecation_reason: vk.deprecation_reason.clone(),
+            valid_from: vk.valid_from,
+            deprecate_at: vk.deprecate_at,
+            revoke_at: vk.revoke_at,
+            historical: None,
         }
     }
 }
@@ -119,11 +262,15 @@ pub struct VkListItem {
     pub hash: String,
     pub alias: Option<String>,
     pub status: String,
+    pub valid_from: Option<DateTime<Utc>>,
+    pub deprecate_at: Option<DateTime<Utc>>,
+    pub revoke_at: Option<DateTime<Utc>>,
     pub created_at: DateTime<Utc>,
 }
 
 impl From<VerificationKey> for VkListItem {
     fn from(vk: VerificationKey) -> Self {
+        let status = vk.status_at(Utc::now()).as_str().to_string();
         VkListItem {
             id: vk.id,
             prover: vk.prover,
@@ -132,8 +279,105 @@ impl From<VerificationKey> for VkListItem {
             proof_type: vk.proof_type,
             hash: vk.vk_hash,
             alias: vk.alias,
-            status: vk.status,
+            status,
+            valid_from: vk.valid_from,
+            deprecate_at: vk.deprecate_at,
+            revoke_at: vk.revoke_at,
             created_at: vk.created_at,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::VerificationKey;
+    use super::VkStatus::{self, *};
+    use chrono::{DateTime, Duration, Utc};
+    use uuid::Uuid;
+
+    fn vk(
+        status: VkStatus,
+        deprecate_at: Option<DateTime<Utc>>,
+        revoke_at: Option<DateTime<Utc>>,
+    ) -> VerificationKey {
+        VerificationKey {
+            id: Uuid::nil(),
+            prover: "sp1".to_string(),
+            version: "1.0.0".to_string(),
+            proof_system: "groth16".to_string(),
+            proof_type: None,
+            vk_hash: "0x00".to_string(),
+            vk_data: serde_json::Value::Null,
+            alias: None,
+            status: status.as_str().to_string(),
+            deprecation_reason: None,
+            registered_by: None,
+            created_at: Utc::now(),
+            active: true,
+            valid_from: None,
+            deprecate_at,
+            revoke_at,
+        }
+    }
+
+    #[test]
+    fn transitions() {
+        // (from, to, allowed without an override)
+        let table = [
+            (Active, Active, true),
+            (Active, Deprecated, true),
+            (Active, Revoked, true),
+            (Deprecated, Deprecated, true),
+            (Deprecated, Revoked, true),
+            (Deprecated, Active, false),
+            (Revoked, Revoked, true),
+            (Revoked, Active, false),
+            (Revoked, Deprecated, false),
+        ];
+        for (from, to, allowed) in table {
+            assert_eq!(from.can_become(to), allowed, "{from} -> {to}");
+        }
+    }
+
+    #[test]
+    fn scheduled_status() {
+        let now = Utc::now();
+        let past = Some(now - Duration::hours(1));
+        let future = Some(now + Duration::hours(1));
+        // (stored status, deprecate_at, revoke_at, status now)
+        let table = [
+            (Active, None, None, Active),
+            (Active, future, future, Active),
+            (Active, past, None, Deprecated),
+            (Active, past, future, Deprecated),
+            (Active, None, past, Revoked),
+            (Active, past, past, Revoked),
+            (Deprecated, None, None, Deprecated),
+            (Deprecated, None, future, Deprecated),
+            (Deprecated, None, past, Revoked),
+            (Revoked, None, None, Revoked),
+            (Revoked, future, future, Revoked),
+        ];
+        for (status, deprecate_at, revoke_at, expected) in table {
+            assert_eq!(
+                vk(status, deprecate_at, revoke_at).status_at(now),
+                expected,
+                "{status} deprecate_at={deprecate_at:?} revoke_at={revoke_at:?}"
+            );
+        }
+        assert_eq!(
+            vk(Active, past, None).status_at(now - Duration::hours(2)),
+            Active
+        );
+    }
+
+    #[test]
+    fn valid_from() {
+        let now = Utc::now();
+        let mut key = vk(Active, None, None);
+        assert!(key.is_valid_at(now));
+        key.valid_from = Some(now);
+        assert!(key.is_valid_at(now));
+        assert!(!key.is_valid_at(now - Duration::seconds(1)));
+    }
+}
@@ -0,0 +1,207 @@
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use chrono::{DateTime, Utc};
+use prova_merkle::{InclusionProof, Leaf, parse_hash};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::error::ApiError;
+use crate::models::{
+    AnchorConfirmation, AnchorFilter, AnchorResponse, AnchorRevision, ChainInfo,
+};
+use crate::services::{ConfirmationStatus, normalize_proof_hash};
+
+#[derive(Debug, Deserialize)]
+pub struct ListAnchorsParams {
+    pub proof_hash: Option<String>,
+    pub chain: Option<String>,
+    pub vk_hash: Option<String>,
+    /// pending, confirmed, finalized or orphaned
+    pub status: Option<String>,
+    /// Inclusive lower bound on the anchor's `created_at`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on the anchor's `created_at`
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProofAnchorsParams {
+    pub chain: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InclusionResponse {
+    pub anchor_id: Uuid,
+    pub batch: BatchInfo,
+    /// Leaf, sibling path and root; check offline with `prova_merkle::InclusionProof::verify`
+    pub inclusion: InclusionProof,
+    /// Transaction that published the root
+    pub chain: ChainInfo,
+    pub confirmation: AnchorConfirmation,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchInfo {
+    pub id: Uuid,
+    pub leaf_index: i32,
+    pub leaf_count: i32,
+    pub submitted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListAnchorsResponse {
+    pub anchors: Vec<AnchorResponse>,
+    pub total: i64,
+}
+
+/// GET /v1/anchors - List anchors (public)
+pub async fn list_anchors(
+    State(state): State<AppState>,
+    Query(params): Query<ListAnchorsParams>,
+) -> Result<Json<ListAnchorsResponse>, ApiError> {
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+    if let Some(status) = &params.status {
+        status
+            .parse::<ConfirmationStatus>()
+            .map_err(ApiError::BadRequest)?;
+    }
+    let filter = AnchorFilter {
+        proof_hash: params.proof_hash.as_deref().map(normalize_proof_hash),
+        chain: params.chain,
+        vk_hash: params.vk_hash,
+        confirmation_status: params.status,
+        from: params.from,
+        to: params.to,
+    };
+
+    let (anchors, total) = state.anchor_service.list(&filter, limit, offset).await?;
+
+    Ok(Json(ListAnchorsResponse {
+        anchors: anchors
+            .into_iter()
+            .map(|anchor| AnchorResponse::from(state.anchor_service.with_explorer_url(anchor)))
+            .collect(),
+        total,
+    }))
+}
+
+/// GET /v1/anchors/:id - Get anchor by ID (public)
+pub async fn get_anchor(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<AnchorResponse>, ApiError> {
+    let anchor = state
+        .anchor_service
+        .get_by_id(id)
+        .await?
+        .ok_or(ApiError::AnchorNotFound)?;
+
+    Ok(Json(AnchorResponse::from(
+        state.anchor_service.with_explorer_url(anchor),
+    )))
+}
+
+/// GET /v1/anchors/:id/history - Every recorded state of an anchor, oldest first (public)
+pub async fn get_anchor_history(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<AnchorRevision>>, ApiError> {
+    state
+        .anchor_service
+        .get_by_id(id)
+        .await?
+        .ok_or(ApiError::AnchorNotFound)?;
+
+    Ok(Json(state.anchor_service.history(id).await?))
+}
+
+/// GET /v1/proofs/:hash/anchors - Anchors of a proof across chains (public)
+pub async fn list_proof_anchors(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    Query(params): Query<ProofAnchorsParams>,
+) -> Result<Json<Vec<AnchorResponse>>, ApiError> {
+    let proof_hash = normalize_proof_hash(&hash);
+
+    let anchors = match params.chain.as_deref() {
+        Some(chain) => state
+            .anchor_service
+            .get_by_proof_hash_and_chain(&proof_hash, chain)
+            .await?
+            .into_iter()
+            .collect(),
+        None => state.anchor_service.get_by_proof_hash(&proof_hash).await?,
+    };
+
+    Ok(Json(
+        anchors
+            .into_iter()
+            .map(|anchor| AnchorResponse::from(state.anchor_service.with_explorer_url(anchor)))
+            .collect(),
+    ))
+}
+
+/// GET /v1/proofs/:hash/inclusion - Merkle inclusion proof of a batch-anchored proof (public)
+pub async fn get_proof_inclusion(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    Query(params): Query<ProofAnchorsParams>,
+) -> Result<Json<InclusionResponse>, ApiError> {
+    let proof_hash = normalize_proof_hash(&hash);
+
+    let row = state
+        .anchor_service
+        .inclusion(&proof_hash, params.chain.as_deref())
+        .await?
+        .ok_or(ApiError::InclusionProofNotFound)?;
+
+    let leaf = Leaf::new(&row.proof_hash, &row.vk_hash, row.valid);
+    let root = parse_hash(&row.merkle_root);
+    let inclusion = match (leaf, root) {
+        (Ok(leaf), Ok(root)) => InclusionProof::new(&leaf, row.merkle_path.0, root),
+        (Err(e), _) | (_, Err(e)) => {
+            tracing::error!(batch_id = %row.batch_id, error = %e, "Stored batch leaf is malformed");
+            return Err(ApiError::Internal);
+        }
+    };
+    // Never serve a path that does not lead to the published root
+    if let Err(e) = inclusion.verify() {
+        tracing::error!(batch_id = %row.batch_id, proof_hash = %proof_hash, error = %e, "Stored inclusion path is inconsistent");
+        return Err(ApiError::Internal);
+    }
+
+    let explorer_url = row.explorer_url.or_else(|| {
+        row.tx_hash
+            .as_deref()
+            .and_then(|tx_hash| state.anchor_service.explorer_url(&row.chain, tx_hash))
+    });
+
+    Ok(Json(InclusionResponse {
+        anchor_id: row.anchor_id,
+        batch: BatchInfo {
+            id: row.batch_id,
+            leaf_index: row.leaf_index,
+            leaf_count: row.leaf_count,
+            submitted_at: row.submitted_at,
+        },
+        inclusion,
+        chain: ChainInfo {
+            name: row.chain,
+            block_number: row.block_number,
+            block_hash: row.block_hash,
+            block_timestamp: row.block_timestamp,
+            tx_hash: row.tx_hash,
+            explorer_url,
+        },
+        confirmation: AnchorConfirmation {
+            status: row.confirmation_status,
+            confirmations: row.confirmations,
+        },
+    }))
+}
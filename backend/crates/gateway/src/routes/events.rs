@@ -0,0 +1,46 @@
+use axum::{
+    extract::{Query, State},
+    http::HeaderMap,
+    response::sse::{Event, KeepAlive, Sse},
+};
+use serde::Deserialize;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+
+use crate::AppState;
+use crate::error::ApiError;
+
+#[derive(Debug, Deserialize)]
+pub struct EventStreamParams {
+    /// Only events of this prover
+    pub prover: Option<String>,
+}
+
+/// GET /v1/events - Server-sent stream of VK registry changes and new anchors (public).
+/// Reconnecting clients send `Last-Event-ID` and receive what they missed first.
+pub async fn stream_events(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(params): Query<EventStreamParams>,
+) -> Result<Sse<impl Stream<Item = Result<Event, axum::Error>>>, ApiError> {
+    let last_event_id = headers
+        .get("last-event-id")
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|id| id.trim().parse::<i64>().ok())
+                .ok_or_else(|| ApiError::BadRequest("Last-Event-ID must be an event id".to_string()))
+        })
+        .transpose()?;
+
+    let events = state.registry_events.stream(last_event_id, params.prover);
+    let stream = ReceiverStream::new(events).map(|event| {
+        Event::default()
+            .id(event.id.to_string())
+            .event(&event.event_type)
+            .json_data(event.as_ref())
+    });
+
+    Ok(Sse::new(stream).keep_alive(KeepAlive::default()))
+}
@@ -1,4 +1,8 @@
-use axum::{Json, extract::State, http::HeaderMap};
+use axum::{
+    Json,
+    extract::{Path, State},
+    http::HeaderMap,
+};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -6,7 +10,7 @@ use uuid::Uuid;
 
 use crate::AppState;
 use crate::error::ApiError;
-use crate::models::{AnchorResponse, CreateAnchor};
+use crate::models::{AnchorResponse, CreateAnchor, UpdateAnchor};
 
 #[derive(Debug, Deserialize)]
 pub struct ProvisionApiKeyRequest {
@@ -133,6 +137,20 @@ pub struct CreateAnchorRequest {
     pub block_timestamp: Option<String>,
     pub tx_hash: Option<String>,
     pub explorer_url: Option<String>,
+    /// Depth of the anchor's block, counting the block itself
+    pub confirmations: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateAnchorRequest {
+    /// pending, confirmed, finalized or orphaned; derived from `confirmations` when absent
+    pub status: Option<String>,
+    pub confirmations: Option<i64>,
+    /// New block data when the anchor was re-included after a reorg
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub block_timestamp: Option<String>,
+    pub tx_hash: Option<String>,
 }
 
 /// POST /internal/anchor - Create anchor record (chain clients only)
@@ -172,9 +190,57 @@ pub async fn create_anchor(
         block_timestamp,
         tx_hash: request.tx_hash,
         explorer_url: request.explorer_url,
+        batch_id: None,
+        confirmations: request.confirmations,
     };
 
     let anchor = state.anchor_service.create_anchor(create_anchor).await?;
 
-    Ok(Json(AnchorResponse::from(anchor)))
+    Ok(Json(AnchorResponse::from(
+        state.anchor_service.with_explorer_url(anchor),
+    )))
+}
+
+/// PATCH /internal/anchor/:id - Report confirmations or a reorg (chain clients only)
+pub async fn update_anchor(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(id): Path<Uuid>,
+    Json(request): Json<UpdateAnchorRequest>,
+) -> Result<Json<AnchorResponse>, ApiError> {
+    let internal_secret = std::env::var("INTERNAL_API_SECRET").map_err(|_| ApiError::Internal)?;
+
+    let provided_secret = headers
+        .get("X-Internal-Secret")
+        .and_then(|h| h.to_str().ok())
+        .ok_or(ApiError::Unauthorized)?;
+
+    if provided_secret != internal_secret {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let block_timestamp = request
+        .block_timestamp
+        .as_deref()
+        .map(|ts| {
+            DateTime::parse_from_rfc3339(ts)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| ApiError::BadRequest("block_timestamp must be RFC 3339".to_string()))
+        })
+        .transpose()?;
+
+    let update = UpdateAnchor {
+        status: request.status,
+        confirmations: request.confirmations,
+        block_number: request.block_number,
+        block_hash: request.block_hash,
+        block_timestamp,
+        tx_hash: request.tx_hash,
+    };
+
+    let anchor = state.anchor_service.update_confirmation(id, update).await?;
+
+    Ok(Json(AnchorResponse::from(
+        state.anchor_service.with_explorer_url(anchor),
+    )))
 }
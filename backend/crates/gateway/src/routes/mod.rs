@@ -1,16 +1,28 @@
+pub mod anchors;
+pub mod events;
 pub mod internal;
+pub mod proofs;
 pub mod provers;
+pub mod reverification;
+pub mod verifications;
 pub mod verify;
 pub mod vk;
+pub mod webhooks;
 
+pub use anchors::*;
+pub use events::*;
 pub use internal::*;
+pub use proofs::*;
 pub use provers::*;
+pub use reverification::*;
+pub use verifications::*;
 pub use verify::*;
 pub use vk::*;
+pub use webhooks::*;
 
 use axum::{
     Json, Router, middleware as axum_middleware,
-    routing::{get, post},
+    routing::{get, patch, post},
 };
 use serde_json::{Value, json};
 use tower_http::cors::CorsLayer;
@@ -32,16 +44,50 @@ pub fn create_router(state: AppState, cors: CorsLayer) -> Router {
         .route("/health", get(health))
         // Prover endpoints
         .route("/v1/provers", get(list_provers))
-        .route("/v1/provers/:prover/versions", get(list_versions))
-        .route("/v1/provers/:prover/:version", get(get_version))
+        .route("/v1/provers/{prover}/versions", get(list_versions))
+        .route("/v1/provers/{prover}/{version}", get(get_version))
         // Verification key endpoints
         .route("/v1/vks", get(list_vks).post(create_vk))
-        .route("/v1/vks/:id", get(get_vk).patch(update_vk))
+        .route("/v1/vks/{id}", get(get_vk).patch(update_vk))
+        .route("/v1/vks/{id}/history", get(get_vk_history))
         // Verification endpoints
         .route("/v1/verify", post(verify))
+        .route("/v1/verify/batch", post(verify_batch))
+        .route("/v1/verify/upload", post(verify_upload))
+        .route("/v1/verifications", get(list_verifications))
+        // Stored proofs
+        .route("/v1/proofs/{hash}", get(get_proof))
+        .route("/v1/proofs/{hash}/raw", get(get_raw_proof))
+        .route("/v1/proofs/{hash}/anchors", get(list_proof_anchors))
+        .route("/v1/proofs/{hash}/inclusion", get(get_proof_inclusion))
+        .route(
+            "/v1/users/{id}/proof-retention",
+            get(get_proof_retention).put(update_proof_retention),
+        )
+        // Anchors
+        .route("/v1/anchors", get(list_anchors))
+        .route("/v1/anchors/{id}", get(get_anchor))
+        .route("/v1/anchors/{id}/history", get(get_anchor_history))
+        // Registry event stream
+        .route("/v1/events", get(stream_events))
+        // Webhooks
+        .route("/v1/webhooks", get(list_webhooks).post(create_webhook))
+        .route("/v1/webhooks/{id}", get(get_webhook).delete(delete_webhook))
+        .route("/v1/webhooks/{id}/deliveries", get(list_webhook_deliveries))
+        // Re-verification jobs
+        .route(
+            "/v1/reverifications",
+            get(list_reverifications).post(create_reverification),
+        )
+        .route("/v1/reverifications/{id}", get(get_reverification))
+        .route(
+            "/v1/reverifications/{id}/discrepancies",
+            get(get_reverification_discrepancies),
+        )
         // == Internal endpoints
         .route("/internal/api-keys/provision", post(provision_api_key))
         .route("/internal/anchor", post(create_anchor))
+        .route("/internal/anchor/{id}", patch(update_anchor))
         // == Middleware
         .layer(axum_middleware::from_fn_with_state(
             state.clone(),
@@ -0,0 +1,187 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Extension, Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::error::ApiError;
+use crate::middleware::AuthenticatedUser;
+use crate::models::{AnchorResponse, VerificationOutcome, VkInfo};
+use crate::services::{ProofPart, ProofStore, normalize_proof_hash};
+
+#[derive(Debug, Deserialize)]
+pub struct RawProofQuery {
+    /// `proof` (default) or `public_inputs`
+    pub part: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRetentionRequest {
+    /// Days to keep the user's proofs; null restores the default, 0 disables storage
+    pub days: Option<u32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RetentionResponse {
+    pub user_id: Uuid,
+    pub retention_days: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProofDetailResponse {
+    pub proof_hash: String,
+    /// Outcome of the most recent verification
+    pub valid: Option<bool>,
+    /// One entry per prover version, verifier version and VK the proof was verified with
+    pub verifications: Vec<ProofVerification>,
+    pub anchors: Vec<AnchorResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProofVerification {
+    #[serde(flatten)]
+    pub outcome: VerificationOutcome,
+    /// The VK used, with its current status
+    pub vk: Option<VkInfo>,
+}
+
+fn proof_store(state: &AppState) -> Result<&ProofStore, ApiError> {
+    state
+        .proof_store
+        .as_ref()
+        .ok_or(ApiError::ProofStorageDisabled)
+}
+
+/// GET /v1/proofs/:hash - Everything known about a proof: verification outcomes and anchors (public)
+pub async fn get_proof(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+) -> Result<Json<ProofDetailResponse>, ApiError> {
+    let proof_hash = normalize_proof_hash(&hash);
+
+    let outcomes = state
+        .verification_history
+        .outcomes_for_proof(&proof_hash)
+        .await?;
+    let anchors = state.anchor_service.get_by_proof_hash(&proof_hash).await?;
+    if outcomes.is_empty() && anchors.is_empty() {
+        return Err(ApiError::ProofNotFound);
+    }
+
+    let mut vks = HashMap::new();
+    for vk_id in outcomes.iter().filter_map(|o| o.vk_id) {
+        if !vks.contains_key(&vk_id)
+            && let Some(vk) = state.vk_service.get_vk(&vk_id.to_string(), None).await?
+        {
+            vks.insert(vk_id, VkInfo::from(&vk));
+        }
+    }
+
+    let valid = outcomes.first().map(|o| o.valid);
+    let verifications = outcomes
+        .into_iter()
+        .map(|outcome| ProofVerification {
+            vk: outcome.vk_id.and_then(|id| vks.get(&id).cloned()),
+            outcome,
+        })
+        .collect();
+    let anchors = anchors
+        .into_iter()
+        .map(|anchor| AnchorResponse::from(state.anchor_service.with_explorer_url(anchor)))
+        .collect();
+
+    Ok(Json(ProofDetailResponse {
+        proof_hash,
+        valid,
+        verifications,
+        anchors,
+    }))
+}
+
+/// GET /v1/proofs/:hash/raw - Download a stored proof (or its public inputs)
+pub async fn get_raw_proof(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(hash): Path<String>,
+    Query(query): Query<RawProofQuery>,
+) -> Result<Response, ApiError> {
+    let proof_hash = normalize_proof_hash(&hash);
+    let part = match query.part.as_deref() {
+        None | Some("proof") => ProofPart::Proof,
+        Some("public_inputs") => ProofPart::PublicInputs,
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown part '{}', expected proof or public_inputs",
+                other
+            )));
+        }
+    };
+
+    let download = proof_store(&state)?
+        .open(&proof_hash, part, user.user_id, user.is_admin())
+        .await?
+        .ok_or(ApiError::ProofNotFound)?;
+
+    let (content_type, filename) = match part {
+        ProofPart::Proof => ("application/octet-stream", format!("{}.bin", proof_hash)),
+        ProofPart::PublicInputs => ("application/json", format!("{}.inputs.json", proof_hash)),
+    };
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::CONTENT_LENGTH, download.size.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        Body::from_stream(download.stream),
+    )
+        .into_response())
+}
+
+/// GET /v1/users/:id/proof-retention - Retention for a user (the user or an admin)
+pub async fn get_proof_retention(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(user_id): Path<Uuid>,
+) -> Result<Json<RetentionResponse>, ApiError> {
+    if user.user_id != user_id && !user.is_admin() {
+        return Err(ApiError::Forbidden);
+    }
+
+    let retention_days = proof_store(&state)?.retention_for(user_id).await?;
+
+    Ok(Json(RetentionResponse {
+        user_id,
+        retention_days,
+    }))
+}
+
+/// PUT /v1/users/:id/proof-retention - Set a user's retention (admin only)
+pub async fn update_proof_retention(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(user_id): Path<Uuid>,
+    Json(request): Json<UpdateRetentionRequest>,
+) -> Result<Json<RetentionResponse>, ApiError> {
+    if !user.is_admin() {
+        return Err(ApiError::Forbidden);
+    }
+
+    let retention_days = proof_store(&state)?
+        .set_retention(user_id, request.days)
+        .await?;
+
+    Ok(Json(RetentionResponse {
+        user_id,
+        retention_days,
+    }))
+}
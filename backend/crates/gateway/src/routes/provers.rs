@@ -48,18 +48,23 @@ pub struct VersionDetailResponse {
     pub registered_at: String,
 }
 
-pub async fn list_provers(
-    State(_state): State<AppState>,
-) -> Result<Json<ListProversResponse>, ApiError> {
+/// Provers the gateway can verify proofs for
+pub fn supported_provers() -> Vec<ProverInfo> {
     // Currently only Zisk is supported
-    let provers = vec![
+    vec![
         ProverInfo {
             name: "zisk".to_string(),
             proof_systems: vec!["stark".to_string()],
         },
-    ];
+    ]
+}
 
-    Ok(Json(ListProversResponse { provers }))
+pub async fn list_provers(
+    State(_state): State<AppState>,
+) -> Result<Json<ListProversResponse>, ApiError> {
+    Ok(Json(ListProversResponse {
+        provers: supported_provers(),
+    }))
 }
 
 #[derive(FromRow)]
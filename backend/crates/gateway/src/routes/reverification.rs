@@ -0,0 +1,79 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, State},
+    http::StatusCode,
+};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::error::ApiError;
+use crate::middleware::AuthenticatedUser;
+use crate::models::{CreateReverificationJob, ReverificationJob, ReverificationResult};
+
+fn require_admin(user: &AuthenticatedUser) -> Result<(), ApiError> {
+    if user.is_admin() {
+        Ok(())
+    } else {
+        Err(ApiError::Forbidden)
+    }
+}
+
+/// POST /v1/reverifications - Re-verify proofs affected by a VK or verifier change (admin only)
+pub async fn create_reverification(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateReverificationJob>,
+) -> Result<(StatusCode, Json<ReverificationJob>), ApiError> {
+    require_admin(&user)?;
+
+    let job = state
+        .reverification_service
+        .start(user.user_id, request)
+        .await?;
+
+    Ok((StatusCode::ACCEPTED, Json(job)))
+}
+
+/// GET /v1/reverifications - Recent re-verification jobs (admin only)
+pub async fn list_reverifications(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<ReverificationJob>>, ApiError> {
+    require_admin(&user)?;
+
+    Ok(Json(state.reverification_service.list_jobs().await?))
+}
+
+/// GET /v1/reverifications/:id - Progress and counts of a job (admin only)
+pub async fn get_reverification(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<ReverificationJob>, ApiError> {
+    require_admin(&user)?;
+
+    let job = state
+        .reverification_service
+        .get_job(id)
+        .await?
+        .ok_or(ApiError::JobNotFound)?;
+
+    Ok(Json(job))
+}
+
+/// GET /v1/reverifications/:id/discrepancies - Proofs that were valid and no longer are
+pub async fn get_reverification_discrepancies(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<Vec<ReverificationResult>>, ApiError> {
+    require_admin(&user)?;
+
+    state
+        .reverification_service
+        .get_job(id)
+        .await?
+        .ok_or(ApiError::JobNotFound)?;
+
+    Ok(Json(state.reverification_service.discrepancies(id).await?))
+}
@@ -0,0 +1,231 @@
+use axum::{
+    Json,
+    body::Body,
+    extract::{Extension, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+};
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::error::ApiError;
+use crate::middleware::AuthenticatedUser;
+use crate::models::VerificationRecord;
+use crate::services::{VerificationCursor, VerificationFilter, VerificationHistory};
+
+/// Rows fetched per query while exporting
+const EXPORT_BATCH_SIZE: i64 = 1000;
+
+const CSV_HEADER: &str = "id,proof_hash,public_inputs_hash,prover,proof_system,prover_version,\
+    verifier_version,vk_id,vk_hash,valid,error,reverification_job_id,verified_at,as_of\n";
+
+#[derive(Debug, Deserialize)]
+pub struct ListVerificationsParams {
+    pub prover: Option<String>,
+    pub vk_hash: Option<String>,
+    pub valid: Option<bool>,
+    /// Inclusive lower bound on `verified_at`
+    pub from: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `verified_at`
+    pub to: Option<DateTime<Utc>>,
+    /// `next_cursor` of the previous page
+    pub cursor: Option<String>,
+    pub limit: Option<i64>,
+    /// `json` (default), `csv` or `ndjson`; exports return every matching record
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListVerificationsResponse {
+    pub verifications: Vec<VerificationRecord>,
+    /// Pass as `cursor` to get the next page; absent on the last page
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ExportFormat {
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    fn content_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    fn line(self, record: &VerificationRecord) -> String {
+        match self {
+            ExportFormat::Csv => csv_line(record),
+            ExportFormat::Ndjson => {
+                let mut line = serde_json::to_string(record).unwrap_or_default();
+                line.push('\n');
+                line
+            }
+        }
+    }
+}
+
+/// GET /v1/verifications - The caller's verification history, newest first
+pub async fn list_verifications(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(params): Query<ListVerificationsParams>,
+) -> Result<Response, ApiError> {
+    let format = match params.format.as_deref() {
+        None | Some("json") => None,
+        Some("csv") => Some(ExportFormat::Csv),
+        Some("ndjson") => Some(ExportFormat::Ndjson),
+        Some(other) => {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown format '{}', expected json, csv or ndjson",
+                other
+            )));
+        }
+    };
+    let cursor = params
+        .cursor
+        .as_deref()
+        .map(VerificationCursor::decode)
+        .transpose()?;
+    let filter = VerificationFilter {
+        prover: params.prover,
+        vk_hash: params.vk_hash,
+        valid: params.valid,
+        from: params.from,
+        to: params.to,
+    };
+
+    if let Some(format) = format {
+        return Ok(export(
+            state.verification_history.clone(),
+            user.user_id,
+            filter,
+            cursor,
+            format,
+        ));
+    }
+
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+    let verifications = state
+        .verification_history
+        .list_for_user(user.user_id, &filter, cursor, limit)
+        .await?;
+    let next_cursor = match verifications.last() {
+        Some(last) if verifications.len() as i64 == limit => {
+            Some(VerificationCursor::after(last).encode())
+        }
+        _ => None,
+    };
+
+    Ok(Json(ListVerificationsResponse {
+        verifications,
+        next_cursor,
+    })
+    .into_response())
+}
+
+/// Stream every matching record, fetching them in batches
+fn export(
+    history: VerificationHistory,
+    user_id: Uuid,
+    filter: VerificationFilter,
+    mut cursor: Option<VerificationCursor>,
+    format: ExportFormat,
+) -> Response {
+    let (tx, rx) = mpsc::channel::<Result<Bytes, ApiError>>(4);
+
+    tokio::spawn(async move {
+        if let ExportFormat::Csv = format
+            && tx.send(Ok(Bytes::from_static(CSV_HEADER.as_bytes()))).await.is_err()
+        {
+            return;
+        }
+
+        loop {
+            let batch = match history
+                .list_for_user(user_id, &filter, cursor, EXPORT_BATCH_SIZE)
+                .await
+            {
+                Ok(batch) => batch,
+                Err(e) => {
+                    tracing::error!(user_id = %user_id, error = %e, "Verification export failed");
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            };
+            let Some(last) = batch.last() else {
+                return;
+            };
+            cursor = Some(VerificationCursor::after(last));
+
+            let chunk: String = batch.iter().map(|record| format.line(record)).collect();
+            if tx.send(Ok(Bytes::from(chunk))).await.is_err() {
+                return;
+            }
+            if (batch.len() as i64) < EXPORT_BATCH_SIZE {
+                return;
+            }
+        }
+    });
+
+    (
+        [
+            (header::CONTENT_TYPE, format.content_type().to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"verifications.{}\"", format.extension()),
+            ),
+        ],
+        Body::from_stream(ReceiverStream::new(rx)),
+    )
+        .into_response()
+}
+
+fn csv_line(record: &VerificationRecord) -> String {
+    let optional = |value: Option<String>| value.unwrap_or_default();
+    let fields = [
+        record.id.to_string(),
+        record.proof_hash.clone(),
+        optional(record.public_inputs_hash.clone()),
+        record.prover.clone(),
+        record.proof_system.clone(),
+        record.prover_version.clone(),
+        optional(record.verifier_version.clone()),
+        optional(record.vk_id.map(|id| id.to_string())),
+        optional(record.vk_hash.clone()),
+        record.valid.to_string(),
+        optional(record.error.clone()),
+        optional(record.reverification_job_id.map(|id| id.to_string())),
+        record.verified_at.to_rfc3339(),
+        optional(record.as_of.map(|at| at.to_rfc3339())),
+    ];
+
+    let mut line = fields.map(|f| csv_field(&f)).join(",");
+    line.push('\n');
+    line
+}
+
+/// Quote a field when it contains separators, quotes or line breaks
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
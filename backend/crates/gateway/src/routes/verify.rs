@@ -1,10 +1,17 @@
-use axum::{extract::State, Json};
-use chrono::Utc;
+use axum::{
+    body::Body,
+    extract::{Extension, Query, State},
+    http::HeaderMap,
+    Json,
+};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use tokio_stream::StreamExt;
 
 use crate::error::ApiError;
-use crate::models::VkInfo;
-use crate::services::{generate_proof_hash, hash_public_inputs};
+use crate::middleware::{AuthenticatedUser, request_key};
+use crate::models::{BatchItem, VerificationResult};
+use crate::services::{ProofSubmission, StreamSubmission, batch_cost};
 use crate::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -15,90 +22,136 @@ pub struct VerifyRequest {
     pub proof_system: Option<String>,
     /// VK identifier: can be a hash (0x...) or a prover-defined alias
     pub vk_id: Option<String>,
+    /// Judge the VK by its status at this time, e.g. when the proof was produced
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+impl VerifyRequest {
+    fn into_submission(self) -> Result<ProofSubmission, ApiError> {
+        let proof = serde_json::to_vec(&self.proof)
+            .map_err(|e| ApiError::BadRequest(format!("Invalid proof format: {}", e)))?;
+
+        Ok(ProofSubmission {
+            proof,
+            public_inputs: self.public_inputs,
+            prover: self.prover,
+            proof_system: self.proof_system,
+            vk_id: self.vk_id,
+            as_of: self.as_of,
+        })
+    }
+}
+
+/// Query parameters of the raw proof upload
+#[derive(Debug, Default)]
+pub struct VerifyUploadParams {
+    /// One per `public_inputs` parameter, in order, so inputs may contain commas
+    pub public_inputs: Vec<String>,
+    pub prover: Option<String>,
+    pub proof_system: Option<String>,
+    pub vk_id: Option<String>,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+impl VerifyUploadParams {
+    fn from_pairs(pairs: Vec<(String, String)>) -> Result<Self, ApiError> {
+        let mut params = Self::default();
+        for (key, value) in pairs {
+            match key.as_str() {
+                "public_inputs" => params.public_inputs.push(value),
+                "prover" => params.prover = Some(value),
+                "proof_system" => params.proof_system = Some(value),
+                "vk_id" => params.vk_id = Some(value),
+                "as_of" => {
+                    let as_of = DateTime::parse_from_rfc3339(&value)
+                        .map_err(|e| ApiError::BadRequest(format!("Invalid as_of: {}", e)))?;
+                    params.as_of = Some(as_of.with_timezone(&Utc));
+                }
+                _ => {}
+            }
+        }
+        Ok(params)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct VerifyBatchRequest {
+    pub requests: Vec<VerifyRequest>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct VerifyResponse {
-    pub valid: bool,
-    pub prover: String,
-    pub proof_system: String,
-    pub proof_type: Option<String>,
-    pub prover_version: String,
-    pub proof_hash: String,
-    pub public_inputs_hash: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub vk: Option<VkInfo>,
-    pub verified_at: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub error: Option<String>,
+pub struct VerifyBatchResponse {
+    pub results: Vec<BatchItem>,
 }
 
 pub async fn verify(
     State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
     Json(request): Json<VerifyRequest>,
-) -> Result<Json<VerifyResponse>, ApiError> {
-    let prover = request.prover.clone().unwrap_or_else(|| "zisk".to_string());
-    let proof_system = request.proof_system.clone().unwrap_or_else(|| "zisk".to_string());
-
-    // Currently only zisk is supported
-    if prover != "zisk" {
-        return Err(ApiError::UnsupportedProver(prover));
-    }
-
-    // Resolve VK if vk_id is provided
-    let vk = if let Some(vk_id) = &request.vk_id {
-        let resolved = state
-            .vk_service
-            .resolve_vk(&prover, vk_id)
-            .await?;
+) -> Result<Json<VerificationResult>, ApiError> {
+    let result = state
+        .verification_service
+        .verify(user.user_id, request.into_submission()?)
+        .await?;
 
-        if resolved.is_none() {
-            return Err(ApiError::VkNotFound);
-        }
+    Ok(Json(result))
+}
 
-        let vk = resolved.unwrap();
+/// POST /v1/verify/batch - Verify several proofs in one request
+pub async fn verify_batch(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    headers: HeaderMap,
+    Json(request): Json<VerifyBatchRequest>,
+) -> Result<Json<VerifyBatchResponse>, ApiError> {
+    // Every proof counts as a request; the middleware already charged the first
+    let extra = batch_cost(request.requests.len()) - 1;
+    if !state.rate_limiter.check_cost(&request_key(&headers), extra) {
+        return Err(ApiError::RateLimitExceeded);
+    }
 
-        // Check if VK is revoked
-        if vk.is_revoked() {
-            return Err(ApiError::BadRequest(format!(
-                "Verification key is revoked: {}",
-                vk.deprecation_reason.as_deref().unwrap_or("unknown reason")
-            )));
-        }
+    let submissions = request
+        .requests
+        .into_iter()
+        .map(VerifyRequest::into_submission)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let results = state
+        .verification_service
+        .verify_batch(user.user_id, submissions)
+        .await?
+        .into_iter()
+        .map(BatchItem::from)
+        .collect();
+
+    Ok(Json(VerifyBatchResponse { results }))
+}
 
-        Some(vk)
-    } else {
-        None
+/// POST /v1/verify/upload - Verify a raw proof body, streamed through to the verifier
+/// without buffering it in the gateway
+pub async fn verify_upload(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Query(pairs): Query<Vec<(String, String)>>,
+    body: Body,
+) -> Result<Json<VerificationResult>, ApiError> {
+    let params = VerifyUploadParams::from_pairs(pairs)?;
+    let submission = StreamSubmission {
+        public_inputs: Some(params.public_inputs).filter(|inputs| !inputs.is_empty()),
+        prover: params.prover,
+        proof_system: params.proof_system,
+        vk_id: params.vk_id,
+        as_of: params.as_of,
     };
 
-    let proof_bytes = serde_json::to_vec(&request.proof)
-        .map_err(|e| ApiError::BadRequest(format!("Invalid proof format: {}", e)))?;
+    let chunks = body.into_data_stream().map(|chunk| {
+        chunk.map_err(|e| ApiError::BadRequest(format!("Failed to read proof upload: {}", e)))
+    });
 
-    let verify_result = state
-        .verifier_client
-        .verify_zisk(proof_bytes, request.public_inputs.clone())
+    let result = state
+        .verification_service
+        .verify_stream(user.user_id, submission, chunks)
         .await?;
 
-    let verified_at = Utc::now();
-    let proof_hash = generate_proof_hash(&request.proof, &request.public_inputs);
-    let public_inputs_hash = hash_public_inputs(&request.public_inputs);
-
-    // Get proof_type from VK if available
-    let proof_type = vk.as_ref().and_then(|v| v.proof_type.clone());
-
-    // Build VK info for response
-    let vk_info = vk.as_ref().map(VkInfo::from);
-
-    Ok(Json(VerifyResponse {
-        valid: verify_result.valid,
-        prover,
-        proof_system,
-        proof_type,
-        prover_version: verify_result.prover_version,
-        proof_hash,
-        public_inputs_hash,
-        vk: vk_info,
-        verified_at: verified_at.to_rfc3339(),
-        error: verify_result.error,
-    }))
+    Ok(Json(result))
 }
@@ -2,11 +2,14 @@ use axum::{
     extract::{Extension, Path, Query, State},
     Json,
 };
+use chrono::Utc;
 use serde::{Deserialize, Serialize};
 
 use crate::error::ApiError;
 use crate::middleware::AuthenticatedUser;
-use crate::models::{CreateVerificationKey, UpdateVerificationKey, VkListItem};
+use crate::models::{
+    CreateVerificationKey, UpdateVerificationKey, VerificationKey, VkEvent, VkListItem,
+};
 use crate::AppState;
 
 #[derive(Debug, Deserialize)]
@@ -34,15 +37,44 @@ pub struct VkDetailResponse {
     pub alias: Option<String>,
     pub status: String,
     pub deprecation_reason: Option<String>,
+    pub valid_from: Option<String>,
+    pub deprecate_at: Option<String>,
+    pub revoke_at: Option<String>,
     pub created_at: String,
 }
 
+impl From<VerificationKey> for VkDetailResponse {
+    fn from(vk: VerificationKey) -> Self {
+        let status = vk.status_at(Utc::now()).as_str().to_string();
+        VkDetailResponse {
+            id: vk.id.to_string(),
+            prover: vk.prover,
+            version: vk.version,
+            proof_system: vk.proof_system,
+            proof_type: vk.proof_type,
+            hash: vk.vk_hash,
+            alias: vk.alias,
+            status,
+            deprecation_reason: vk.deprecation_reason,
+            valid_from: vk.valid_from.map(|t| t.to_rfc3339()),
+            deprecate_at: vk.deprecate_at.map(|t| t.to_rfc3339()),
+            revoke_at: vk.revoke_at.map(|t| t.to_rfc3339()),
+            created_at: vk.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Page size for VK listings: 50 by default, at most 100
+pub fn vk_page_limit(limit: Option<i64>) -> i64 {
+    limit.unwrap_or(50).min(100)
+}
+
 /// GET /v1/vks - List verification keys (public)
 pub async fn list_vks(
     State(state): State<AppState>,
     Query(params): Query<ListVksParams>,
 ) -> Result<Json<ListVksResponse>, ApiError> {
-    let limit = params.limit.unwrap_or(50).min(100);
+    let limit = vk_page_limit(params.limit);
     let offset = params.offset.unwrap_or(0);
 
     let (vks, total) = state
@@ -72,18 +104,31 @@ pub async fn get_vk(
         .await?
         .ok_or(ApiError::VkNotFound)?;
 
-    Ok(Json(VkDetailResponse {
-        id: vk.id.to_string(),
-        prover: vk.prover,
-        version: vk.version,
-        proof_system: vk.proof_system,
-        proof_type: vk.proof_type,
-        hash: vk.vk_hash,
-        alias: vk.alias,
-        status: vk.status,
-        deprecation_reason: vk.deprecation_reason,
-        created_at: vk.created_at.to_rfc3339(),
-    }))
+    Ok(Json(VkDetailResponse::from(vk)))
+}
+
+/// GET /v1/vks/:id/history - How a VK changed, oldest first; who and why for its managers
+pub async fn get_vk_history(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<String>,
+    Query(params): Query<ListVksParams>,
+) -> Result<Json<Vec<VkEvent>>, ApiError> {
+    let vk = state
+        .vk_service
+        .get_vk(&id, params.prover.as_deref())
+        .await?
+        .ok_or(ApiError::VkNotFound)?;
+
+    let mut events = state.vk_service.history(vk.id).await?;
+    // Who made a change and why an admin overrode it are for the prover's managers
+    if !user.can_manage_vk(&vk.prover) {
+        for event in &mut events {
+            event.actor = None;
+            event.justification = None;
+        }
+    }
+    Ok(Json(events))
 }
 
 /// POST /v1/vks - Create verification key (admin/prover_manager only)
@@ -103,26 +148,16 @@ pub async fn create_vk(
         .await
         .map_err(|e| {
             // Check for unique constraint violation
-            if let ApiError::Database(ref db_err) = e {
-                if db_err.to_string().contains("duplicate key") || db_err.to_string().contains("unique constraint") {
-                    return ApiError::VkAlreadyExists;
-                }
+            if let ApiError::Database(ref db_err) = e
+                && (db_err.to_string().contains("duplicate key")
+                    || db_err.to_string().contains("unique constraint"))
+            {
+                return ApiError::VkAlreadyExists;
             }
             e
         })?;
 
-    Ok(Json(VkDetailResponse {
-        id: vk.id.to_string(),
-        prover: vk.prover,
-        version: vk.version,
-        proof_system: vk.proof_system,
-        proof_type: vk.proof_type,
-        hash: vk.vk_hash,
-        alias: vk.alias,
-        status: vk.status,
-        deprecation_reason: vk.deprecation_reason,
-        created_at: vk.created_at.to_rfc3339(),
-    }))
+    Ok(Json(VkDetailResponse::from(vk)))
 }
 
 /// PATCH /v1/vks/:id - Update verification key (admin/prover_manager only)
@@ -143,22 +178,15 @@ pub async fn update_vk(
     if !user.can_manage_vk(&existing_vk.prover) {
         return Err(ApiError::Forbidden);
     }
+    // Only admins can override the status state machine
+    if request.justification.is_some() && !user.is_admin() {
+        return Err(ApiError::Forbidden);
+    }
 
     let vk = state
         .vk_service
-        .update_vk(existing_vk.id, request)
+        .update_vk(existing_vk.id, request, user.user_id)
         .await?;
 
-    Ok(Json(VkDetailResponse {
-        id: vk.id.to_string(),
-        prover: vk.prover,
-        version: vk.version,
-        proof_system: vk.proof_system,
-        proof_type: vk.proof_type,
-        hash: vk.vk_hash,
-        alias: vk.alias,
-        status: vk.status,
-        deprecation_reason: vk.deprecation_reason,
-        created_at: vk.created_at.to_rfc3339(),
-    }))
+    Ok(Json(VkDetailResponse::from(vk)))
 }
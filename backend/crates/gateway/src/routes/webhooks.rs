@@ -0,0 +1,130 @@
+use axum::{
+    Json,
+    extract::{Extension, Path, Query, State},
+    http::StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::AppState;
+use crate::error::ApiError;
+use crate::middleware::AuthenticatedUser;
+use crate::models::{CreateWebhook, WebhookAttempt, WebhookDelivery, WebhookSubscription};
+
+#[derive(Debug, Serialize)]
+pub struct CreateWebhookResponse {
+    #[serde(flatten)]
+    pub subscription: WebhookSubscription,
+    /// Signing secret; shown only once
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ListDeliveriesParams {
+    /// pending, delivered or failed
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryLogEntry {
+    #[serde(flatten)]
+    pub delivery: WebhookDelivery,
+    /// Oldest first
+    pub attempts: Vec<WebhookAttempt>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ListDeliveriesResponse {
+    pub deliveries: Vec<DeliveryLogEntry>,
+    pub total: i64,
+}
+
+/// POST /v1/webhooks - Subscribe an endpoint to events
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Json(request): Json<CreateWebhook>,
+) -> Result<Json<CreateWebhookResponse>, ApiError> {
+    let subscription = state.webhook_service.create(user.user_id, request).await?;
+
+    Ok(Json(CreateWebhookResponse {
+        secret: subscription.secret.clone(),
+        subscription,
+    }))
+}
+
+/// GET /v1/webhooks - The caller's subscriptions
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+) -> Result<Json<Vec<WebhookSubscription>>, ApiError> {
+    Ok(Json(state.webhook_service.list(user.user_id).await?))
+}
+
+/// GET /v1/webhooks/:id - One of the caller's subscriptions
+pub async fn get_webhook(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<WebhookSubscription>, ApiError> {
+    let subscription = state
+        .webhook_service
+        .get(user.user_id, id)
+        .await?
+        .ok_or(ApiError::WebhookNotFound)?;
+
+    Ok(Json(subscription))
+}
+
+/// DELETE /v1/webhooks/:id - Unsubscribe; pending deliveries are dropped
+pub async fn delete_webhook(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    if !state.webhook_service.delete(user.user_id, id).await? {
+        return Err(ApiError::WebhookNotFound);
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// GET /v1/webhooks/:id/deliveries - Delivery log with every attempt, newest first
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(user): Extension<AuthenticatedUser>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ListDeliveriesParams>,
+) -> Result<Json<ListDeliveriesResponse>, ApiError> {
+    state
+        .webhook_service
+        .get(user.user_id, id)
+        .await?
+        .ok_or(ApiError::WebhookNotFound)?;
+
+    if let Some(status) = params.status.as_deref()
+        && !matches!(status, "pending" | "delivered" | "failed")
+    {
+        return Err(ApiError::BadRequest(format!(
+            "Unknown status '{}', expected pending, delivered or failed",
+            status
+        )));
+    }
+    let limit = params.limit.unwrap_or(50).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let (deliveries, total) = state
+        .webhook_service
+        .deliveries(id, params.status.as_deref(), limit, offset)
+        .await?;
+
+    Ok(Json(ListDeliveriesResponse {
+        deliveries: deliveries
+            .into_iter()
+            .map(|(delivery, attempts)| DeliveryLogEntry { delivery, attempts })
+            .collect(),
+        total,
+    }))
+}
@@ -1,36 +1,258 @@
 use crate::error::ApiError;
-use crate::models::{Anchor, CreateAnchor};
+use crate::models::{
+    Anchor, AnchorFilter, AnchorResponse, AnchorRevision, BatchInclusion, CreateAnchor,
+    UpdateAnchor,
+};
+use crate::services::{Audience, VerificationHistory, enqueue_event, publish_event};
 use sqlx::PgPool;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
 use uuid::Uuid;
 
+const ANCHOR_COLUMNS: &str = "id, proof_hash, vk_hash, valid, prover, proof_system, chain, \
+    block_number, block_hash, block_timestamp, tx_hash, explorer_url, integrity_status, \
+    integrity_note, verification_id, batch_id, confirmation_status, confirmations, created_at, \
+    updated_at";
+
+const REVISION_COLUMNS: &str = "revision, vk_hash, valid, prover, proof_system, block_number, \
+    block_hash, block_timestamp, tx_hash, explorer_url, integrity_status, integrity_note, \
+    verification_id, batch_id, confirmation_status, confirmations, recorded_at";
+
+/// How transaction and block hashes are written on a chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainFormat {
+    /// 0x-prefixed 32-byte hex
+    Evm,
+    /// 32-byte hex without prefix
+    Bitcoin,
+    /// base58 signatures and block hashes
+    Solana,
+}
+
+impl FromStr for ChainFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "evm" => Ok(ChainFormat::Evm),
+            "bitcoin" => Ok(ChainFormat::Bitcoin),
+            "solana" => Ok(ChainFormat::Solana),
+            other => Err(format!("unknown chain format '{}', expected evm, bitcoin or solana", other)),
+        }
+    }
+}
+
+impl ChainFormat {
+    fn is_tx_hash(self, value: &str) -> bool {
+        match self {
+            ChainFormat::Evm => is_prefixed_hash(value),
+            ChainFormat::Bitcoin => is_hex(value, 64),
+            ChainFormat::Solana => is_base58(value, 64..=88),
+        }
+    }
+
+    fn is_block_hash(self, value: &str) -> bool {
+        match self {
+            ChainFormat::Evm => is_prefixed_hash(value),
+            ChainFormat::Bitcoin => is_hex(value, 64),
+            ChainFormat::Solana => is_base58(value, 32..=44),
+        }
+    }
+
+    /// Hex hashes are stored lowercase so lookups match whatever case clients send
+    fn normalize(self, value: String) -> String {
+        match self {
+            ChainFormat::Evm | ChainFormat::Bitcoin => value.to_ascii_lowercase(),
+            ChainFormat::Solana => value,
+        }
+    }
+}
+
+/// What happens to anchors that don't match Prova's verification records. Anchors of
+/// proofs Prova has no record of, such as proofs verified before records were kept, are
+/// always stored as `unverified`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IntegrityPolicy {
+    /// Refuse the anchor
+    Reject,
+    /// Store it with its integrity status so it can be reviewed
+    Flag,
+}
+
+impl FromStr for IntegrityPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "reject" => Ok(IntegrityPolicy::Reject),
+            "flag" => Ok(IntegrityPolicy::Flag),
+            other => Err(format!("unknown policy '{}', expected reject or flag", other)),
+        }
+    }
+}
+
+/// Where an anchor's block stands on its chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConfirmationStatus {
+    /// Submitted, or reported without enough information to count confirmations
+    Pending,
+    /// In a block, not yet past the chain's finality depth
+    Confirmed,
+    /// Past the finality depth; only a new outcome in a new inclusion replaces it
+    Finalized,
+    /// Dropped by a reorg
+    Orphaned,
+}
+
+impl FromStr for ConfirmationStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ConfirmationStatus::Pending),
+            "confirmed" => Ok(ConfirmationStatus::Confirmed),
+            "finalized" => Ok(ConfirmationStatus::Finalized),
+            "orphaned" => Ok(ConfirmationStatus::Orphaned),
+            other => Err(format!(
+                "unknown status '{}', expected pending, confirmed, finalized or orphaned",
+                other
+            )),
+        }
+    }
+}
+
+impl ConfirmationStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ConfirmationStatus::Pending => "pending",
+            ConfirmationStatus::Confirmed => "confirmed",
+            ConfirmationStatus::Finalized => "finalized",
+            ConfirmationStatus::Orphaned => "orphaned",
+        }
+    }
+
+    /// Status implied by a block depth; `confirmations` counts the anchor's own block
+    pub fn from_depth(confirmations: i64, finality_depth: i64) -> Self {
+        match confirmations {
+            c if c <= 0 => ConfirmationStatus::Pending,
+            c if c < finality_depth => ConfirmationStatus::Confirmed,
+            _ => ConfirmationStatus::Finalized,
+        }
+    }
+
+    /// Anchors only move forward, except that a reorg can orphan anything not finalized.
+    /// Moving back from orphaned or confirmed takes a new block or transaction.
+    fn check_transition(self, next: Self, new_inclusion: bool) -> Result<(), String> {
+        use ConfirmationStatus::*;
+        match (self, next) {
+            (Finalized, Finalized) if !new_inclusion => Ok(()),
+            (Finalized, _) => Err("a finalized anchor cannot change".to_string()),
+            (from, to) if from == to => Ok(()),
+            (_, Orphaned) => Ok(()),
+            (Orphaned, to) | (Confirmed, to @ Pending) if !new_inclusion => Err(format!(
+                "an anchor moves from {} to {} only when re-included in another block",
+                self.as_str(),
+                to.as_str()
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AnchorConfig {
+    /// Explorer tx URL template per chain, used when a chain client sent none
+    pub explorer_urls: HashMap<String, String>,
+    /// Chains anchors are accepted for
+    pub chain_formats: HashMap<String, ChainFormat>,
+    /// Confirmations after which an anchor is final, per accepted chain
+    pub finality_depths: HashMap<String, i64>,
+    pub integrity_policy: IntegrityPolicy,
+}
+
+/// Result of checking an anchor against verification records
+struct Integrity {
+    status: &'static str,
+    note: Option<String>,
+    verification_id: Option<Uuid>,
+}
+
 #[derive(Clone)]
 pub struct AnchorService {
     pool: PgPool,
+    history: VerificationHistory,
+    config: Arc<AnchorConfig>,
 }
 
 impl AnchorService {
-    pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+    pub fn new(pool: PgPool, history: VerificationHistory, config: AnchorConfig) -> Self {
+        Self {
+            pool,
+            history,
+            config: Arc::new(config),
+        }
+    }
+
+    /// Fill in the explorer URL of an anchor from the chain's template when it has none
+    pub fn with_explorer_url(&self, mut anchor: Anchor) -> Anchor {
+        if anchor.explorer_url.is_none()
+            && let Some(tx_hash) = &anchor.tx_hash
+        {
+            anchor.explorer_url = self.explorer_url(&anchor.chain, tx_hash);
+        }
+        anchor
     }
 
-    /// Create a new anchor record
-    /// Uses upsert to handle duplicate (proof_hash, chain) combinations
+    /// Explorer link for a transaction from the configured template, if the chain has one
+    pub fn explorer_url(&self, chain: &str, tx_hash: &str) -> Option<String> {
+        self.config
+            .explorer_urls
+            .get(chain)
+            .map(|template| template.replace("{tx}", tx_hash))
+    }
+
+    /// Record an anchor reported by a chain client.
+    /// The anchor is checked against Prova's verification records first; an anchor that
+    /// already exists for the chain is only updated when something changed, and every
+    /// state is kept in `anchor_revisions`. New anchors are announced on the event stream.
     pub async fn create_anchor(&self, request: CreateAnchor) -> Result<Anchor, ApiError> {
-        let anchor = sqlx::query_as::<_, Anchor>(
+        let mut request = self.validate(request)?;
+        let integrity = self.check_integrity(&request).await?;
+
+        if integrity.status != "verified" {
+            let note = integrity.note.clone().unwrap_or_default();
+            if integrity.status == "mismatch"
+                && self.config.integrity_policy == IntegrityPolicy::Reject
+            {
+                return Err(ApiError::AnchorIntegrity(note));
+            }
+            tracing::warn!(
+                proof_hash = %request.proof_hash,
+                chain = %request.chain,
+                status = integrity.status,
+                note = %note,
+                "Recording anchor that does not match verification records"
+            );
+        }
+
+        let initial_status = request
+            .confirmations
+            .map(|c| ConfirmationStatus::from_depth(c, self.finality_depth(&request.chain)))
+            .unwrap_or(ConfirmationStatus::Pending);
+
+        let mut tx = self.pool.begin().await?;
+
+        let inserted = sqlx::query_as::<_, Anchor>(&format!(
             r#"INSERT INTO anchors
                (proof_hash, vk_hash, valid, prover, proof_system, chain,
-                block_number, block_hash, block_timestamp, tx_hash, explorer_url)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
-               ON CONFLICT (proof_hash, chain) DO UPDATE SET
-                   valid = EXCLUDED.valid,
-                   block_number = EXCLUDED.block_number,
-                   block_hash = EXCLUDED.block_hash,
-                   block_timestamp = EXCLUDED.block_timestamp,
-                   tx_hash = EXCLUDED.tx_hash,
-                   explorer_url = EXCLUDED.explorer_url
-               RETURNING id, proof_hash, vk_hash, valid, prover, proof_system, chain,
-                         block_number, block_hash, block_timestamp, tx_hash, explorer_url, created_at"#,
-        )
+                block_number, block_hash, block_timestamp, tx_hash, explorer_url,
+                integrity_status, integrity_note, verification_id, batch_id,
+                confirmation_status, confirmations)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+               ON CONFLICT (proof_hash, chain) DO NOTHING
+               RETURNING {ANCHOR_COLUMNS}"#
+        ))
         .bind(&request.proof_hash)
         .bind(&request.vk_hash)
         .bind(request.valid)
@@ -42,20 +264,430 @@ impl AnchorService {
         .bind(request.block_timestamp)
         .bind(&request.tx_hash)
         .bind(&request.explorer_url)
-        .fetch_one(&self.pool)
+        .bind(integrity.status)
+        .bind(&integrity.note)
+        .bind(integrity.verification_id)
+        .bind(request.batch_id)
+        .bind(initial_status.as_str())
+        .bind(request.confirmations)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (anchor, previous_status) = match inserted {
+            Some(anchor) => (anchor, None),
+            None => {
+                let existing = sqlx::query_as::<_, Anchor>(&format!(
+                    "SELECT {ANCHOR_COLUMNS} FROM anchors WHERE proof_hash = $1 AND chain = $2 FOR UPDATE"
+                ))
+                .bind(&request.proof_hash)
+                .bind(&request.chain)
+                .fetch_one(&mut *tx)
+                .await?;
+
+                let new_inclusion = existing.block_number != request.block_number
+                    || existing.block_hash != request.block_hash
+                    || existing.tx_hash != request.tx_hash;
+                // Chain clients re-reporting a worker anchor do not know its batch
+                if request.batch_id.is_none() && !new_inclusion {
+                    request.batch_id = existing.batch_id;
+                }
+                // A different outcome committed in a new inclusion supersedes the anchor, even
+                // a finalized one; the earlier state stays in the anchor's history
+                let new_outcome =
+                    existing.vk_hash != request.vk_hash || existing.valid != request.valid;
+                let status = if new_outcome && new_inclusion {
+                    initial_status
+                } else {
+                    self.next_status(&existing, None, request.confirmations, new_inclusion)?
+                };
+                let confirmations = if new_inclusion {
+                    request.confirmations
+                } else {
+                    request.confirmations.or(existing.confirmations)
+                };
+
+                if is_unchanged(&existing, &request, &integrity)
+                    && existing.confirmation_status == status.as_str()
+                    && existing.confirmations == confirmations
+                {
+                    return Ok(existing);
+                }
+
+                let updated = sqlx::query_as::<_, Anchor>(&format!(
+                    r#"UPDATE anchors SET
+                           vk_hash = $2, valid = $3, prover = $4, proof_system = $5,
+                           block_number = $6, block_hash = $7, block_timestamp = $8,
+                           tx_hash = $9, explorer_url = $10, integrity_status = $11,
+                           integrity_note = $12, verification_id = $13, batch_id = $14,
+                           confirmation_status = $15, confirmations = $16, updated_at = NOW()
+                       WHERE id = $1
+                       RETURNING {ANCHOR_COLUMNS}"#
+                ))
+                .bind(existing.id)
+                .bind(&request.vk_hash)
+                .bind(request.valid)
+                .bind(&request.prover)
+                .bind(&request.proof_system)
+                .bind(request.block_number)
+                .bind(&request.block_hash)
+                .bind(request.block_timestamp)
+                .bind(&request.tx_hash)
+                .bind(&request.explorer_url)
+                .bind(integrity.status)
+                .bind(&integrity.note)
+                .bind(integrity.verification_id)
+                .bind(request.batch_id)
+                .bind(status.as_str())
+                .bind(confirmations)
+                .fetch_one(&mut *tx)
+                .await?;
+                (updated, Some(existing.confirmation_status))
+            }
+        };
+
+        record_revision(&mut tx, anchor.id).await?;
+        if previous_status.is_none() {
+            let event = AnchorResponse::from(self.with_explorer_url(anchor.clone()));
+            publish_event(&mut *tx, "anchor.created", &anchor.prover, &event).await?;
+        }
+        self.notify_confirmed(&mut tx, previous_status.as_deref(), &anchor)
+            .await?;
+        tx.commit().await?;
+        Ok(anchor)
+    }
+
+    /// Advance an anchor's confirmation status as reported by a chain client.
+    /// A new revision is recorded when the status or the including block changes; plain
+    /// confirmation count updates only touch the anchor.
+    pub async fn update_confirmation(
+        &self,
+        id: Uuid,
+        update: UpdateAnchor,
+    ) -> Result<Anchor, ApiError> {
+        let requested = update
+            .status
+            .as_deref()
+            .map(str::parse::<ConfirmationStatus>)
+            .transpose()
+            .map_err(ApiError::BadRequest)?;
+        if requested.is_none() && update.confirmations.is_none() {
+            return Err(ApiError::BadRequest(
+                "status or confirmations is required".to_string(),
+            ));
+        }
+        if update.confirmations.is_some_and(|c| c < 0) {
+            return Err(ApiError::BadRequest("confirmations must not be negative".to_string()));
+        }
+
+        let mut tx = self.pool.begin().await?;
+
+        let existing = sqlx::query_as::<_, Anchor>(&format!(
+            "SELECT {ANCHOR_COLUMNS} FROM anchors WHERE id = $1 FOR UPDATE"
+        ))
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        .ok_or(ApiError::AnchorNotFound)?;
+
+        let format = self.check_chain_data(
+            &existing.chain,
+            update.tx_hash.as_deref(),
+            update.block_hash.as_deref(),
+            update.block_number,
+        )?;
+        let tx_hash = update.tx_hash.map(|h| format.normalize(h));
+        let block_hash = update.block_hash.map(|h| format.normalize(h));
+
+        let new_inclusion = (block_hash.is_some() && block_hash != existing.block_hash)
+            || (update.block_number.is_some() && update.block_number != existing.block_number)
+            || (tx_hash.is_some() && tx_hash != existing.tx_hash);
+        let (block_number, block_hash, block_timestamp) = if new_inclusion {
+            (update.block_number, block_hash, update.block_timestamp)
+        } else {
+            (
+                existing.block_number,
+                existing.block_hash.clone(),
+                update.block_timestamp.or(existing.block_timestamp),
+            )
+        };
+        // A new transaction invalidates the stored explorer link
+        let (tx_hash, explorer_url) = match tx_hash {
+            Some(tx_hash) if existing.tx_hash.as_deref() != Some(&tx_hash) => (Some(tx_hash), None),
+            _ => (existing.tx_hash.clone(), existing.explorer_url.clone()),
+        };
+
+        if update.confirmations.is_some_and(|c| c > 0)
+            && block_number.is_none()
+            && block_hash.is_none()
+        {
+            return Err(ApiError::BadRequest(
+                "confirmations need a block_number or block_hash".to_string(),
+            ));
+        }
+
+        let status = self.next_status(&existing, requested, update.confirmations, new_inclusion)?;
+        if matches!(status, ConfirmationStatus::Confirmed | ConfirmationStatus::Finalized)
+            && block_number.is_none()
+            && block_hash.is_none()
+        {
+            return Err(ApiError::BadRequest(format!(
+                "a {} anchor needs a block_number or block_hash",
+                status.as_str()
+            )));
+        }
+        let confirmations = if new_inclusion || status == ConfirmationStatus::Orphaned {
+            update.confirmations
+        } else {
+            update.confirmations.or(existing.confirmations)
+        };
+
+        let status_changed = existing.confirmation_status != status.as_str();
+        if !status_changed
+            && !new_inclusion
+            && existing.confirmations == confirmations
+            && existing.block_timestamp == block_timestamp
+        {
+            return Ok(existing);
+        }
+
+        let anchor = sqlx::query_as::<_, Anchor>(&format!(
+            r#"UPDATE anchors SET
+                   confirmation_status = $2, confirmations = $3, block_number = $4,
+                   block_hash = $5, block_timestamp = $6, tx_hash = $7, explorer_url = $8,
+                   updated_at = NOW()
+               WHERE id = $1
+               RETURNING {ANCHOR_COLUMNS}"#
+        ))
+        .bind(id)
+        .bind(status.as_str())
+        .bind(confirmations)
+        .bind(block_number)
+        .bind(&block_hash)
+        .bind(block_timestamp)
+        .bind(&tx_hash)
+        .bind(&explorer_url)
+        .fetch_one(&mut *tx)
         .await?;
 
+        if status_changed || new_inclusion {
+            record_revision(&mut tx, id).await?;
+        }
+        self.notify_confirmed(&mut tx, Some(&existing.confirmation_status), &anchor)
+            .await?;
+        tx.commit().await?;
+
+        if status_changed {
+            tracing::info!(
+                anchor_id = %id,
+                chain = %anchor.chain,
+                from = %existing.confirmation_status,
+                to = status.as_str(),
+                "Anchor confirmation status changed"
+            );
+        }
         Ok(anchor)
     }
 
+    /// Queue anchor.confirmed for the proof's verifiers when an anchor first counts as
+    /// confirmed (or goes straight to finalized)
+    async fn notify_confirmed(
+        &self,
+        tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        previous_status: Option<&str>,
+        anchor: &Anchor,
+    ) -> Result<(), ApiError> {
+        let is_confirmed = |status: &str| matches!(status, "confirmed" | "finalized");
+        if !is_confirmed(&anchor.confirmation_status) || previous_status.is_some_and(is_confirmed) {
+            return Ok(());
+        }
+
+        let event = AnchorResponse::from(self.with_explorer_url(anchor.clone()));
+        enqueue_event(
+            &mut **tx,
+            "anchor.confirmed",
+            &event,
+            Audience::ProofVerifiers(&anchor.proof_hash),
+        )
+        .await?;
+        Ok(())
+    }
+
+    /// Confirmations after which anchors on `chain` are final
+    pub fn finality_depth(&self, chain: &str) -> i64 {
+        self.config.finality_depths.get(chain).copied().unwrap_or(1)
+    }
+
+    /// Status an existing anchor moves to: the requested one, the one implied by the
+    /// reported confirmations, pending when it was re-included without a depth, or the
+    /// current one
+    fn next_status(
+        &self,
+        existing: &Anchor,
+        requested: Option<ConfirmationStatus>,
+        confirmations: Option<i64>,
+        new_inclusion: bool,
+    ) -> Result<ConfirmationStatus, ApiError> {
+        let current: ConfirmationStatus = existing.confirmation_status.parse().map_err(|e| {
+            tracing::error!(anchor_id = %existing.id, error = %e, "Stored anchor status is invalid");
+            ApiError::Internal
+        })?;
+        let depth = self.finality_depth(&existing.chain);
+
+        let next = match (requested, confirmations) {
+            (Some(status), _) => status,
+            (None, Some(confirmations)) => ConfirmationStatus::from_depth(confirmations, depth),
+            (None, None) if new_inclusion => ConfirmationStatus::Pending,
+            (None, None) => current,
+        };
+        if next == ConfirmationStatus::Finalized && confirmations.is_some_and(|c| c < depth) {
+            return Err(ApiError::BadRequest(format!(
+                "{} anchors are final after {} confirmations",
+                existing.chain, depth
+            )));
+        }
+
+        current
+            .check_transition(next, new_inclusion)
+            .map_err(ApiError::InvalidTransition)?;
+        Ok(next)
+    }
+
+    /// Every recorded state of an anchor, oldest first
+    pub async fn history(&self, anchor_id: Uuid) -> Result<Vec<AnchorRevision>, ApiError> {
+        let revisions = sqlx::query_as::<_, AnchorRevision>(&format!(
+            "SELECT {REVISION_COLUMNS} FROM anchor_revisions WHERE anchor_id = $1 ORDER BY revision"
+        ))
+        .bind(anchor_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(revisions)
+    }
+
+    /// Check hash formats for the anchor's chain and normalize them
+    fn validate(&self, mut request: CreateAnchor) -> Result<CreateAnchor, ApiError> {
+        let format = self.check_chain_data(
+            &request.chain,
+            request.tx_hash.as_deref(),
+            request.block_hash.as_deref(),
+            request.block_number,
+        )?;
+
+        if !is_prefixed_hash(&request.proof_hash) {
+            return Err(ApiError::BadRequest(
+                "proof_hash must be a 0x-prefixed 32-byte hex hash".to_string(),
+            ));
+        }
+        if !is_prefixed_hash(&request.vk_hash) {
+            return Err(ApiError::BadRequest(
+                "vk_hash must be a 0x-prefixed 32-byte hex hash".to_string(),
+            ));
+        }
+        if request.confirmations.is_some_and(|c| c < 0) {
+            return Err(ApiError::BadRequest("confirmations must not be negative".to_string()));
+        }
+        if request.confirmations.is_some_and(|c| c > 0)
+            && request.block_number.is_none()
+            && request.block_hash.is_none()
+        {
+            return Err(ApiError::BadRequest(
+                "confirmations need a block_number or block_hash".to_string(),
+            ));
+        }
+
+        request.proof_hash = request.proof_hash.to_ascii_lowercase();
+        request.vk_hash = request.vk_hash.to_ascii_lowercase();
+        request.tx_hash = request.tx_hash.map(|h| format.normalize(h));
+        request.block_hash = request.block_hash.map(|h| format.normalize(h));
+        Ok(request)
+    }
+
+    /// Check transaction and block data against the chain's format
+    fn check_chain_data(
+        &self,
+        chain: &str,
+        tx_hash: Option<&str>,
+        block_hash: Option<&str>,
+        block_number: Option<i64>,
+    ) -> Result<ChainFormat, ApiError> {
+        let format = *self
+            .config
+            .chain_formats
+            .get(chain)
+            .ok_or_else(|| ApiError::BadRequest(format!("Unsupported chain '{}'", chain)))?;
+
+        if let Some(tx_hash) = tx_hash
+            && !format.is_tx_hash(tx_hash)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "tx_hash is not a valid {} transaction hash",
+                chain
+            )));
+        }
+        if let Some(block_hash) = block_hash
+            && !format.is_block_hash(block_hash)
+        {
+            return Err(ApiError::BadRequest(format!(
+                "block_hash is not a valid {} block hash",
+                chain
+            )));
+        }
+        if block_number.is_some_and(|n| n < 0) {
+            return Err(ApiError::BadRequest("block_number must not be negative".to_string()));
+        }
+
+        Ok(format)
+    }
+
+    /// Compare the anchor with the latest verification of the proof with the same VK
+    async fn check_integrity(&self, request: &CreateAnchor) -> Result<Integrity, ApiError> {
+        let Some(record) = self
+            .history
+            .latest_with_vk(&request.proof_hash, &request.vk_hash)
+            .await?
+        else {
+            return Ok(Integrity {
+                status: "unverified",
+                note: Some(format!(
+                    "Prova has no verification of this proof with VK {}",
+                    request.vk_hash
+                )),
+                verification_id: None,
+            });
+        };
+
+        let mut differences = Vec::new();
+        if record.valid != request.valid {
+            differences.push(format!(
+                "anchored valid={} but Prova recorded valid={}",
+                request.valid, record.valid
+            ));
+        }
+        if record.prover != request.prover {
+            differences.push(format!(
+                "anchored prover '{}' but Prova recorded '{}'",
+                request.prover, record.prover
+            ));
+        }
+        if record.proof_system != request.proof_system {
+            differences.push(format!(
+                "anchored proof system '{}' but Prova recorded '{}'",
+                request.proof_system, record.proof_system
+            ));
+        }
+
+        Ok(Integrity {
+            status: if differences.is_empty() { "verified" } else { "mismatch" },
+            note: (!differences.is_empty()).then(|| differences.join("; ")),
+            verification_id: Some(record.id),
+        })
+    }
+
     /// Get anchor by ID
     pub async fn get_by_id(&self, id: Uuid) -> Result<Option<Anchor>, ApiError> {
-        let anchor = sqlx::query_as::<_, Anchor>(
-            r#"SELECT id, proof_hash, vk_hash, valid, prover, proof_system, chain,
-                      block_number, block_hash, block_timestamp, tx_hash, explorer_url, created_at
-               FROM anchors
-               WHERE id = $1"#,
-        )
+        let anchor = sqlx::query_as::<_, Anchor>(&format!(
+            "SELECT {ANCHOR_COLUMNS} FROM anchors WHERE id = $1"
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -65,13 +697,9 @@ impl AnchorService {
 
     /// Get anchors by proof hash
     pub async fn get_by_proof_hash(&self, proof_hash: &str) -> Result<Vec<Anchor>, ApiError> {
-        let anchors = sqlx::query_as::<_, Anchor>(
-            r#"SELECT id, proof_hash, vk_hash, valid, prover, proof_system, chain,
-                      block_number, block_hash, block_timestamp, tx_hash, explorer_url, created_at
-               FROM anchors
-               WHERE proof_hash = $1
-               ORDER BY created_at DESC"#,
-        )
+        let anchors = sqlx::query_as::<_, Anchor>(&format!(
+            "SELECT {ANCHOR_COLUMNS} FROM anchors WHERE proof_hash = $1 ORDER BY created_at DESC"
+        ))
         .bind(proof_hash)
         .fetch_all(&self.pool)
         .await?;
@@ -85,12 +713,9 @@ impl AnchorService {
         proof_hash: &str,
         chain: &str,
     ) -> Result<Option<Anchor>, ApiError> {
-        let anchor = sqlx::query_as::<_, Anchor>(
-            r#"SELECT id, proof_hash, vk_hash, valid, prover, proof_system, chain,
-                      block_number, block_hash, block_timestamp, tx_hash, explorer_url, created_at
-               FROM anchors
-               WHERE proof_hash = $1 AND chain = $2"#,
-        )
+        let anchor = sqlx::query_as::<_, Anchor>(&format!(
+            "SELECT {ANCHOR_COLUMNS} FROM anchors WHERE proof_hash = $1 AND chain = $2"
+        ))
         .bind(proof_hash)
         .bind(chain)
         .fetch_optional(&self.pool)
@@ -98,4 +723,187 @@ impl AnchorService {
 
         Ok(anchor)
     }
+
+    /// The batch leaf behind a proof's worker-written anchor, on `chain` or on whichever
+    /// chain was anchored most recently
+    pub async fn inclusion(
+        &self,
+        proof_hash: &str,
+        chain: Option<&str>,
+    ) -> Result<Option<BatchInclusion>, ApiError> {
+        let inclusion = sqlx::query_as::<_, BatchInclusion>(
+            r#"SELECT a.id AS anchor_id, a.chain, a.confirmation_status, a.confirmations,
+                      b.id AS batch_id, b.merkle_root, b.leaf_count,
+                      i.leaf_index, i.proof_hash, i.vk_hash, i.valid, i.merkle_path,
+                      b.block_number, b.block_hash, b.block_timestamp, b.tx_hash,
+                      COALESCE(b.explorer_url, a.explorer_url) AS explorer_url, b.submitted_at
+               FROM anchors a
+               JOIN anchor_batches b ON b.id = a.batch_id AND b.status = 'submitted'
+               JOIN anchor_batch_items i ON i.batch_id = b.id AND i.proof_hash = a.proof_hash
+               WHERE a.proof_hash = $1 AND ($2::text IS NULL OR a.chain = $2)
+               ORDER BY a.updated_at DESC
+               LIMIT 1"#,
+        )
+        .bind(proof_hash)
+        .bind(chain)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(inclusion)
+    }
+
+    /// List anchors matching the filter, newest first; returns (anchors, total_count)
+    pub async fn list(
+        &self,
+        filter: &AnchorFilter,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Anchor>, i64), ApiError> {
+        const WHERE: &str = r#"WHERE ($1::text IS NULL OR proof_hash = $1)
+                 AND ($2::text IS NULL OR chain = $2)
+                 AND ($3::text IS NULL OR vk_hash = $3)
+                 AND ($4::timestamptz IS NULL OR created_at >= $4)
+                 AND ($5::timestamptz IS NULL OR created_at < $5)
+                 AND ($6::text IS NULL OR confirmation_status = $6)"#;
+
+        let anchors = sqlx::query_as::<_, Anchor>(&format!(
+            "SELECT {ANCHOR_COLUMNS} FROM anchors {WHERE} ORDER BY created_at DESC, id LIMIT $7 OFFSET $8"
+        ))
+        .bind(&filter.proof_hash)
+        .bind(&filter.chain)
+        .bind(&filter.vk_hash)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(&filter.confirmation_status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM anchors {WHERE}"))
+            .bind(&filter.proof_hash)
+            .bind(&filter.chain)
+            .bind(&filter.vk_hash)
+            .bind(filter.from)
+            .bind(filter.to)
+            .bind(&filter.confirmation_status)
+            .fetch_one(&self.pool)
+            .await?;
+
+        Ok((anchors, total))
+    }
+}
+
+/// Snapshot the anchor's current state as its next revision
+async fn record_revision(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    anchor_id: Uuid,
+) -> Result<(), ApiError> {
+    sqlx::query(
+        r#"INSERT INTO anchor_revisions
+           (anchor_id, revision, vk_hash, valid, prover, proof_system, block_number,
+            block_hash, block_timestamp, tx_hash, explorer_url, integrity_status,
+            integrity_note, verification_id, batch_id, confirmation_status, confirmations)
+           SELECT id,
+                  COALESCE((SELECT MAX(revision) FROM anchor_revisions WHERE anchor_id = $1), 0) + 1,
+                  vk_hash, valid, prover, proof_system, block_number, block_hash,
+                  block_timestamp, tx_hash, explorer_url, integrity_status,
+                  integrity_note, verification_id, batch_id, confirmation_status, confirmations
+           FROM anchors WHERE id = $1"#,
+    )
+    .bind(anchor_id)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
+
+/// Whether reporting `request` again would leave the anchor as it is
+fn is_unchanged(existing: &Anchor, request: &CreateAnchor, integrity: &Integrity) -> bool {
+    existing.vk_hash == request.vk_hash
+        && existing.valid == request.valid
+        && existing.prover == request.prover
+        && existing.proof_system == request.proof_system
+        && existing.block_number == request.block_number
+        && existing.block_hash == request.block_hash
+        && existing.block_timestamp == request.block_timestamp
+        && existing.tx_hash == request.tx_hash
+        && existing.explorer_url == request.explorer_url
+        && existing.integrity_status == integrity.status
+        && existing.integrity_note == integrity.note
+        && existing.verification_id == integrity.verification_id
+        && existing.batch_id == request.batch_id
+}
+
+fn is_hex(value: &str, len: usize) -> bool {
+    value.len() == len && value.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// 0x-prefixed 32-byte hex, as used for proof, VK and EVM hashes
+fn is_prefixed_hash(value: &str) -> bool {
+    value.strip_prefix("0x").is_some_and(|hex| is_hex(hex, 64))
+}
+
+fn is_base58(value: &str, len: std::ops::RangeInclusive<usize>) -> bool {
+    len.contains(&value.len())
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() && !matches!(c, '0' | 'O' | 'I' | 'l'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConfirmationStatus::{self, *};
+
+    #[test]
+    fn depth_decides_status() {
+        assert_eq!(ConfirmationStatus::from_depth(0, 12), Pending);
+        assert_eq!(ConfirmationStatus::from_depth(-1, 12), Pending);
+        assert_eq!(ConfirmationStatus::from_depth(1, 12), Confirmed);
+        assert_eq!(ConfirmationStatus::from_depth(11, 12), Confirmed);
+        assert_eq!(ConfirmationStatus::from_depth(12, 12), Finalized);
+        assert_eq!(ConfirmationStatus::from_depth(1, 1), Finalized);
+    }
+
+    #[test]
+    fn transitions() {
+        // (from, to, new inclusion, allowed)
+        let table = [
+            (Pending, Pending, false, true),
+            (Pending, Confirmed, false, true),
+            (Pending, Finalized, false, true),
+            (Pending, Orphaned, false, true),
+            (Confirmed, Confirmed, false, true),
+            (Confirmed, Finalized, false, true),
+            (Confirmed, Orphaned, false, true),
+            // Going back to pending takes another block or transaction
+            (Confirmed, Pending, false, false),
+            (Confirmed, Pending, true, true),
+            // A reorg orphans anything not final; it only moves on when re-included
+            (Orphaned, Orphaned, false, true),
+            (Orphaned, Pending, false, false),
+            (Orphaned, Confirmed, false, false),
+            (Orphaned, Finalized, false, false),
+            (Orphaned, Pending, true, true),
+            (Orphaned, Confirmed, true, true),
+            (Orphaned, Finalized, true, true),
+            // The same outcome stays final, even when reported in a new inclusion
+            (Finalized, Finalized, false, true),
+            (Finalized, Finalized, true, false),
+            (Finalized, Pending, false, false),
+            (Finalized, Confirmed, false, false),
+            (Finalized, Orphaned, false, false),
+            (Finalized, Confirmed, true, false),
+            (Finalized, Orphaned, true, false),
+        ];
+        for (from, to, new_inclusion, allowed) in table {
+            assert_eq!(
+                from.check_transition(to, new_inclusion).is_ok(),
+                allowed,
+                "{} -> {} (new inclusion: {new_inclusion})",
+                from.as_str(),
+                to.as_str()
+            );
+        }
+    }
 }
@@ -0,0 +1,311 @@
+use prova_merkle::{Leaf, MerkleTree, to_hex};
+use sqlx::PgPool;
+use std::sync::Arc;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{CreateAnchor, VerificationRecord};
+use crate::services::{AnchorService, ChainSubmitter, VerificationHistory};
+
+/// Settings of the built-in anchoring worker
+#[derive(Debug, Clone)]
+pub struct AnchorWorkerConfig {
+    /// Chain the mock submitter reports
+    pub chain: String,
+    pub interval: Duration,
+    /// Most results committed to one root
+    pub max_batch_size: i64,
+}
+
+/// Periodically commits new verification results to a Merkle root, publishes the root
+/// through a `ChainSubmitter` and records an anchor with its inclusion path per proof
+#[derive(Clone)]
+pub struct AnchorWorker {
+    pool: PgPool,
+    history: VerificationHistory,
+    anchor_service: AnchorService,
+    submitter: Arc<dyn ChainSubmitter>,
+    interval: Duration,
+    max_batch_size: i64,
+}
+
+/// Verification results committed to by a batch, with their leaf hashes
+struct BatchLeaf {
+    record: VerificationRecord,
+    leaf: Leaf,
+}
+
+/// Leaves for the records that can be anchored, the tree over them and its root as hex.
+/// None when no record can be anchored.
+fn build_batch(records: Vec<VerificationRecord>) -> Option<(Vec<BatchLeaf>, MerkleTree, String)> {
+    let leaves: Vec<BatchLeaf> = records
+        .into_iter()
+        .filter_map(|record| {
+            let vk_hash = record.vk_hash.as_deref()?;
+            match Leaf::new(&record.proof_hash, vk_hash, record.valid) {
+                Ok(leaf) => Some(BatchLeaf { record, leaf }),
+                Err(e) => {
+                    tracing::warn!(proof_hash = %record.proof_hash, error = %e, "Skipping result that cannot be anchored");
+                    None
+                }
+            }
+        })
+        .collect();
+
+    let tree = MerkleTree::new(leaves.iter().map(|l| l.leaf.hash()).collect());
+    let merkle_root = to_hex(&tree.root()?);
+    Some((leaves, tree, merkle_root))
+}
+
+impl AnchorWorker {
+    pub fn new(
+        pool: PgPool,
+        history: VerificationHistory,
+        anchor_service: AnchorService,
+        submitter: Arc<dyn ChainSubmitter>,
+        config: &AnchorWorkerConfig,
+    ) -> Self {
+        Self {
+            pool,
+            history,
+            anchor_service,
+            submitter,
+            interval: config.interval,
+            max_batch_size: config.max_batch_size,
+        }
+    }
+
+    pub fn spawn(&self) {
+        let worker = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(worker.interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = worker.run_once().await {
+                    tracing::warn!(chain = %worker.submitter.chain(), error = %e, "Anchoring run failed");
+                }
+            }
+        });
+    }
+
+    /// Anchor one batch, unless another gateway replica is already anchoring this chain.
+    /// Returns the batch id when a batch was submitted.
+    pub async fn run_once(&self) -> Result<Option<Uuid>, ApiError> {
+        let chain = self.submitter.chain().to_string();
+
+        // Session lock, held on this connection for the whole run
+        let mut lock = self.pool.acquire().await?;
+        let locked: bool =
+            sqlx::query_scalar("SELECT pg_try_advisory_lock(hashtext('anchor_worker:' || $1))")
+                .bind(&chain)
+                .fetch_one(&mut *lock)
+                .await?;
+        if !locked {
+            return Ok(None);
+        }
+
+        let outcome = self.anchor_batch(&chain).await;
+
+        sqlx::query("SELECT pg_advisory_unlock(hashtext('anchor_worker:' || $1))")
+            .bind(&chain)
+            .execute(&mut *lock)
+            .await?;
+        outcome
+    }
+
+    async fn anchor_batch(&self, chain: &str) -> Result<Option<Uuid>, ApiError> {
+        let records = self.history.unanchored(chain, self.max_batch_size).await?;
+        let Some((leaves, tree, merkle_root)) = build_batch(records) else {
+            return Ok(None);
+        };
+        let batch_id = self.record_batch(chain, &merkle_root, &tree, &leaves).await?;
+
+        let submission = match self.submitter.submit(&merkle_root, leaves.len()).await {
+            Ok(submission) => submission,
+            Err(e) => {
+                sqlx::query("UPDATE anchor_batches SET status = 'failed', error = $2 WHERE id = $1")
+                    .bind(batch_id)
+                    .bind(&e)
+                    .execute(&self.pool)
+                    .await?;
+                return Err(ApiError::Anchoring(e));
+            }
+        };
+
+        sqlx::query(
+            r#"UPDATE anchor_batches SET
+                   status = 'submitted', tx_hash = $2, block_number = $3, block_hash = $4,
+                   block_timestamp = $5, explorer_url = $6, submitted_at = NOW()
+               WHERE id = $1"#,
+        )
+        .bind(batch_id)
+        .bind(&submission.tx_hash)
+        .bind(submission.block_number)
+        .bind(&submission.block_hash)
+        .bind(submission.block_timestamp)
+        .bind(&submission.explorer_url)
+        .execute(&self.pool)
+        .await?;
+
+        let mut anchored = 0;
+        for BatchLeaf { record, .. } in leaves {
+            let anchor = CreateAnchor {
+                proof_hash: record.proof_hash,
+                vk_hash: record.vk_hash.unwrap_or_default(),
+                valid: record.valid,
+                prover: record.prover,
+                proof_system: record.proof_system,
+                chain: chain.to_string(),
+                block_number: submission.block_number,
+                block_hash: submission.block_hash.clone(),
+                block_timestamp: submission.block_timestamp,
+                tx_hash: Some(submission.tx_hash.clone()),
+                explorer_url: submission.explorer_url.clone(),
+                batch_id: Some(batch_id),
+                confirmations: submission.confirmations,
+            };
+            let proof_hash = anchor.proof_hash.clone();
+            match self.anchor_service.create_anchor(anchor).await {
+                Ok(_) => anchored += 1,
+                Err(e) => {
+                    tracing::warn!(batch_id = %batch_id, proof_hash = %proof_hash, error = %e, "Failed to record anchor");
+                    // Otherwise the same result would head every later batch
+                    sqlx::query(
+                        r#"UPDATE anchor_batch_items SET anchor_error = $3
+                           WHERE batch_id = $1 AND verification_id = $2"#,
+                    )
+                    .bind(batch_id)
+                    .bind(record.id)
+                    .bind(e.to_string())
+                    .execute(&self.pool)
+                    .await?;
+                }
+            }
+        }
+
+        tracing::info!(
+            batch_id = %batch_id,
+            chain = %chain,
+            merkle_root = %merkle_root,
+            tx_hash = %submission.tx_hash,
+            anchored,
+            "Anchored verification batch"
+        );
+        Ok(Some(batch_id))
+    }
+
+    /// Store the batch and every leaf's inclusion path before the root is submitted
+    async fn record_batch(
+        &self,
+        chain: &str,
+        merkle_root: &str,
+        tree: &MerkleTree,
+        leaves: &[BatchLeaf],
+    ) -> Result<Uuid, ApiError> {
+        let mut tx = self.pool.begin().await?;
+
+        let batch_id: Uuid = sqlx::query_scalar(
+            r#"INSERT INTO anchor_batches (chain, merkle_root, leaf_count)
+               VALUES ($1, $2, $3)
+               RETURNING id"#,
+        )
+        .bind(chain)
+        .bind(merkle_root)
+        .bind(leaves.len() as i32)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        for (index, BatchLeaf { record, leaf }) in leaves.iter().enumerate() {
+            let path = tree.proof(index).unwrap_or_default();
+            sqlx::query(
+                r#"INSERT INTO anchor_batch_items
+                   (batch_id, leaf_index, proof_hash, vk_hash, valid, verification_id,
+                    leaf_hash, merkle_path)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+            )
+            .bind(batch_id)
+            .bind(index as i32)
+            .bind(&record.proof_hash)
+            .bind(&record.vk_hash)
+            .bind(record.valid)
+            .bind(record.id)
+            .bind(to_hex(&leaf.hash()))
+            .bind(sqlx::types::Json(path))
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(batch_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::MockSubmitter;
+    use chrono::Utc;
+    use prova_merkle::{InclusionProof, parse_hash};
+
+    fn record(proof_hash: String, vk_hash: Option<String>, valid: bool) -> VerificationRecord {
+        VerificationRecord {
+            id: Uuid::new_v4(),
+            user_id: Uuid::new_v4(),
+            proof_hash,
+            public_inputs_hash: None,
+            prover: "zisk".to_string(),
+            proof_system: "zisk".to_string(),
+            prover_version: "v0.1.0".to_string(),
+            verifier_version: None,
+            vk_id: None,
+            vk_hash,
+            valid,
+            error: None,
+            reverification_job_id: None,
+            verified_at: Utc::now(),
+            as_of: None,
+        }
+    }
+
+    fn hash(i: u8) -> String {
+        to_hex(&[i; 32])
+    }
+
+    #[tokio::test]
+    async fn batch_submitted_through_mock_submitter_proves_every_leaf() {
+        let records = vec![
+            record(hash(1), Some(hash(0xaa)), true),
+            record(hash(2), Some(hash(0xaa)), false),
+            // Results without a VK or with malformed hashes are left out
+            record(hash(3), None, true),
+            record("0xnot-a-hash".to_string(), Some(hash(0xaa)), true),
+            record(hash(4), Some(hash(0xbb)), true),
+        ];
+        let (leaves, tree, merkle_root) = build_batch(records).unwrap();
+        assert_eq!(leaves.len(), 3);
+
+        let submitter = MockSubmitter::new("local".to_string());
+        let first = submitter.submit(&merkle_root, leaves.len()).await.unwrap();
+        let second = submitter.submit(&merkle_root, leaves.len()).await.unwrap();
+        assert_eq!(submitter.chain(), "local");
+        assert_eq!(first.block_number, Some(1));
+        assert_eq!(second.block_number, Some(2));
+        assert_eq!(first.confirmations, Some(1));
+        assert_ne!(first.tx_hash, second.tx_hash);
+        assert!(parse_hash(&first.tx_hash).is_ok());
+
+        let root = parse_hash(&merkle_root).unwrap();
+        for (index, BatchLeaf { record, leaf }) in leaves.iter().enumerate() {
+            let proof = InclusionProof::new(leaf, tree.proof(index).unwrap(), root);
+            assert_eq!(proof.proof_hash, record.proof_hash);
+            assert!(proof.verify().is_ok(), "leaf {index}");
+        }
+    }
+
+    #[test]
+    fn nothing_to_anchor_builds_no_batch() {
+        assert!(build_batch(Vec::new()).is_none());
+        assert!(build_batch(vec![record(hash(1), None, true)]).is_none());
+    }
+}
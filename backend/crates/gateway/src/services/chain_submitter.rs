@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicI64, Ordering};
+
+/// Where a Merkle root ended up on chain
+#[derive(Debug, Clone)]
+pub struct ChainSubmission {
+    pub tx_hash: String,
+    pub block_number: Option<i64>,
+    pub block_hash: Option<String>,
+    pub block_timestamp: Option<DateTime<Utc>>,
+    pub explorer_url: Option<String>,
+    /// Depth of the block when the submitter returned, if it waited for inclusion
+    pub confirmations: Option<i64>,
+}
+
+pub type SubmitFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<ChainSubmission, String>> + Send + 'a>>;
+
+/// Publishes Merkle roots of anchor batches to a chain
+pub trait ChainSubmitter: Send + Sync {
+    /// Chain name recorded on anchors; must be an accepted anchor chain
+    fn chain(&self) -> &str;
+
+    /// Publish `merkle_root` (0x-prefixed hex) committing to `leaf_count` results
+    fn submit<'a>(&'a self, merkle_root: &'a str, leaf_count: usize) -> SubmitFuture<'a>;
+}
+
+/// Pretends to publish roots: returns a deterministic EVM-style transaction in a new block
+/// every time, without talking to any chain. The block counts as one confirmation.
+/// For development and tests.
+pub struct MockSubmitter {
+    chain: String,
+    next_block: AtomicI64,
+}
+
+impl MockSubmitter {
+    pub fn new(chain: String) -> Self {
+        Self {
+            chain,
+            next_block: AtomicI64::new(1),
+        }
+    }
+}
+
+impl ChainSubmitter for MockSubmitter {
+    fn chain(&self) -> &str {
+        &self.chain
+    }
+
+    fn submit<'a>(&'a self, merkle_root: &'a str, leaf_count: usize) -> SubmitFuture<'a> {
+        Box::pin(async move {
+            let block_number = self.next_block.fetch_add(1, Ordering::Relaxed);
+            let digest = |parts: &[&[u8]]| {
+                let mut hasher = Sha256::new();
+                for part in parts {
+                    hasher.update(part);
+                }
+                format!("0x{}", hex::encode(hasher.finalize()))
+            };
+
+            tracing::info!(
+                chain = %self.chain,
+                merkle_root = %merkle_root,
+                leaf_count,
+                block_number,
+                "Mock chain accepted Merkle root"
+            );
+
+            Ok(ChainSubmission {
+                tx_hash: digest(&[b"tx", merkle_root.as_bytes(), &block_number.to_be_bytes()]),
+                block_number: Some(block_number),
+                block_hash: Some(digest(&[b"block", &block_number.to_be_bytes()])),
+                block_timestamp: Some(Utc::now()),
+                explorer_url: None,
+                confirmations: Some(1),
+            })
+        })
+    }
+}
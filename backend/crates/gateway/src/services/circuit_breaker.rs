@@ -0,0 +1,109 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Circuit breaker for a verifier backend.
+///
+/// Opens after `threshold` consecutive failures and rejects calls until the
+/// cooldown has passed. The first call after the cooldown is let through as a
+/// trial (half-open): success closes the breaker, failure re-opens it.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    name: String,
+    inner: Arc<Mutex<BreakerState>>,
+    threshold: u32,
+    cooldown: Duration,
+}
+
+struct BreakerState {
+    state: State,
+    consecutive_failures: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Closed,
+    Open { until: Instant },
+    HalfOpen,
+}
+
+/// Retry-After given to callers while a half-open trial call is in flight
+const HALF_OPEN_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+impl CircuitBreaker {
+    pub fn new(name: impl Into<String>, threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            name: name.into(),
+            inner: Arc::new(Mutex::new(BreakerState {
+                state: State::Closed,
+                consecutive_failures: 0,
+            })),
+            threshold: threshold.max(1),
+            cooldown,
+        }
+    }
+
+    /// Check whether a call may proceed. Returns the time to wait when the breaker is open.
+    pub fn check(&self) -> Result<(), Duration> {
+        let mut inner = self.lock();
+        let now = Instant::now();
+
+        match inner.state {
+            State::Closed => Ok(()),
+            State::Open { until } if now >= until => {
+                inner.state = State::HalfOpen;
+                tracing::info!(backend = %self.name, "Circuit breaker half-open, allowing trial call");
+                Ok(())
+            }
+            State::Open { until } => Err(until - now),
+            State::HalfOpen => Err(HALF_OPEN_RETRY_AFTER),
+        }
+    }
+
+    pub fn record_success(&self) {
+        let mut inner = self.lock();
+        if inner.state != State::Closed {
+            tracing::info!(backend = %self.name, "Circuit breaker closed");
+        }
+        inner.state = State::Closed;
+        inner.consecutive_failures = 0;
+    }
+
+    /// End an admitted call that never got an answer from the verifier, e.g. because the
+    /// upload failed. A half-open trial is given back, so the next call becomes the trial.
+    pub fn record_abandoned(&self) {
+        let mut inner = self.lock();
+        if inner.state == State::HalfOpen {
+            inner.state = State::Open {
+                until: Instant::now(),
+            };
+        }
+    }
+
+    pub fn record_failure(&self) {
+        let mut inner = self.lock();
+        inner.consecutive_failures += 1;
+
+        let should_open = match inner.state {
+            State::HalfOpen => true,
+            State::Closed => inner.consecutive_failures >= self.threshold,
+            State::Open { .. } => false,
+        };
+
+        if should_open {
+            inner.state = State::Open {
+                until: Instant::now() + self.cooldown,
+            };
+            tracing::warn!(
+                backend = %self.name,
+                consecutive_failures = inner.consecutive_failures,
+                cooldown_secs = self.cooldown.as_secs(),
+                "Circuit breaker opened"
+            );
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BreakerState> {
+        // The state stays consistent even if a holder panicked
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -0,0 +1,330 @@
+use hickory_resolver::Resolver;
+use rand::seq::IndexedRandom;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use tonic::transport::{Channel, ClientTlsConfig};
+
+use crate::error::ApiError;
+use crate::services::VerifierTls;
+use crate::services::verifier::proto::verifier_client::VerifierClient as GrpcClient;
+
+/// Where the replicas of a verifier backend come from
+#[derive(Debug, Clone)]
+pub enum EndpointSource {
+    /// Fixed list of URLs: `http://a:50051,http://b:50051`
+    Static(Vec<String>),
+    /// A/AAAA records, re-resolved periodically: `dns://verifier.internal:50051`
+    Dns { host: String, port: u16 },
+    /// SRV records, re-resolved periodically: `dns+srv://_grpc._tcp.verifier.internal`
+    Srv { name: String },
+}
+
+impl EndpointSource {
+    pub fn parse(spec: &str) -> Result<Self, String> {
+        if let Some(name) = spec.strip_prefix("dns+srv://") {
+            let name = name.trim_start_matches('/').trim_end_matches('/');
+            if name.is_empty() {
+                return Err("SRV name cannot be empty".to_string());
+            }
+            return Ok(Self::Srv {
+                name: name.to_string(),
+            });
+        }
+
+        if let Some(target) = spec.strip_prefix("dns://") {
+            let target = target.trim_start_matches('/').trim_end_matches('/');
+            let (host, port) = target
+                .rsplit_once(':')
+                .ok_or_else(|| format!("DNS target '{}' must be host:port", target))?;
+            let port = port
+                .parse()
+                .map_err(|_| format!("invalid port in DNS target '{}'", target))?;
+            return Ok(Self::Dns {
+                host: host.to_string(),
+                port,
+            });
+        }
+
+        let urls: Vec<String> = spec
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        if urls.is_empty() {
+            return Err("at least one endpoint URL is required".to_string());
+        }
+        Ok(Self::Static(urls))
+    }
+
+    fn is_dynamic(&self) -> bool {
+        !matches!(self, Self::Static(_))
+    }
+}
+
+/// A single verifier replica
+pub struct Endpoint {
+    pub url: String,
+    client: GrpcClient<Channel>,
+    outstanding: AtomicUsize,
+    healthy: AtomicBool,
+    /// When an endpoint ejected by a failed call is tried again without a health check
+    readmit_at: Mutex<Option<Instant>>,
+}
+
+impl Endpoint {
+    /// `domain` is the name to check the server certificate against, needed when `url`
+    /// holds a resolved IP address (defaults to the URL host)
+    fn new(url: String, domain: Option<&str>, tls: Option<&ClientTlsConfig>) -> Result<Self, ApiError> {
+        let invalid = |e: &dyn std::fmt::Display| {
+            ApiError::VerifierService(format!("Invalid verifier endpoint {}: {}", url, e))
+        };
+
+        let mut endpoint = Channel::from_shared(url.clone()).map_err(|e| invalid(&e))?;
+        if let Some(tls) = tls {
+            if endpoint.uri().scheme_str() != Some("https") {
+                return Err(invalid(&"TLS is configured, so the URL must use https://"));
+            }
+            let mut tls = tls.clone();
+            if let Some(domain) = domain {
+                tls = tls.domain_name(domain);
+            }
+            endpoint = endpoint.tls_config(tls).map_err(|e| invalid(&e))?;
+        }
+        let channel = endpoint.connect_lazy();
+
+        Ok(Self {
+            url,
+            client: GrpcClient::new(channel),
+            outstanding: AtomicUsize::new(0),
+            // Optimistic until the first health check says otherwise
+            healthy: AtomicBool::new(true),
+            readmit_at: Mutex::new(None),
+        })
+    }
+
+    pub fn client(&self) -> GrpcClient<Channel> {
+        self.client.clone()
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        if self.healthy.load(Ordering::Relaxed) {
+            return true;
+        }
+
+        let mut readmit_at = self.lock_readmit();
+        if readmit_at.is_some_and(|t| Instant::now() >= t) {
+            *readmit_at = None;
+            self.healthy.store(true, Ordering::Relaxed);
+            tracing::info!(endpoint = %self.url, "Re-admitting verifier endpoint after ejection");
+            return true;
+        }
+        false
+    }
+
+    /// Record a health check result. An unhealthy endpoint stays out until a later check
+    /// passes.
+    pub fn set_healthy(&self, healthy: bool) {
+        *self.lock_readmit() = None;
+        self.log_transition(self.healthy.swap(healthy, Ordering::Relaxed), healthy);
+    }
+
+    /// Take the endpoint out after a failed call. It comes back after `duration`, or
+    /// sooner if a health check passes, so the pool recovers without health checks.
+    pub fn eject_for(&self, duration: Duration) {
+        *self.lock_readmit() = Some(Instant::now() + duration);
+        self.log_transition(self.healthy.swap(false, Ordering::Relaxed), false);
+    }
+
+    fn log_transition(&self, was_healthy: bool, healthy: bool) {
+        if was_healthy && !healthy {
+            tracing::warn!(endpoint = %self.url, "Ejecting unhealthy verifier endpoint");
+        } else if !was_healthy && healthy {
+            tracing::info!(endpoint = %self.url, "Verifier endpoint healthy again");
+        }
+    }
+
+    fn lock_readmit(&self) -> std::sync::MutexGuard<'_, Option<Instant>> {
+        self.readmit_at.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Marks a request in flight on an endpoint until dropped
+pub struct Lease {
+    endpoint: Arc<Endpoint>,
+}
+
+impl Lease {
+    pub fn endpoint(&self) -> &Endpoint {
+        &self.endpoint
+    }
+}
+
+impl Drop for Lease {
+    fn drop(&mut self) {
+        self.endpoint.outstanding.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Replicas of one verifier backend, balanced by least outstanding requests
+#[derive(Clone)]
+pub struct EndpointPool {
+    name: String,
+    source: EndpointSource,
+    tls: Option<ClientTlsConfig>,
+    /// Fixed certificate name for every endpoint, from `VerifierTls::domain`
+    tls_domain: Option<String>,
+    endpoints: Arc<RwLock<Vec<Arc<Endpoint>>>>,
+}
+
+impl EndpointPool {
+    pub async fn new(
+        name: impl Into<String>,
+        spec: &str,
+        tls: Option<&VerifierTls>,
+    ) -> Result<Self, ApiError> {
+        let name = name.into();
+        let source = EndpointSource::parse(spec)
+            .map_err(|e| ApiError::VerifierService(format!("Invalid {} endpoints: {}", name, e)))?;
+
+        let pool = Self {
+            name,
+            source,
+            tls: tls.map(VerifierTls::client_config).transpose()?,
+            tls_domain: tls.and_then(|t| t.domain.clone()),
+            endpoints: Arc::new(RwLock::new(Vec::new())),
+        };
+
+        match &pool.source {
+            EndpointSource::Static(urls) => {
+                let endpoints = urls
+                    .iter()
+                    .map(|url| Endpoint::new(url.clone(), pool.tls_domain.as_deref(), pool.tls.as_ref()).map(Arc::new))
+                    .collect::<Result<Vec<_>, _>>()?;
+                *pool.write() = endpoints;
+            }
+            // Resolution failures are retried on every refresh
+            _ => pool.refresh().await,
+        }
+
+        Ok(pool)
+    }
+
+    pub fn endpoints(&self) -> Vec<Arc<Endpoint>> {
+        self.read().clone()
+    }
+
+    /// Pick the healthy endpoint with the fewest requests in flight, skipping `exclude`
+    /// unless nothing else is available.
+    pub fn acquire(&self, exclude: &[String]) -> Option<Lease> {
+        let endpoints = self.read();
+        let healthy: Vec<&Arc<Endpoint>> = endpoints.iter().filter(|e| e.is_healthy()).collect();
+
+        let fresh: Vec<&Arc<Endpoint>> = healthy
+            .iter()
+            .copied()
+            .filter(|e| !exclude.contains(&e.url))
+            .collect();
+        let candidates = if fresh.is_empty() { healthy } else { fresh };
+
+        let least = candidates
+            .iter()
+            .map(|e| e.outstanding.load(Ordering::Relaxed))
+            .min()?;
+        let tied: Vec<&Arc<Endpoint>> = candidates
+            .into_iter()
+            .filter(|e| e.outstanding.load(Ordering::Relaxed) == least)
+            .collect();
+
+        // Break ties randomly so idle replicas share the load
+        let endpoint = Arc::clone(*tied.choose(&mut rand::rng())?);
+        endpoint.outstanding.fetch_add(1, Ordering::Relaxed);
+        Some(Lease { endpoint })
+    }
+
+    /// Re-resolve DNS-based endpoints, keeping state for replicas that are still present
+    pub async fn refresh(&self) {
+        if !self.source.is_dynamic() {
+            return;
+        }
+
+        let targets = match self.resolve().await {
+            Ok(targets) if !targets.is_empty() => targets,
+            Ok(_) => {
+                tracing::warn!(backend = %self.name, "DNS returned no verifier endpoints, keeping previous set");
+                return;
+            }
+            Err(e) => {
+                tracing::warn!(backend = %self.name, error = %e, "Failed to resolve verifier endpoints");
+                return;
+            }
+        };
+
+        let mut endpoints = self.write();
+        let mut updated = Vec::with_capacity(targets.len());
+        for (url, domain) in targets {
+            if let Some(existing) = endpoints.iter().find(|e| e.url == url) {
+                updated.push(Arc::clone(existing));
+                continue;
+            }
+            let domain = self.tls_domain.as_deref().unwrap_or(&domain);
+            match Endpoint::new(url, Some(domain), self.tls.as_ref()) {
+                Ok(endpoint) => {
+                    tracing::info!(backend = %self.name, endpoint = %endpoint.url, "Added verifier endpoint");
+                    updated.push(Arc::new(endpoint));
+                }
+                Err(e) => tracing::warn!(backend = %self.name, error = %e, "Skipping verifier endpoint"),
+            }
+        }
+        for removed in endpoints.iter().filter(|e| !updated.iter().any(|u| u.url == e.url)) {
+            tracing::info!(backend = %self.name, endpoint = %removed.url, "Removed verifier endpoint");
+        }
+        *endpoints = updated;
+    }
+
+    /// Resolve DNS-based sources to endpoint URLs, each with the host name it was found under
+    async fn resolve(&self) -> Result<Vec<(String, String)>, String> {
+        let scheme = if self.tls.is_some() { "https" } else { "http" };
+
+        let mut targets = match &self.source {
+            EndpointSource::Static(_) => Vec::new(),
+            EndpointSource::Dns { host, port } => tokio::net::lookup_host((host.as_str(), *port))
+                .await
+                .map_err(|e| e.to_string())?
+                .map(|addr| (format!("{}://{}", scheme, addr), host.clone()))
+                .collect(),
+            EndpointSource::Srv { name } => {
+                let resolver = Resolver::builder_tokio().map_err(|e| e.to_string())?.build();
+                let records = resolver.srv_lookup(name.as_str()).await.map_err(|e| e.to_string())?;
+
+                let mut targets = Vec::new();
+                for record in records.iter() {
+                    let target = record.target().to_utf8();
+                    let ips = resolver
+                        .lookup_ip(target.as_str())
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    let host = target.trim_end_matches('.').to_string();
+                    for ip in ips.iter() {
+                        let addr = std::net::SocketAddr::new(ip, record.port());
+                        targets.push((format!("{}://{}", scheme, addr), host.clone()));
+                    }
+                }
+                targets
+            }
+        };
+
+        targets.sort();
+        targets.dedup_by(|a, b| a.0 == b.0);
+        Ok(targets)
+    }
+
+    // A poisoned lock still holds a usable endpoint list
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, Vec<Arc<Endpoint>>> {
+        self.endpoints.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, Vec<Arc<Endpoint>>> {
+        self.endpoints.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
@@ -1,9 +1,29 @@
 pub mod anchor_service;
+pub mod anchor_worker;
+pub mod chain_submitter;
+pub mod circuit_breaker;
+pub mod endpoint_pool;
 pub mod proof_registry;
+pub mod proof_store;
+pub mod registry_events;
+pub mod reverification;
+pub mod verification;
+pub mod verification_history;
 pub mod verifier;
 pub mod vk_service;
+pub mod webhook;
 
 pub use anchor_service::*;
+pub use anchor_worker::*;
+pub use chain_submitter::*;
+pub use circuit_breaker::*;
+pub use endpoint_pool::*;
 pub use proof_registry::*;
+pub use proof_store::*;
+pub use registry_events::*;
+pub use reverification::*;
+pub use verification::*;
+pub use verification_history::*;
 pub use verifier::*;
 pub use vk_service::*;
+pub use webhook::*;
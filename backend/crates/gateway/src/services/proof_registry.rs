@@ -1,18 +1,45 @@
 use sha2::{Digest, Sha256};
 
-/// Utility functions for proof hashing
-/// Note: Proof storage has been removed. These are hash utilities only.
-
-/// Generate a hash for a proof (used for proof_hash in responses)
-pub fn generate_proof_hash(proof: &serde_json::Value, public_inputs: &Option<Vec<String>>) -> String {
-    let mut hasher = Sha256::new();
-    hasher.update(proof.to_string().as_bytes());
-    if let Some(inputs) = public_inputs {
-        for input in inputs {
-            hasher.update(input.as_bytes());
+// Utility functions for proof hashing
+// Proof storage lives in proof_store; these are hash utilities only.
+
+/// Generate a hash for a proof (used for proof_hash in responses).
+/// JSON proofs are hashed in their compact encoding, so REST and gRPC submissions match.
+pub fn generate_proof_hash(proof: &[u8], public_inputs: &Option<Vec<String>>) -> String {
+    let mut hasher = ProofHasher::new();
+    hasher.update(proof);
+    hasher.finish(public_inputs)
+}
+
+/// Incremental form of `generate_proof_hash`, for proofs that arrive in chunks
+#[derive(Default)]
+pub struct ProofHasher {
+    hasher: Sha256,
+}
+
+impl ProofHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    pub fn finish(mut self, public_inputs: &Option<Vec<String>>) -> String {
+        if let Some(inputs) = public_inputs {
+            for input in inputs {
+                self.hasher.update(input.as_bytes());
+            }
         }
+        format!("0x{}", hex::encode(self.hasher.finalize()))
     }
-    format!("0x{}", hex::encode(hasher.finalize()))
+}
+
+/// Canonical form of a proof hash given by a client: lowercase with a `0x` prefix
+pub fn normalize_proof_hash(hash: &str) -> String {
+    let hex = hash.strip_prefix("0x").unwrap_or(hash);
+    format!("0x{}", hex.to_ascii_lowercase())
 }
 
 /// Hash public inputs for the response
@@ -0,0 +1,595 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::path::Path;
+use object_store::{GetResult, ObjectStore, PutPayload, WriteMultipart};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_stream::{Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+
+/// Parts uploaded at the same time while streaming a proof to the blob store
+const UPLOAD_CONCURRENCY: usize = 4;
+
+/// Orphaned blobs removed per purge run
+const PURGE_BATCH_SIZE: i64 = 500;
+
+/// Staged uploads older than this were abandoned by a crashed gateway
+const STAGING_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where proof blobs are kept
+#[derive(Debug, Clone)]
+pub enum BlobBackend {
+    Filesystem {
+        root: PathBuf,
+    },
+    /// AWS S3 or any S3-compatible service (MinIO, R2, ...)
+    S3 {
+        bucket: String,
+        region: String,
+        /// Custom endpoint for S3-compatible services
+        endpoint: Option<String>,
+        /// Falls back to the standard AWS credential chain when unset
+        access_key_id: Option<String>,
+        secret_access_key: Option<String>,
+        allow_http: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct ProofStoreConfig {
+    pub backend: BlobBackend,
+    /// Days to keep proofs of users without their own retention policy
+    pub default_retention_days: u32,
+    pub purge_interval: Duration,
+}
+
+/// Which blob of a stored proof to read
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProofPart {
+    Proof,
+    PublicInputs,
+}
+
+/// A blob opened for download
+pub struct BlobDownload {
+    pub size: u64,
+    pub stream: std::pin::Pin<Box<dyn Stream<Item = Result<Bytes, ApiError>> + Send>>,
+}
+
+/// A proof being written to staging while it streams through the gateway.
+/// Dropping it without `finish` discards the staged data.
+pub struct BlobUpload {
+    staging: Path,
+    writer: WriteMultipart,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl BlobUpload {
+    pub async fn write(&mut self, chunk: &[u8]) -> Result<(), ApiError> {
+        self.writer
+            .wait_for_capacity(UPLOAD_CONCURRENCY)
+            .await
+            .map_err(storage_error)?;
+        self.writer.write(chunk);
+        self.hasher.update(chunk);
+        self.size += chunk.len() as u64;
+        Ok(())
+    }
+}
+
+/// Stored proofs as content-addressed blobs, with per-user retention.
+/// Blob rows in Postgres track which blobs are referenced; the blobs themselves
+/// live on the filesystem or in S3.
+#[derive(Clone)]
+pub struct ProofStore {
+    db: PgPool,
+    blobs: Arc<dyn ObjectStore>,
+    default_retention_days: u32,
+    purge_interval: Duration,
+}
+
+#[derive(sqlx::FromRow)]
+struct StoredBlob {
+    digest: String,
+    size_bytes: i64,
+}
+
+impl ProofStore {
+    pub fn new(db: PgPool, config: &ProofStoreConfig) -> Result<Self, ApiError> {
+        let blobs: Arc<dyn ObjectStore> = match &config.backend {
+            BlobBackend::Filesystem { root } => {
+                std::fs::create_dir_all(root).map_err(|e| {
+                    ApiError::ProofStore(format!("Cannot create {}: {}", root.display(), e))
+                })?;
+                Arc::new(LocalFileSystem::new_with_prefix(root).map_err(storage_error)?)
+            }
+            BlobBackend::S3 {
+                bucket,
+                region,
+                endpoint,
+                access_key_id,
+                secret_access_key,
+                allow_http,
+            } => {
+                let mut builder = AmazonS3Builder::from_env()
+                    .with_bucket_name(bucket)
+                    .with_region(region)
+                    .with_allow_http(*allow_http);
+                if let Some(endpoint) = endpoint {
+                    // S3-compatible services generally only support path-style requests
+                    builder = builder
+                        .with_endpoint(endpoint)
+                        .with_virtual_hosted_style_request(false);
+                }
+                if let (Some(key_id), Some(secret)) = (access_key_id, secret_access_key) {
+                    builder = builder
+                        .with_access_key_id(key_id)
+                        .with_secret_access_key(secret);
+                }
+                Arc::new(builder.build().map_err(storage_error)?)
+            }
+        };
+
+        Ok(Self {
+            db,
+            blobs,
+            default_retention_days: config.default_retention_days,
+            purge_interval: config.purge_interval,
+        })
+    }
+
+    /// Store a proof submitted by `user_id`. Returns false when the user's retention
+    /// policy says not to keep proofs.
+    pub async fn store(
+        &self,
+        user_id: Uuid,
+        proof_hash: &str,
+        proof: Bytes,
+        public_inputs: &Option<Vec<String>>,
+    ) -> Result<bool, ApiError> {
+        let Some(expires_at) = self.expiry_for(user_id).await? else {
+            return Ok(false);
+        };
+
+        let proof_digest = content_digest(&proof);
+        let proof_size = proof.len() as u64;
+        let inputs = encode_public_inputs(public_inputs)?;
+
+        self.record(user_id, proof_hash, (&proof_digest, proof_size), inputs.as_ref(), expires_at)
+            .await?;
+
+        let uploaded = async {
+            self.put_blob(&proof_digest, proof).await?;
+            if let Some((digest, data)) = inputs {
+                self.put_blob(&digest, data).await?;
+            }
+            Ok(())
+        }
+        .await;
+        if uploaded.is_err() {
+            self.forget(user_id, proof_hash).await;
+        }
+        uploaded.map(|_| true)
+    }
+
+    /// Start staging a streamed proof. None when the user's retention policy
+    /// says not to keep proofs.
+    pub async fn begin_upload(&self, user_id: Uuid) -> Result<Option<BlobUpload>, ApiError> {
+        if self.expiry_for(user_id).await?.is_none() {
+            return Ok(None);
+        }
+
+        let staging = Path::from(format!("staging/{}", Uuid::new_v4()));
+        let upload = self
+            .blobs
+            .put_multipart(&staging)
+            .await
+            .map_err(storage_error)?;
+
+        Ok(Some(BlobUpload {
+            staging,
+            writer: WriteMultipart::new(upload),
+            hasher: Sha256::new(),
+            size: 0,
+        }))
+    }
+
+    /// Move a staged proof to its content address and record it for `user_id`
+    pub async fn finish_upload(
+        &self,
+        user_id: Uuid,
+        proof_hash: &str,
+        upload: BlobUpload,
+        public_inputs: &Option<Vec<String>>,
+    ) -> Result<bool, ApiError> {
+        let staging = upload.staging;
+        upload.writer.finish().await.map_err(storage_error)?;
+
+        let result = async {
+            let Some(expires_at) = self.expiry_for(user_id).await? else {
+                return Ok(false);
+            };
+
+            let proof_digest = hex::encode(upload.hasher.finalize());
+            let inputs = encode_public_inputs(public_inputs)?;
+            self.record(user_id, proof_hash, (&proof_digest, upload.size), inputs.as_ref(), expires_at)
+                .await?;
+
+            let uploaded = async {
+                self.blobs
+                    .copy(&staging, &blob_path(&proof_digest))
+                    .await
+                    .map_err(storage_error)?;
+                if let Some((digest, data)) = inputs {
+                    self.put_blob(&digest, data).await?;
+                }
+                Ok(())
+            }
+            .await;
+            if uploaded.is_err() {
+                self.forget(user_id, proof_hash).await;
+            }
+            uploaded.map(|_| true)
+        }
+        .await;
+
+        if let Err(e) = self.blobs.delete(&staging).await {
+            tracing::warn!(path = %staging, error = %e, "Failed to delete staged proof");
+        }
+        result
+    }
+
+    /// Open a stored proof for download. Only users who stored the proof (and admins)
+    /// can read it.
+    pub async fn open(
+        &self,
+        proof_hash: &str,
+        part: ProofPart,
+        user_id: Uuid,
+        is_admin: bool,
+    ) -> Result<Option<BlobDownload>, ApiError> {
+        let column = match part {
+            ProofPart::Proof => "proof_digest",
+            ProofPart::PublicInputs => "public_inputs_digest",
+        };
+        let blob = sqlx::query_as::<_, StoredBlob>(&format!(
+            r#"SELECT b.digest, b.size_bytes
+               FROM stored_proofs s
+               JOIN proof_blobs b ON b.digest = s.{column}
+               WHERE s.proof_hash = $1
+                 AND (s.user_id = $2 OR $3)
+                 AND (s.expires_at IS NULL OR s.expires_at > NOW())
+               LIMIT 1"#
+        ))
+        .bind(proof_hash)
+        .bind(user_id)
+        .bind(is_admin)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some(blob) = blob else {
+            return Ok(None);
+        };
+
+        let Some(result) = self.get_blob(&blob.digest).await? else {
+            return Ok(None);
+        };
+
+        Ok(Some(BlobDownload {
+            size: blob.size_bytes as u64,
+            stream: Box::pin(result.into_stream().map(|chunk| chunk.map_err(storage_error))),
+        }))
+    }
+
+    /// Read back a proof `user_id` stored, with its public inputs
+    pub async fn load(
+        &self,
+        proof_hash: &str,
+        user_id: Uuid,
+    ) -> Result<Option<(Bytes, Option<Vec<String>>)>, ApiError> {
+        let digests: Option<(String, Option<String>)> = sqlx::query_as(
+            r#"SELECT proof_digest, public_inputs_digest
+               FROM stored_proofs
+               WHERE proof_hash = $1 AND user_id = $2
+                 AND (expires_at IS NULL OR expires_at > NOW())"#,
+        )
+        .bind(proof_hash)
+        .bind(user_id)
+        .fetch_optional(&self.db)
+        .await?;
+
+        let Some((proof_digest, inputs_digest)) = digests else {
+            return Ok(None);
+        };
+        let Some(proof) = self.read_blob(&proof_digest).await? else {
+            return Ok(None);
+        };
+        let public_inputs = match inputs_digest {
+            Some(digest) => match self.read_blob(&digest).await? {
+                Some(data) => Some(serde_json::from_slice(&data).map_err(|e| {
+                    ApiError::ProofStore(format!("Invalid public inputs blob {}: {}", digest, e))
+                })?),
+                None => return Ok(None),
+            },
+            None => None,
+        };
+
+        Ok(Some((proof, public_inputs)))
+    }
+
+    async fn read_blob(&self, digest: &str) -> Result<Option<Bytes>, ApiError> {
+        match self.get_blob(digest).await? {
+            Some(result) => Ok(Some(result.bytes().await.map_err(storage_error)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// A recorded blob that is missing from the backend is treated as not stored
+    async fn get_blob(&self, digest: &str) -> Result<Option<GetResult>, ApiError> {
+        match self.blobs.get(&blob_path(digest)).await {
+            Ok(result) => Ok(Some(result)),
+            Err(object_store::Error::NotFound { .. }) => {
+                tracing::error!(digest, "Stored proof blob is missing");
+                Ok(None)
+            }
+            Err(e) => Err(storage_error(e)),
+        }
+    }
+
+    /// Set a user's retention in days (None restores the default, 0 stops storing
+    /// their proofs) and re-date the proofs they already stored. Stopping storage keeps
+    /// the proofs already stored until they expire under the previous policy.
+    pub async fn set_retention(&self, user_id: Uuid, days: Option<u32>) -> Result<u32, ApiError> {
+        let updated = sqlx::query("UPDATE users SET proof_retention_days = $2 WHERE id = $1")
+            .bind(user_id)
+            .bind(days.map(|d| d as i32))
+            .execute(&self.db)
+            .await?;
+        if updated.rows_affected() == 0 {
+            return Err(ApiError::UserNotFound);
+        }
+
+        let effective = days.unwrap_or(self.default_retention_days);
+        if effective == 0 {
+            return Ok(effective);
+        }
+        sqlx::query(
+            r#"UPDATE stored_proofs
+               SET expires_at = created_at + make_interval(days => $2)
+               WHERE user_id = $1"#,
+        )
+        .bind(user_id)
+        .bind(effective as i32)
+        .execute(&self.db)
+        .await?;
+
+        Ok(effective)
+    }
+
+    /// Retention in days that applies to a user
+    pub async fn retention_for(&self, user_id: Uuid) -> Result<u32, ApiError> {
+        let days: Option<i32> =
+            sqlx::query_scalar("SELECT proof_retention_days FROM users WHERE id = $1")
+                .bind(user_id)
+                .fetch_optional(&self.db)
+                .await?
+                .ok_or(ApiError::UserNotFound)?;
+
+        Ok(days.map_or(self.default_retention_days, |d| d.max(0) as u32))
+    }
+
+    /// Purge expired proofs on a fixed interval
+    pub fn spawn_purge(&self) {
+        if self.purge_interval.is_zero() {
+            return;
+        }
+
+        let store = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(store.purge_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = store.purge().await {
+                    tracing::warn!(error = %e, "Proof store purge failed");
+                }
+            }
+        });
+    }
+
+    /// Drop expired proof records, then delete blobs no record refers to anymore
+    pub async fn purge(&self) -> Result<(), ApiError> {
+        let expired = sqlx::query("DELETE FROM stored_proofs WHERE expires_at <= NOW()")
+            .execute(&self.db)
+            .await?
+            .rows_affected();
+
+        let orphans: Vec<String> = sqlx::query_scalar(
+            r#"SELECT digest FROM proof_blobs b
+               WHERE NOT EXISTS (
+                   SELECT 1 FROM stored_proofs s
+                   WHERE s.proof_digest = b.digest OR s.public_inputs_digest = b.digest
+               )
+               LIMIT $1"#,
+        )
+        .bind(PURGE_BATCH_SIZE)
+        .fetch_all(&self.db)
+        .await?;
+
+        let mut deleted = 0;
+        for digest in orphans {
+            // The row lock keeps a concurrent store of the same content waiting until
+            // the blob is gone, so it re-uploads instead of relying on a deleted blob
+            let mut tx = self.db.begin().await?;
+            let removed: Option<String> = sqlx::query_scalar(
+                r#"DELETE FROM proof_blobs b
+                   WHERE digest = $1 AND NOT EXISTS (
+                       SELECT 1 FROM stored_proofs s
+                       WHERE s.proof_digest = b.digest OR s.public_inputs_digest = b.digest
+                   )
+                   RETURNING digest"#,
+            )
+            .bind(&digest)
+            .fetch_optional(&mut *tx)
+            .await?;
+            if removed.is_none() {
+                continue;
+            }
+
+            match self.blobs.delete(&blob_path(&digest)).await {
+                Ok(()) | Err(object_store::Error::NotFound { .. }) => {
+                    tx.commit().await?;
+                    deleted += 1;
+                }
+                Err(e) => {
+                    tracing::warn!(digest = %digest, error = %e, "Failed to delete proof blob");
+                }
+            }
+        }
+
+        self.purge_staging().await;
+
+        if expired > 0 || deleted > 0 {
+            tracing::info!(expired, blobs_deleted = deleted, "Purged stored proofs");
+        }
+        Ok(())
+    }
+
+    /// Remove staged uploads abandoned by a gateway that stopped mid-request
+    async fn purge_staging(&self) {
+        let cutoff = Utc::now() - STAGING_MAX_AGE;
+        let mut staged = self.blobs.list(Some(&Path::from("staging")));
+        while let Some(meta) = staged.next().await {
+            match meta {
+                Ok(meta) if meta.last_modified < cutoff => {
+                    if let Err(e) = self.blobs.delete(&meta.location).await {
+                        tracing::warn!(path = %meta.location, error = %e, "Failed to delete staged proof");
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => {
+                    tracing::warn!(error = %e, "Failed to list staged proofs");
+                    return;
+                }
+            }
+        }
+    }
+
+    /// When a proof stored now for `user_id` expires, or None if it should not be stored
+    async fn expiry_for(&self, user_id: Uuid) -> Result<Option<DateTime<Utc>>, ApiError> {
+        let days = self.retention_for(user_id).await?;
+        if days == 0 {
+            return Ok(None);
+        }
+        Ok(Some(Utc::now() + chrono::Duration::days(days as i64)))
+    }
+
+    /// Record blob and proof rows before the blobs are uploaded, so a concurrent
+    /// purge never sees the blobs unreferenced
+    async fn record(
+        &self,
+        user_id: Uuid,
+        proof_hash: &str,
+        (proof_digest, proof_size): (&str, u64),
+        inputs: Option<&(String, Bytes)>,
+        expires_at: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        let mut tx = self.db.begin().await?;
+
+        let mut blobs = vec![(proof_digest, proof_size)];
+        if let Some((digest, data)) = inputs {
+            blobs.push((digest.as_str(), data.len() as u64));
+        }
+        for (digest, size) in blobs {
+            sqlx::query(
+                r#"INSERT INTO proof_blobs (digest, size_bytes) VALUES ($1, $2)
+                   ON CONFLICT (digest) DO UPDATE SET size_bytes = EXCLUDED.size_bytes"#,
+            )
+            .bind(digest)
+            .bind(size as i64)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query(
+            r#"INSERT INTO stored_proofs
+               (proof_hash, user_id, proof_digest, public_inputs_digest, expires_at)
+               VALUES ($1, $2, $3, $4, $5)
+               ON CONFLICT (proof_hash, user_id) DO UPDATE SET
+                   expires_at = GREATEST(stored_proofs.expires_at, EXCLUDED.expires_at)"#,
+        )
+        .bind(proof_hash)
+        .bind(user_id)
+        .bind(proof_digest)
+        .bind(inputs.map(|(digest, _)| digest))
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Undo `record` after a failed upload; the purge job removes the blob rows
+    async fn forget(&self, user_id: Uuid, proof_hash: &str) {
+        if let Err(e) = sqlx::query(
+            "DELETE FROM stored_proofs WHERE proof_hash = $1 AND user_id = $2",
+        )
+        .bind(proof_hash)
+        .bind(user_id)
+        .execute(&self.db)
+        .await
+        {
+            tracing::warn!(proof_hash, error = %e, "Failed to remove stored proof record");
+        }
+    }
+
+    async fn put_blob(&self, digest: &str, data: Bytes) -> Result<(), ApiError> {
+        let path = blob_path(digest);
+        // Content-addressed, so an existing blob already holds these bytes
+        match self.blobs.head(&path).await {
+            Ok(_) => return Ok(()),
+            Err(object_store::Error::NotFound { .. }) => {}
+            Err(e) => return Err(storage_error(e)),
+        }
+        self.blobs
+            .put(&path, PutPayload::from_bytes(data))
+            .await
+            .map_err(storage_error)?;
+        Ok(())
+    }
+}
+
+/// SHA-256 of the blob content, hex encoded
+fn content_digest(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Blobs are sharded by the first digest byte to keep directories small
+fn blob_path(digest: &str) -> Path {
+    Path::from(format!("blobs/{}/{}", &digest[..2], digest))
+}
+
+/// Public inputs are stored as a JSON array
+fn encode_public_inputs(
+    public_inputs: &Option<Vec<String>>,
+) -> Result<Option<(String, Bytes)>, ApiError> {
+    public_inputs
+        .as_ref()
+        .map(|inputs| {
+            let data = serde_json::to_vec(inputs).map_err(|_| ApiError::Internal)?;
+            Ok((content_digest(&data), Bytes::from(data)))
+        })
+        .transpose()
+}
+
+fn storage_error(error: object_store::Error) -> ApiError {
+    ApiError::ProofStore(error.to_string())
+}
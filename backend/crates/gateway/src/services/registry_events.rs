@@ -0,0 +1,242 @@
+use serde::Serialize;
+use sqlx::postgres::PgListener;
+use sqlx::{PgExecutor, PgPool};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{broadcast, mpsc};
+
+use crate::error::ApiError;
+use crate::models::RegistryEvent;
+
+/// Postgres channel announcing new `registry_events` rows by id
+const CHANNEL: &str = "registry_events";
+
+const EVENT_COLUMNS: &str = "id, event_type, prover, data, created_at";
+
+/// Events a slow stream may fall behind before it is closed
+const BROADCAST_CAPACITY: usize = 1024;
+
+/// Events buffered per stream
+const STREAM_BUFFER: usize = 64;
+
+/// Events read per query when a stream resumes
+const REPLAY_PAGE: i64 = 500;
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(2);
+
+/// Record a registry change and announce it to every gateway replica.
+/// Runs on the caller's executor; the announcement is only sent if the transaction
+/// commits. Returns the event id.
+///
+/// Streams resume after the last id they saw, so ids must become visible in order. A
+/// transaction-scoped advisory lock is taken before the id is drawn, which makes
+/// transactions that publish events commit one after another in id order.
+pub async fn publish_event<'e, E, T>(
+    executor: E,
+    event_type: &str,
+    prover: &str,
+    data: &T,
+) -> Result<i64, ApiError>
+where
+    E: PgExecutor<'e>,
+    T: Serialize,
+{
+    let data = serde_json::to_value(data).map_err(|e| {
+        tracing::error!(event_type, error = %e, "Failed to serialize registry event");
+        ApiError::Internal
+    })?;
+
+    let id: i64 = sqlx::query_scalar(
+        r#"WITH ordered AS (
+               SELECT pg_advisory_xact_lock(hashtext('registry_events'))
+           ),
+           event AS (
+               INSERT INTO registry_events (event_type, prover, data)
+               SELECT $1, $2, $3 FROM ordered
+               RETURNING id
+           )
+           SELECT id, pg_notify($4, id::text) FROM event"#,
+    )
+    .bind(event_type)
+    .bind(prover)
+    .bind(&data)
+    .bind(CHANNEL)
+    .fetch_one(executor)
+    .await?;
+
+    Ok(id)
+}
+
+/// Fans registry events out to this replica's SSE streams. Every replica listens on
+/// the Postgres channel, so a change made through any replica reaches all streams.
+#[derive(Clone)]
+pub struct RegistryEvents {
+    pool: PgPool,
+    sender: broadcast::Sender<Arc<RegistryEvent>>,
+}
+
+impl RegistryEvents {
+    pub fn new(pool: PgPool) -> Self {
+        let (sender, _) = broadcast::channel(BROADCAST_CAPACITY);
+        Self { pool, sender }
+    }
+
+    /// Events after `after`, oldest first
+    pub async fn since(
+        &self,
+        after: i64,
+        prover: Option<&str>,
+        limit: i64,
+    ) -> Result<Vec<RegistryEvent>, ApiError> {
+        let events = sqlx::query_as::<_, RegistryEvent>(&format!(
+            r#"SELECT {EVENT_COLUMNS} FROM registry_events
+               WHERE id > $1 AND ($2::text IS NULL OR prover = $2)
+               ORDER BY id
+               LIMIT $3"#
+        ))
+        .bind(after)
+        .bind(prover)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// Stream events for `prover` (all provers if `None`). With `after`, events recorded
+    /// since that id are replayed first. The stream ends if it falls too far behind, so
+    /// the client reconnects and catches up from the database.
+    pub fn stream(&self, after: Option<i64>, prover: Option<String>) -> mpsc::Receiver<Arc<RegistryEvent>> {
+        // Subscribe before replaying so nothing committed in between is missed
+        let mut live = self.sender.subscribe();
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let service = self.clone();
+
+        tokio::spawn(async move {
+            let mut replayed_up_to = after.unwrap_or(0);
+            if let Some(after) = after {
+                let mut cursor = after;
+                loop {
+                    let page = match service.since(cursor, prover.as_deref(), REPLAY_PAGE).await {
+                        Ok(page) => page,
+                        Err(e) => {
+                            tracing::warn!(error = %e, "Failed to replay registry events");
+                            return;
+                        }
+                    };
+                    let full = page.len() as i64 == REPLAY_PAGE;
+                    for event in page {
+                        cursor = event.id;
+                        if sender.send(Arc::new(event)).await.is_err() {
+                            return;
+                        }
+                    }
+                    if !full {
+                        break;
+                    }
+                }
+                replayed_up_to = cursor;
+            }
+
+            loop {
+                let event = tokio::select! {
+                    _ = sender.closed() => return,
+                    event = live.recv() => event,
+                };
+                match event {
+                    Ok(event) => {
+                        if event.id <= replayed_up_to
+                            || prover.as_deref().is_some_and(|p| p != event.prover)
+                        {
+                            continue;
+                        }
+                        if sender.send(event).await.is_err() {
+                            return;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::debug!(skipped, "Closing lagging event stream");
+                        return;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return,
+                }
+            }
+        });
+
+        receiver
+    }
+
+    /// Follow the Postgres channel in the background, reconnecting when the connection
+    /// drops. Events recorded while disconnected are read back from the table.
+    pub fn spawn_listener(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut last_id = None;
+            loop {
+                if let Err(e) = service.listen(&mut last_id).await {
+                    tracing::warn!(error = %e, "Registry event listener disconnected");
+                }
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+
+    async fn listen(&self, last_id: &mut Option<i64>) -> Result<(), ApiError> {
+        let mut listener = PgListener::connect_with(&self.pool).await?;
+        listener.listen(CHANNEL).await?;
+
+        // Catch up after LISTEN so nothing falls between; events read here may also be
+        // announced, so those are skipped once
+        let mut caught_up = HashSet::new();
+        match *last_id {
+            Some(_) => loop {
+                let page = self.since(last_id.unwrap_or(0), None, REPLAY_PAGE).await?;
+                let full = page.len() as i64 == REPLAY_PAGE;
+                for event in page {
+                    caught_up.insert(event.id);
+                    self.broadcast(last_id, event);
+                }
+                if !full {
+                    break;
+                }
+            },
+            None => {
+                let latest: Option<i64> = sqlx::query_scalar("SELECT MAX(id) FROM registry_events")
+                    .fetch_one(&self.pool)
+                    .await?;
+                *last_id = Some(latest.unwrap_or(0));
+            }
+        }
+
+        // try_recv returns None when the connection is lost
+        while let Some(notification) = listener.try_recv().await? {
+            let Ok(id) = notification.payload().parse::<i64>() else {
+                tracing::warn!(payload = notification.payload(), "Ignoring malformed registry event notification");
+                continue;
+            };
+            if caught_up.remove(&id) {
+                continue;
+            }
+
+            let event = sqlx::query_as::<_, RegistryEvent>(&format!(
+                "SELECT {EVENT_COLUMNS} FROM registry_events WHERE id = $1"
+            ))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+            if let Some(event) = event {
+                self.broadcast(last_id, event);
+            }
+        }
+
+        tracing::warn!("Registry event listener lost its connection");
+        Ok(())
+    }
+
+    fn broadcast(&self, last_id: &mut Option<i64>, event: RegistryEvent) {
+        *last_id = Some(last_id.unwrap_or(0).max(event.id));
+        // Fails only when no stream is open
+        let _ = self.sender.send(Arc::new(event));
+    }
+}
@@ -0,0 +1,293 @@
+use sqlx::PgPool;
+use std::time::Duration;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{
+    CreateReverificationJob, ReverificationJob, ReverificationResult, VerificationRecord,
+};
+use crate::services::{ProofStore, VerificationHistory, VerificationService, VkService};
+
+const JOB_COLUMNS: &str = "id, triggered_by, reason, vk_id, prover_version, status, total, \
+    processed, skipped, discrepancies, error, created_at, finished_at";
+
+/// Jobs listed by `list_jobs`
+const JOB_LIST_LIMIT: i64 = 50;
+
+/// A running job without progress for this long was lost with the gateway running it.
+/// Long enough for one proof's verifier call with all its retries.
+const STALE_JOB_AFTER: Duration = Duration::from_secs(600);
+
+const STALE_JOB_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Re-runs recorded verifications on the current verifier after a VK is revoked or a
+/// new verifier version becomes active, and reports proofs whose outcome changed
+#[derive(Clone)]
+pub struct ReverificationService {
+    pool: PgPool,
+    vk_service: VkService,
+    verification_service: VerificationService,
+    history: VerificationHistory,
+    proof_store: Option<ProofStore>,
+}
+
+impl ReverificationService {
+    pub fn new(
+        pool: PgPool,
+        vk_service: VkService,
+        verification_service: VerificationService,
+        history: VerificationHistory,
+        proof_store: Option<ProofStore>,
+    ) -> Self {
+        Self {
+            pool,
+            vk_service,
+            verification_service,
+            history,
+            proof_store,
+        }
+    }
+
+    /// Create a job for every proof in scope and run it in the background
+    pub async fn start(
+        &self,
+        triggered_by: Uuid,
+        request: CreateReverificationJob,
+    ) -> Result<ReverificationJob, ApiError> {
+        let Some(proof_store) = self.proof_store.clone() else {
+            return Err(ApiError::BadRequest(
+                "Re-verification needs proof storage to be enabled".to_string(),
+            ));
+        };
+        if request.vk_id.is_none() && request.prover_version.is_none() {
+            return Err(ApiError::BadRequest(
+                "Either vk_id or prover_version is required".to_string(),
+            ));
+        }
+
+        let vk_id = match &request.vk_id {
+            Some(id) => {
+                let prover = request.prover.as_deref().unwrap_or("zisk");
+                let vk = self
+                    .vk_service
+                    .get_vk(id, Some(prover))
+                    .await?
+                    .ok_or(ApiError::VkNotFound)?;
+                Some(vk.id)
+            }
+            None => None,
+        };
+
+        let records = self
+            .history
+            .latest_in_scope(vk_id, request.prover_version.as_deref())
+            .await?;
+
+        let job = sqlx::query_as::<_, ReverificationJob>(&format!(
+            r#"INSERT INTO reverification_jobs (triggered_by, reason, vk_id, prover_version, total)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING {JOB_COLUMNS}"#
+        ))
+        .bind(triggered_by)
+        .bind(&request.reason)
+        .bind(vk_id)
+        .bind(&request.prover_version)
+        .bind(records.len() as i32)
+        .fetch_one(&self.pool)
+        .await?;
+
+        tracing::info!(
+            job_id = %job.id,
+            total = records.len(),
+            vk_id = ?vk_id,
+            prover_version = ?request.prover_version,
+            "Starting re-verification job"
+        );
+
+        let service = self.clone();
+        let job_id = job.id;
+        tokio::spawn(async move {
+            let outcome = service.run(job_id, &proof_store, records).await;
+            if let Err(e) = service.finish(job_id, outcome.as_ref().err()).await {
+                tracing::error!(job_id = %job_id, error = %e, "Failed to finish re-verification job");
+            }
+        });
+
+        Ok(job)
+    }
+
+    /// Mark jobs whose gateway stopped before finishing them as failed, at startup and
+    /// then periodically. Jobs of other replicas keep their heartbeat and are left alone.
+    pub fn spawn_stale_job_check(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(STALE_JOB_CHECK_INTERVAL);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.fail_stale_jobs().await {
+                    tracing::warn!(error = %e, "Failed to check for stale re-verification jobs");
+                }
+            }
+        });
+    }
+
+    async fn fail_stale_jobs(&self) -> Result<(), ApiError> {
+        let failed: Vec<Uuid> = sqlx::query_scalar(
+            r#"UPDATE reverification_jobs
+               SET status = 'failed', finished_at = NOW(),
+                   error = 'Job stopped making progress; the gateway running it was likely restarted'
+               WHERE status = 'running' AND heartbeat_at < NOW() - make_interval(secs => $1)
+               RETURNING id"#,
+        )
+        .bind(STALE_JOB_AFTER.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await?;
+
+        for job_id in failed {
+            tracing::warn!(job_id = %job_id, "Marked stale re-verification job as failed");
+        }
+        Ok(())
+    }
+
+    pub async fn get_job(&self, id: Uuid) -> Result<Option<ReverificationJob>, ApiError> {
+        let job = sqlx::query_as::<_, ReverificationJob>(&format!(
+            "SELECT {JOB_COLUMNS} FROM reverification_jobs WHERE id = $1"
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(job)
+    }
+
+    /// Most recent jobs first
+    pub async fn list_jobs(&self) -> Result<Vec<ReverificationJob>, ApiError> {
+        let jobs = sqlx::query_as::<_, ReverificationJob>(&format!(
+            "SELECT {JOB_COLUMNS} FROM reverification_jobs ORDER BY created_at DESC LIMIT $1"
+        ))
+        .bind(JOB_LIST_LIMIT)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(jobs)
+    }
+
+    /// Proofs of a job that were valid before and are not anymore
+    pub async fn discrepancies(&self, job_id: Uuid) -> Result<Vec<ReverificationResult>, ApiError> {
+        let results = sqlx::query_as::<_, ReverificationResult>(
+            r#"SELECT proof_hash, previous_verification_id, verification_id, previous_valid,
+                      valid, skipped_reason, created_at
+               FROM reverification_results
+               WHERE job_id = $1 AND previous_valid AND NOT valid
+               ORDER BY created_at"#,
+        )
+        .bind(job_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(results)
+    }
+
+    async fn run(
+        &self,
+        job_id: Uuid,
+        proof_store: &ProofStore,
+        records: Vec<VerificationRecord>,
+    ) -> Result<(), ApiError> {
+        for previous in records {
+            let outcome = match proof_store.load(&previous.proof_hash, previous.user_id).await {
+                Ok(Some((proof, public_inputs))) => self
+                    .verification_service
+                    .reverify(&previous, proof.to_vec(), public_inputs, job_id)
+                    .await
+                    .map_err(|e| e.to_string()),
+                Ok(None) => Err("proof is no longer stored".to_string()),
+                Err(e) => Err(e.to_string()),
+            };
+
+            let (verification, skipped_reason) = match outcome {
+                Ok(record) => (Some(record), None),
+                Err(reason) => {
+                    tracing::warn!(
+                        job_id = %job_id,
+                        proof_hash = %previous.proof_hash,
+                        reason = %reason,
+                        "Skipping proof in re-verification"
+                    );
+                    (None, Some(reason))
+                }
+            };
+            let valid = verification.as_ref().map(|v| v.valid);
+            let discrepancy = previous.valid && valid == Some(false);
+            if discrepancy {
+                tracing::warn!(
+                    job_id = %job_id,
+                    proof_hash = %previous.proof_hash,
+                    "Previously valid proof no longer verifies"
+                );
+            }
+
+            let mut tx = self.pool.begin().await?;
+            sqlx::query(
+                r#"INSERT INTO reverification_results
+                   (job_id, proof_hash, user_id, previous_verification_id, verification_id,
+                    previous_valid, valid, skipped_reason)
+                   VALUES ($1, $2, $3, $4, $5, $6, $7, $8)"#,
+            )
+            .bind(job_id)
+            .bind(&previous.proof_hash)
+            .bind(previous.user_id)
+            .bind(previous.id)
+            .bind(verification.as_ref().map(|v| v.id))
+            .bind(previous.valid)
+            .bind(valid)
+            .bind(&skipped_reason)
+            .execute(&mut *tx)
+            .await?;
+            sqlx::query(
+                r#"UPDATE reverification_jobs SET
+                       processed = processed + 1,
+                       skipped = skipped + $2,
+                       discrepancies = discrepancies + $3,
+                       heartbeat_at = NOW()
+                   WHERE id = $1"#,
+            )
+            .bind(job_id)
+            .bind(skipped_reason.is_some() as i32)
+            .bind(discrepancy as i32)
+            .execute(&mut *tx)
+            .await?;
+            tx.commit().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn finish(&self, job_id: Uuid, error: Option<&ApiError>) -> Result<(), ApiError> {
+        let job = sqlx::query_as::<_, ReverificationJob>(&format!(
+            r#"UPDATE reverification_jobs
+               SET status = $2, error = $3, finished_at = NOW()
+               WHERE id = $1 AND status = 'running'
+               RETURNING {JOB_COLUMNS}"#
+        ))
+        .bind(job_id)
+        .bind(if error.is_some() { "failed" } else { "completed" })
+        .bind(error.map(|e| e.to_string()))
+        .fetch_optional(&self.pool)
+        .await?;
+        let Some(job) = job else {
+            tracing::warn!(job_id = %job_id, "Re-verification job finished after it was marked stale");
+            return Ok(());
+        };
+
+        tracing::info!(
+            job_id = %job.id,
+            status = %job.status,
+            processed = job.processed,
+            skipped = job.skipped,
+            discrepancies = job.discrepancies,
+            "Re-verification job finished"
+        );
+        Ok(())
+    }
+}
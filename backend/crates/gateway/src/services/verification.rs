@@ -0,0 +1,432 @@
+use bytes::Bytes;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+use tokio_stream::{Stream, StreamExt};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{
+    HistoricalVkStatus, VerificationKey, VerificationRecord, VerificationResult, VkInfo, VkStatus,
+};
+use crate::services::{
+    BlobUpload, ProofHasher, ProofStore, VerificationHistory, VerifierClient, VerifyResponse,
+    VkService, generate_proof_hash, hash_public_inputs,
+};
+
+/// Maximum number of proofs in one batch request
+pub const MAX_BATCH_SIZE: usize = 32;
+
+/// Rate limit requests charged for a batch: one per proof. Oversized batches are
+/// rejected anyway, so they are charged as a full batch.
+pub fn batch_cost(proofs: usize) -> u32 {
+    proofs.clamp(1, MAX_BATCH_SIZE) as u32
+}
+
+/// Proofs of one batch verified at the same time
+const BATCH_CONCURRENCY: usize = 4;
+
+/// Largest chunk forwarded to the verifier in one stream message
+const STREAM_CHUNK_SIZE: usize = 1024 * 1024;
+
+/// A proof to verify, independent of the API it arrived through
+#[derive(Debug, Clone)]
+pub struct ProofSubmission {
+    pub proof: Vec<u8>,
+    pub public_inputs: Option<Vec<String>>,
+    pub prover: Option<String>,
+    pub proof_system: Option<String>,
+    /// VK identifier: can be a hash (0x...) or a prover-defined alias
+    pub vk_id: Option<String>,
+    /// Evaluate the VK's status at this earlier time instead of now
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Everything about a streamed proof except the proof bytes
+#[derive(Debug, Clone, Default)]
+pub struct StreamSubmission {
+    pub public_inputs: Option<Vec<String>>,
+    pub prover: Option<String>,
+    pub proof_system: Option<String>,
+    pub vk_id: Option<String>,
+    pub as_of: Option<DateTime<Utc>>,
+}
+
+/// Verification logic shared by the REST and gRPC APIs
+#[derive(Clone)]
+pub struct VerificationService {
+    vk_service: VkService,
+    verifier_client: VerifierClient,
+    max_proof_bytes: u64,
+    /// Keeps submitted proofs when proof storage is enabled
+    proof_store: Option<ProofStore>,
+    history: VerificationHistory,
+}
+
+/// Prover, proof system and VK resolved for a submission
+struct Target {
+    prover: String,
+    proof_system: String,
+    vk: Option<VerificationKey>,
+    /// VK status at `as_of`, when the submission asked for one
+    historical: Option<HistoricalVkStatus>,
+}
+
+impl VerificationService {
+    pub fn new(
+        vk_service: VkService,
+        verifier_client: VerifierClient,
+        max_proof_bytes: u64,
+        proof_store: Option<ProofStore>,
+        history: VerificationHistory,
+    ) -> Self {
+        Self {
+            vk_service,
+            verifier_client,
+            max_proof_bytes,
+            proof_store,
+            history,
+        }
+    }
+
+    /// Verify a proof submitted by `user_id`
+    pub async fn verify(
+        &self,
+        user_id: Uuid,
+        submission: ProofSubmission,
+    ) -> Result<VerificationResult, ApiError> {
+        if submission.proof.len() as u64 > self.max_proof_bytes {
+            return Err(self.proof_too_large());
+        }
+
+        let target = self
+            .resolve_target(
+                submission.prover,
+                submission.proof_system,
+                submission.vk_id.as_deref(),
+                submission.as_of,
+            )
+            .await?;
+
+        let proof_hash = generate_proof_hash(&submission.proof, &submission.public_inputs);
+        let public_inputs_hash = hash_public_inputs(&submission.public_inputs);
+
+        // The verifier request takes ownership of the proof
+        let kept = self
+            .proof_store
+            .as_ref()
+            .map(|_| (Bytes::copy_from_slice(&submission.proof), submission.public_inputs.clone()));
+
+        let verify_result = self
+            .verifier_client
+            .verify_zisk(submission.proof, submission.public_inputs)
+            .await?;
+
+        if let (Some(store), Some((proof, public_inputs))) = (&self.proof_store, kept)
+            && let Err(e) = store.store(user_id, &proof_hash, proof, &public_inputs).await
+        {
+            tracing::warn!(proof_hash = %proof_hash, error = %e, "Failed to store proof");
+        }
+
+        let result = Self::build_result(target, verify_result, proof_hash, public_inputs_hash);
+        self.record(user_id, &result).await;
+        Ok(result)
+    }
+
+    /// Verify a proof that arrives in chunks, forwarding them to the verifier as they come.
+    /// Only one chunk at a time is held in memory.
+    pub async fn verify_stream<S>(
+        &self,
+        user_id: Uuid,
+        submission: StreamSubmission,
+        mut chunks: S,
+    ) -> Result<VerificationResult, ApiError>
+    where
+        S: Stream<Item = Result<Bytes, ApiError>> + Unpin,
+    {
+        let target = self
+            .resolve_target(
+                submission.prover,
+                submission.proof_system,
+                submission.vk_id.as_deref(),
+                submission.as_of,
+            )
+            .await?;
+
+        let public_inputs = submission.public_inputs;
+        let public_inputs_hash = hash_public_inputs(&public_inputs);
+        let max_proof_bytes = self.max_proof_bytes;
+        let inputs = &public_inputs;
+        let mut upload = self.begin_upload(user_id).await;
+
+        let (verify_result, (proof_hash, upload)) = self
+            .verifier_client
+            .verify_zisk_stream(public_inputs.clone().unwrap_or_default(), |sender| async move {
+                let mut hasher = ProofHasher::new();
+                let mut proof_len: u64 = 0;
+
+                while let Some(chunk) = chunks.next().await {
+                    let chunk = chunk?;
+                    proof_len += chunk.len() as u64;
+                    if proof_len > max_proof_bytes {
+                        return Err(self.proof_too_large());
+                    }
+                    hasher.update(&chunk);
+
+                    if let Some(blob) = upload.as_mut()
+                        && let Err(e) = blob.write(&chunk).await
+                    {
+                        tracing::warn!(error = %e, "Failed to stage streamed proof, it will not be stored");
+                        upload = None;
+                    }
+
+                    for part in chunk.chunks(STREAM_CHUNK_SIZE) {
+                        sender.send(part.to_vec()).await.map_err(|_| {
+                            ApiError::VerifierService("Verifier closed the upload stream".to_string())
+                        })?;
+                    }
+                }
+
+                if proof_len == 0 {
+                    return Err(ApiError::BadRequest("Proof is empty".to_string()));
+                }
+                Ok((hasher.finish(inputs), upload))
+            })
+            .await?;
+
+        if let (Some(store), Some(upload)) = (&self.proof_store, upload)
+            && let Err(e) = store.finish_upload(user_id, &proof_hash, upload, &public_inputs).await
+        {
+            tracing::warn!(proof_hash = %proof_hash, error = %e, "Failed to store proof");
+        }
+
+        let result = Self::build_result(target, verify_result, proof_hash, public_inputs_hash);
+        self.record(user_id, &result).await;
+        Ok(result)
+    }
+
+    /// Re-run a recorded verification on the current verifier, recording the new outcome
+    /// under the re-verification job. A proof checked against a VK that has since been
+    /// revoked is no longer valid, whatever the verifier says; a historical re-check keeps
+    /// its `as_of` and goes by the VK's status then.
+    pub async fn reverify(
+        &self,
+        previous: &VerificationRecord,
+        proof: Vec<u8>,
+        public_inputs: Option<Vec<String>>,
+        job_id: Uuid,
+    ) -> Result<VerificationRecord, ApiError> {
+        let vk = match previous.vk_id {
+            Some(vk_id) => self.vk_service.get_by_id(vk_id).await?,
+            None => None,
+        };
+        let historical = match (&vk, previous.as_of) {
+            (Some(vk), Some(as_of)) => Some(HistoricalVkStatus::new(
+                &self.vk_service.state_at(vk, as_of).await?,
+                as_of,
+            )),
+            _ => None,
+        };
+        let target = Target {
+            prover: previous.prover.clone(),
+            proof_system: previous.proof_system.clone(),
+            vk,
+            historical,
+        };
+
+        let public_inputs_hash = hash_public_inputs(&public_inputs);
+        let verify_result = self.verifier_client.verify_zisk(proof, public_inputs).await?;
+
+        let mut result =
+            Self::build_result(target, verify_result, previous.proof_hash.clone(), public_inputs_hash);
+        let revoked = result.vk.as_ref().and_then(|vk| {
+            let (status, reason) = match &vk.historical {
+                Some(historical) => (&historical.status, &historical.deprecation_reason),
+                None => (&vk.status, &vk.deprecation_reason),
+            };
+            (status == "revoked").then(|| {
+                format!(
+                    "Verification key is revoked: {}",
+                    reason.as_deref().unwrap_or("unknown reason")
+                )
+            })
+        });
+        if let Some(error) = revoked {
+            result.valid = false;
+            result.error = Some(error);
+        }
+
+        self.history.record(previous.user_id, &result, Some(job_id)).await
+    }
+
+    /// Record an outcome; a failure to record never fails the verification
+    async fn record(&self, user_id: Uuid, result: &VerificationResult) {
+        if let Err(e) = self.history.record(user_id, result, None).await {
+            tracing::error!(proof_hash = %result.proof_hash, error = %e, "Failed to record verification");
+        }
+    }
+
+    /// Stage a streamed proof for storage; storage problems never fail the verification
+    async fn begin_upload(&self, user_id: Uuid) -> Option<BlobUpload> {
+        let store = self.proof_store.as_ref()?;
+        store
+            .begin_upload(user_id)
+            .await
+            .inspect_err(|e| tracing::warn!(error = %e, "Failed to stage streamed proof"))
+            .ok()
+            .flatten()
+    }
+
+    fn proof_too_large(&self) -> ApiError {
+        ApiError::BadRequest(format!(
+            "Proof exceeds the maximum size of {} bytes",
+            self.max_proof_bytes
+        ))
+    }
+
+    async fn resolve_target(
+        &self,
+        prover: Option<String>,
+        proof_system: Option<String>,
+        vk_id: Option<&str>,
+        as_of: Option<DateTime<Utc>>,
+    ) -> Result<Target, ApiError> {
+        let prover = prover.unwrap_or_else(|| "zisk".to_string());
+        let proof_system = proof_system.unwrap_or_else(|| "zisk".to_string());
+
+        // Currently only zisk is supported
+        if prover != "zisk" {
+            return Err(ApiError::UnsupportedProver(prover));
+        }
+
+        let now = Utc::now();
+        if let Some(as_of) = as_of {
+            if vk_id.is_none() {
+                return Err(ApiError::BadRequest("as_of requires a vk_id".to_string()));
+            }
+            if as_of > now {
+                return Err(ApiError::BadRequest("as_of must not be in the future".to_string()));
+            }
+        }
+
+        // Resolve VK if vk_id is provided
+        let vk = match vk_id {
+            Some(vk_id) => {
+                let vk = self
+                    .vk_service
+                    .resolve_vk(&prover, vk_id)
+                    .await?
+                    .ok_or(ApiError::VkNotFound)?;
+
+                // With as_of, the VK is judged by what its audit log recorded then
+                let (at, effective) = match as_of {
+                    Some(as_of) => (as_of, self.vk_service.state_at(&vk, as_of).await?),
+                    None => (now, vk.clone()),
+                };
+
+                // Scheduled dates count as soon as they pass, whether or not the
+                // scheduler has applied them
+                if !effective.is_valid_at(at) {
+                    return Err(ApiError::BadRequest(format!(
+                        "Verification key is not valid until {}",
+                        effective.valid_from.map(|t| t.to_rfc3339()).unwrap_or_default()
+                    )));
+                }
+                if effective.status_at(at) == VkStatus::Revoked {
+                    let when = match as_of {
+                        Some(as_of) => format!(" as of {}", as_of.to_rfc3339()),
+                        None => String::new(),
+                    };
+                    return Err(ApiError::BadRequest(format!(
+                        "Verification key is revoked{}: {}",
+                        when,
+                        effective.deprecation_reason.as_deref().unwrap_or("unknown reason")
+                    )));
+                }
+
+                let historical = as_of.map(|as_of| HistoricalVkStatus::new(&effective, as_of));
+                Some((vk, historical))
+            }
+            None => None,
+        };
+        let (vk, historical) = vk.unzip();
+
+        Ok(Target {
+            prover,
+            proof_system,
+            vk,
+            historical: historical.flatten(),
+        })
+    }
+
+    fn build_result(
+        target: Target,
+        verify_result: VerifyResponse,
+        proof_hash: String,
+        public_inputs_hash: Option<String>,
+    ) -> VerificationResult {
+        VerificationResult {
+            valid: verify_result.valid,
+            prover: target.prover,
+            proof_system: target.proof_system,
+            // Get proof_type from VK if available
+            proof_type: target.vk.as_ref().and_then(|v| v.proof_type.clone()),
+            prover_version: verify_result.prover_version,
+            proof_hash,
+            public_inputs_hash,
+            vk: target.vk.as_ref().map(|vk| VkInfo {
+                historical: target.historical,
+                ..VkInfo::from(vk)
+            }),
+            verified_at: Utc::now().to_rfc3339(),
+            error: verify_result.error,
+            public_values: verify_result.public_values,
+            program_id: verify_result.program_id,
+            cycles: verify_result.cycles,
+            verifier_version: verify_result.verifier_version,
+            diagnostics: verify_result.diagnostics,
+        }
+    }
+
+    /// Verify several proofs, a few at a time. Results are in submission order;
+    /// a failing item does not fail the batch.
+    pub async fn verify_batch(
+        &self,
+        user_id: Uuid,
+        submissions: Vec<ProofSubmission>,
+    ) -> Result<Vec<Result<VerificationResult, ApiError>>, ApiError> {
+        if submissions.is_empty() {
+            return Err(ApiError::BadRequest("Batch must contain at least one proof".to_string()));
+        }
+        if submissions.len() > MAX_BATCH_SIZE {
+            return Err(ApiError::BadRequest(format!(
+                "Batch contains {} proofs, the maximum is {}",
+                submissions.len(),
+                MAX_BATCH_SIZE
+            )));
+        }
+
+        let permits = Arc::new(Semaphore::new(BATCH_CONCURRENCY));
+        let mut tasks = tokio::task::JoinSet::new();
+        for (index, submission) in submissions.into_iter().enumerate() {
+            let service = self.clone();
+            let permits = Arc::clone(&permits);
+            tasks.spawn(async move {
+                let _permit = permits.acquire_owned().await;
+                (index, service.verify(user_id, submission).await)
+            });
+        }
+
+        let mut results: Vec<Option<Result<VerificationResult, ApiError>>> =
+            std::iter::repeat_with(|| None).take(tasks.len()).collect();
+        while let Some(joined) = tasks.join_next().await {
+            let (index, result) = joined.map_err(|e| {
+                tracing::error!("Batch verification task failed: {}", e);
+                ApiError::Internal
+            })?;
+            results[index] = Some(result);
+        }
+
+        Ok(results.into_iter().map(|r| r.unwrap_or(Err(ApiError::Internal))).collect())
+    }
+}
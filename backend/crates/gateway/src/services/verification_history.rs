@@ -0,0 +1,269 @@
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{VerificationOutcome, VerificationRecord, VerificationResult};
+use crate::services::{Audience, enqueue_event};
+
+const RECORD_COLUMNS: &str = "id, user_id, proof_hash, public_inputs_hash, prover, proof_system, \
+    prover_version, verifier_version, vk_id, vk_hash, valid, error, reverification_job_id, verified_at, as_of";
+
+/// Filters for a user's verification history
+#[derive(Debug, Default, Clone)]
+pub struct VerificationFilter {
+    pub prover: Option<String>,
+    pub vk_hash: Option<String>,
+    pub valid: Option<bool>,
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// Position after the last record of a page (newest first)
+#[derive(Debug, Clone, Copy)]
+pub struct VerificationCursor {
+    pub verified_at: DateTime<Utc>,
+    pub id: Uuid,
+}
+
+impl VerificationCursor {
+    pub fn after(record: &VerificationRecord) -> Self {
+        Self {
+            verified_at: record.verified_at,
+            id: record.id,
+        }
+    }
+
+    /// Opaque form handed to clients
+    pub fn encode(&self) -> String {
+        hex::encode(format!("{}:{}", self.verified_at.timestamp_micros(), self.id))
+    }
+
+    pub fn decode(cursor: &str) -> Result<Self, ApiError> {
+        let invalid = || ApiError::BadRequest("Invalid cursor".to_string());
+        let raw = String::from_utf8(hex::decode(cursor).map_err(|_| invalid())?)
+            .map_err(|_| invalid())?;
+        let (micros, id) = raw.split_once(':').ok_or_else(invalid)?;
+
+        Ok(Self {
+            verified_at: micros
+                .parse()
+                .ok()
+                .and_then(DateTime::from_timestamp_micros)
+                .ok_or_else(invalid)?,
+            id: id.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// Recorded verification outcomes
+#[derive(Clone)]
+pub struct VerificationHistory {
+    pool: PgPool,
+}
+
+impl VerificationHistory {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Record the outcome of a verification run for `user_id`, notifying their webhooks
+    /// unless a re-verification job produced it
+    pub async fn record(
+        &self,
+        user_id: Uuid,
+        result: &VerificationResult,
+        reverification_job_id: Option<Uuid>,
+    ) -> Result<VerificationRecord, ApiError> {
+        let verified_at = DateTime::parse_from_rfc3339(&result.verified_at)
+            .map(|t| t.with_timezone(&Utc))
+            .unwrap_or_else(|_| Utc::now());
+        let as_of = result
+            .vk
+            .as_ref()
+            .and_then(|vk| vk.historical.as_ref())
+            .map(|historical| historical.as_of);
+
+        let mut tx = self.pool.begin().await?;
+
+        let record = sqlx::query_as::<_, VerificationRecord>(&format!(
+            r#"INSERT INTO verifications
+               (user_id, proof_hash, public_inputs_hash, prover, proof_system, prover_version,
+                verifier_version, vk_id, vk_hash, valid, error, reverification_job_id, verified_at,
+                as_of)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14)
+               RETURNING {RECORD_COLUMNS}"#
+        ))
+        .bind(user_id)
+        .bind(&result.proof_hash)
+        .bind(&result.public_inputs_hash)
+        .bind(&result.prover)
+        .bind(&result.proof_system)
+        .bind(&result.prover_version)
+        .bind(&result.verifier_version)
+        .bind(result.vk.as_ref().map(|vk| vk.id))
+        .bind(result.vk.as_ref().map(|vk| &vk.hash))
+        .bind(result.valid)
+        .bind(&result.error)
+        .bind(reverification_job_id)
+        .bind(verified_at)
+        .bind(as_of)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if reverification_job_id.is_none() {
+            enqueue_event(&mut *tx, "verification.completed", &record, Audience::User(user_id))
+                .await?;
+        }
+        tx.commit().await?;
+
+        Ok(record)
+    }
+
+    /// Latest record of every proof (per user) whose latest verification used the given
+    /// VK and/or prover version. Historical re-checks (`as_of`) are not current outcomes
+    /// and are skipped.
+    pub async fn latest_in_scope(
+        &self,
+        vk_id: Option<Uuid>,
+        prover_version: Option<&str>,
+    ) -> Result<Vec<VerificationRecord>, ApiError> {
+        let records = sqlx::query_as::<_, VerificationRecord>(&format!(
+            r#"SELECT {RECORD_COLUMNS} FROM (
+                   SELECT DISTINCT ON (proof_hash, user_id) {RECORD_COLUMNS}
+                   FROM verifications
+                   WHERE as_of IS NULL
+                   ORDER BY proof_hash, user_id, verified_at DESC
+               ) latest
+               WHERE ($1::uuid IS NULL OR vk_id = $1)
+                 AND ($2::text IS NULL OR prover_version = $2)
+               ORDER BY verified_at"#
+        ))
+        .bind(vk_id)
+        .bind(prover_version)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// A page of `user_id`'s verifications, newest first
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+        filter: &VerificationFilter,
+        after: Option<VerificationCursor>,
+        limit: i64,
+    ) -> Result<Vec<VerificationRecord>, ApiError> {
+        let records = sqlx::query_as::<_, VerificationRecord>(&format!(
+            r#"SELECT {RECORD_COLUMNS} FROM verifications
+               WHERE user_id = $1
+                 AND ($2::text IS NULL OR prover = $2)
+                 AND ($3::text IS NULL OR vk_hash = $3)
+                 AND ($4::bool IS NULL OR valid = $4)
+                 AND ($5::timestamptz IS NULL OR verified_at >= $5)
+                 AND ($6::timestamptz IS NULL OR verified_at < $6)
+                 AND ($7::timestamptz IS NULL OR (verified_at, id) < ($7, $8))
+               ORDER BY verified_at DESC, id DESC
+               LIMIT $9"#
+        ))
+        .bind(user_id)
+        .bind(&filter.prover)
+        .bind(&filter.vk_hash)
+        .bind(filter.valid)
+        .bind(filter.from)
+        .bind(filter.to)
+        .bind(after.map(|c| c.verified_at))
+        .bind(after.map(|c| c.id))
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Most recent verification of a proof with the VK of the given hash, ignoring
+    /// historical re-checks
+    pub async fn latest_with_vk(
+        &self,
+        proof_hash: &str,
+        vk_hash: &str,
+    ) -> Result<Option<VerificationRecord>, ApiError> {
+        let record = sqlx::query_as::<_, VerificationRecord>(&format!(
+            r#"SELECT {RECORD_COLUMNS} FROM verifications
+               WHERE proof_hash = $1 AND vk_hash = $2 AND as_of IS NULL
+               ORDER BY verified_at DESC
+               LIMIT 1"#
+        ))
+        .bind(proof_hash)
+        .bind(vk_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(record)
+    }
+
+    /// Latest verification of every proof whose current outcome (VK and validity) is not
+    /// anchored on `chain` yet, or only by an orphaned anchor, oldest first. Historical
+    /// re-checks (`as_of`) say nothing about the current outcome and are never anchored,
+    /// and verifications whose anchor could not be recorded are not tried again.
+    pub async fn unanchored(
+        &self,
+        chain: &str,
+        limit: i64,
+    ) -> Result<Vec<VerificationRecord>, ApiError> {
+        let records = sqlx::query_as::<_, VerificationRecord>(&format!(
+            r#"SELECT {RECORD_COLUMNS} FROM (
+                   SELECT DISTINCT ON (proof_hash) {RECORD_COLUMNS}
+                   FROM verifications
+                   WHERE vk_hash IS NOT NULL AND as_of IS NULL
+                   ORDER BY proof_hash, verified_at DESC
+               ) latest
+               WHERE NOT EXISTS (
+                   SELECT 1 FROM anchors a
+                   WHERE a.proof_hash = latest.proof_hash AND a.chain = $1
+                     AND a.vk_hash = latest.vk_hash AND a.valid = latest.valid
+                     AND a.confirmation_status <> 'orphaned'
+               )
+               AND NOT EXISTS (
+                   SELECT 1 FROM anchor_batch_items i
+                   JOIN anchor_batches b ON b.id = i.batch_id
+                   WHERE i.verification_id = latest.id AND b.chain = $1
+                     AND i.anchor_error IS NOT NULL
+               )
+               ORDER BY verified_at
+               LIMIT $2"#
+        ))
+        .bind(chain)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(records)
+    }
+
+    /// Outcomes of a proof grouped by prover version, verifier version and VK, newest first.
+    /// Historical re-checks (`as_of`) describe a past VK status, not the proof, and are left out.
+    pub async fn outcomes_for_proof(
+        &self,
+        proof_hash: &str,
+    ) -> Result<Vec<VerificationOutcome>, ApiError> {
+        let outcomes = sqlx::query_as::<_, VerificationOutcome>(
+            r#"SELECT prover, proof_system, prover_version, verifier_version, vk_id, vk_hash,
+                      (array_agg(valid ORDER BY verified_at DESC))[1] AS valid,
+                      (array_agg(error ORDER BY verified_at DESC))[1] AS error,
+                      COUNT(*) AS verification_count,
+                      MIN(verified_at) AS first_verified_at,
+                      MAX(verified_at) AS last_verified_at
+               FROM verifications
+               WHERE proof_hash = $1 AND as_of IS NULL
+               GROUP BY prover, proof_system, prover_version, verifier_version, vk_id, vk_hash
+               ORDER BY last_verified_at DESC"#,
+        )
+        .bind(proof_hash)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(outcomes)
+    }
+}
@@ -3,14 +3,75 @@ pub mod proto {
 }
 
 use crate::error::ApiError;
-use proto::verifier_client::VerifierClient as GrpcClient;
-use proto::{HealthRequest, VerifyRequest as GrpcVerifyRequest};
-use tonic::transport::Channel;
+use crate::services::{CircuitBreaker, Endpoint, EndpointPool};
+use proto::verify_stream_request::Payload;
+use proto::{HealthRequest, VerifyMetadata, VerifyRequest as GrpcVerifyRequest, VerifyStreamRequest};
+use rand::Rng;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio_stream::StreamExt;
+use tokio_stream::wrappers::ReceiverStream;
+use tonic::transport::{Certificate, ClientTlsConfig, Identity};
+use tonic::{Code, Status};
+
+/// Retry, deadline and circuit breaker settings for verifier calls
+#[derive(Debug, Clone)]
+pub struct VerifierPolicy {
+    /// Deadline for a single verify attempt
+    pub request_timeout: Duration,
+    /// Retries after the first attempt, only for transient failures
+    pub max_retries: u32,
+    /// Base delay, doubled on every retry
+    pub retry_backoff: Duration,
+    /// Consecutive failures that open the circuit breaker
+    pub breaker_threshold: u32,
+    pub breaker_cooldown: Duration,
+    /// Background health check and DNS re-resolution interval (0 disables)
+    pub health_interval: Duration,
+}
+
+/// TLS settings for verifier connections, as PEM file paths
+#[derive(Debug, Clone)]
+pub struct VerifierTls {
+    /// CA that signed the verifier server certificates (system roots when unset)
+    pub ca_cert: Option<String>,
+    /// Client certificate and key presented to verifiers that require mTLS
+    pub client_identity: Option<(String, String)>,
+    /// Name to verify server certificates against, instead of the endpoint host
+    pub domain: Option<String>,
+}
+
+impl VerifierTls {
+    /// Client config without a domain name; the endpoint pool sets it per endpoint
+    pub fn client_config(&self) -> Result<ClientTlsConfig, ApiError> {
+        let mut config = ClientTlsConfig::new();
+
+        config = match &self.ca_cert {
+            Some(path) => config.ca_certificate(Certificate::from_pem(read_pem(path)?)),
+            None => config.with_enabled_roots(),
+        };
+        if let Some((cert, key)) = &self.client_identity {
+            config = config.identity(Identity::from_pem(read_pem(cert)?, read_pem(key)?));
+        }
+
+        Ok(config)
+    }
+}
+
+fn read_pem(path: &str) -> Result<Vec<u8>, ApiError> {
+    std::fs::read(path)
+        .map_err(|e| ApiError::VerifierService(format!("Failed to read TLS file {}: {}", path, e)))
+}
+
+/// Proof chunks buffered between the upload and the verifier stream
+const STREAM_BUFFER: usize = 4;
 
 #[derive(Clone)]
 pub struct VerifierClient {
-    zisk_client: GrpcClient<Channel>,
-    zisk_url: String,
+    zisk_pool: EndpointPool,
+    zisk_breaker: CircuitBreaker,
+    policy: VerifierPolicy,
 }
 
 #[derive(Debug)]
@@ -18,32 +79,72 @@ pub struct VerifyResponse {
     pub valid: bool,
     pub prover_version: String,
     pub error: Option<String>,
+    pub public_values: Vec<String>,
+    pub program_id: Option<String>,
+    pub cycles: Option<u64>,
+    pub verifier_version: Option<String>,
+    pub diagnostics: Option<String>,
 }
 
 impl VerifierClient {
-    pub async fn new(zisk_url: String) -> Result<Self, ApiError> {
-        let zisk_channel = Channel::from_shared(zisk_url.clone())
-            .map_err(|e| ApiError::VerifierService(format!("Invalid zisk URL: {}", e)))?
-            .connect_lazy();
-
+    /// `zisk_endpoints` is a comma-separated list of URLs, `dns://host:port` or
+    /// `dns+srv://name` (see `EndpointSource`).
+    pub async fn new(
+        zisk_endpoints: &str,
+        policy: VerifierPolicy,
+        tls: Option<&VerifierTls>,
+    ) -> Result<Self, ApiError> {
         Ok(Self {
-            zisk_client: GrpcClient::new(zisk_channel),
-            zisk_url,
+            zisk_pool: EndpointPool::new("zisk", zisk_endpoints, tls).await?,
+            zisk_breaker: CircuitBreaker::new("zisk", policy.breaker_threshold, policy.breaker_cooldown),
+            policy,
         })
     }
 
-    /// Check if the verifier service is healthy.
-    /// Returns Ok(version) if healthy, Err if not reachable.
+    /// Check every verifier replica and update which ones receive traffic.
+    /// Returns Ok(version) if at least one replica is healthy.
     pub async fn health_check(&self) -> Result<String, ApiError> {
-        let mut client = self.zisk_client.clone();
+        let mut checks = tokio::task::JoinSet::new();
+        for endpoint in self.zisk_pool.endpoints() {
+            let client = self.clone();
+            checks.spawn(async move {
+                let result = client.endpoint_health(&endpoint).await;
+                endpoint.set_healthy(result.is_ok());
+                result
+            });
+        }
+
+        let mut version = None;
+        let mut last_error = None;
+        while let Some(joined) = checks.join_next().await {
+            match joined {
+                Ok(Ok(v)) => version = Some(v),
+                Ok(Err(e)) => last_error = Some(e),
+                Err(e) => last_error = Some(ApiError::VerifierService(format!("Health check task failed: {}", e))),
+            }
+        }
+
+        if let Some(version) = version {
+            return Ok(version);
+        }
+        Err(last_error.unwrap_or_else(|| {
+            ApiError::VerifierService("No verifier endpoints resolved".to_string())
+        }))
+    }
+
+    async fn endpoint_health(&self, endpoint: &Endpoint) -> Result<String, ApiError> {
+        let mut client = endpoint.client();
+
+        let mut request = tonic::Request::new(HealthRequest {});
+        request.set_timeout(self.policy.request_timeout);
 
         let response = client
-            .health(tonic::Request::new(HealthRequest {}))
+            .health(request)
             .await
             .map_err(|e| {
                 ApiError::VerifierService(format!(
                     "Verifier health check failed ({}): {}",
-                    self.zisk_url, e
+                    endpoint.url, e
                 ))
             })?;
 
@@ -51,35 +152,229 @@ impl VerifierClient {
         if inner.healthy {
             Ok(inner.version)
         } else {
-            Err(ApiError::VerifierService(
-                "Verifier reported unhealthy status".to_string(),
-            ))
+            Err(ApiError::VerifierService(format!(
+                "Verifier reported unhealthy status ({})",
+                endpoint.url
+            )))
+        }
+    }
+
+    /// Run health checks in the background: re-resolve DNS endpoints, eject replicas whose
+    /// Health RPC fails and feed the overall result into the circuit breaker, so an
+    /// unhealthy verifier is cut off before user requests fail against it.
+    pub fn spawn_health_checks(&self) {
+        if self.policy.health_interval.is_zero() {
+            return;
         }
+
+        let client = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(client.policy.health_interval);
+            loop {
+                ticker.tick().await;
+                client.zisk_pool.refresh().await;
+                match client.health_check().await {
+                    Ok(_) => client.zisk_breaker.record_success(),
+                    Err(e) => {
+                        tracing::warn!(error = %e, "Background verifier health check failed");
+                        client.zisk_breaker.record_failure();
+                    }
+                }
+            }
+        });
     }
 
+    /// Verify a proof with the zisk verifier.
+    /// Verification is idempotent, so transient failures are retried with backoff.
     pub async fn verify_zisk(
         &self,
         proof: Vec<u8>,
         public_inputs: Option<Vec<String>>,
     ) -> Result<VerifyResponse, ApiError> {
-        let mut client = self.zisk_client.clone();
-
-        let request = tonic::Request::new(GrpcVerifyRequest {
+        let grpc_request = GrpcVerifyRequest {
             proof,
             public_inputs: public_inputs.unwrap_or_default(),
             proof_system: "zisk".to_string(),
-        });
+        };
 
-        let response = client
-            .verify(request)
+        let mut attempt = 0;
+        let mut tried: Vec<String> = Vec::new();
+        loop {
+            self.zisk_breaker
+                .check()
+                .map_err(|retry_after| ApiError::VerifierUnavailable(retry_after.as_secs().max(1)))?;
+
+            // Prefer a replica that has not failed this request yet
+            let Some(lease) = self.zisk_pool.acquire(&tried) else {
+                self.zisk_breaker.record_failure();
+                return Err(self.no_endpoint());
+            };
+            let endpoint = lease.endpoint();
+
+            let mut client = endpoint.client();
+            let mut request = tonic::Request::new(grpc_request.clone());
+            request.set_timeout(self.policy.request_timeout);
+
+            let status = match client.verify(request).await {
+                Ok(response) => {
+                    self.zisk_breaker.record_success();
+                    return Ok(Self::into_verify_response(response.into_inner()));
+                }
+                Err(status) => status,
+            };
+
+            // The verifier answered, so it counts as up even though the call failed
+            if !is_transient(&status) {
+                self.zisk_breaker.record_success();
+                return Err(ApiError::VerifierService(format!("gRPC error: {}", status)));
+            }
+
+            self.zisk_breaker.record_failure();
+            // An unreachable replica is ejected until a health check restores it or the
+            // breaker cooldown passes
+            if status.code() == Code::Unavailable {
+                endpoint.eject_for(self.policy.breaker_cooldown);
+            }
+            tried.push(endpoint.url.clone());
+            drop(lease);
+
+            if attempt >= self.policy.max_retries || !is_retryable(&status) {
+                return Err(ApiError::VerifierService(format!(
+                    "gRPC error after {} attempts: {}",
+                    attempt + 1,
+                    status
+                )));
+            }
+
+            let delay = self.backoff(attempt);
+            tracing::warn!(
+                attempt = attempt + 1,
+                endpoint = %tried[tried.len() - 1],
+                delay_ms = delay.as_millis() as u64,
+                code = ?status.code(),
+                "Verifier call failed, retrying"
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Stream a proof to the zisk verifier without holding it in memory.
+    ///
+    /// `feed` pushes the proof chunks into the sender and returns once everything is sent.
+    /// If it fails, the call is cancelled so the verifier never checks a truncated proof.
+    /// An upload cannot be replayed, so unlike `verify_zisk` this does not retry.
+    pub async fn verify_zisk_stream<F, Fut, T>(
+        &self,
+        public_inputs: Vec<String>,
+        feed: F,
+    ) -> Result<(VerifyResponse, T), ApiError>
+    where
+        F: FnOnce(mpsc::Sender<Vec<u8>>) -> Fut,
+        Fut: Future<Output = Result<T, ApiError>>,
+    {
+        self.zisk_breaker
+            .check()
+            .map_err(|retry_after| ApiError::VerifierUnavailable(retry_after.as_secs().max(1)))?;
+
+        let Some(lease) = self.zisk_pool.acquire(&[]) else {
+            self.zisk_breaker.record_failure();
+            return Err(self.no_endpoint());
+        };
+        let endpoint = lease.endpoint();
+
+        let metadata = VerifyStreamRequest {
+            payload: Some(Payload::Metadata(VerifyMetadata {
+                public_inputs,
+                proof_system: "zisk".to_string(),
+            })),
+        };
+        let (sender, receiver) = mpsc::channel(STREAM_BUFFER);
+        let outbound = tokio_stream::once(metadata).chain(ReceiverStream::new(receiver).map(
+            |chunk| VerifyStreamRequest {
+                payload: Some(Payload::Chunk(chunk)),
+            },
+        ));
+
+        let mut client = endpoint.client();
+        let call = client.verify_stream(outbound);
+        tokio::pin!(call);
+
+        let fed = tokio::select! {
+            // Returning early drops the call, which resets the stream
+            fed = feed(sender) => fed.inspect_err(|_| self.zisk_breaker.record_abandoned())?,
+            // The verifier only answers before the upload is complete when it rejects it
+            result = &mut call => {
+                let status = match result {
+                    Err(status) => status,
+                    Ok(_) => Status::internal("verifier answered before the proof was sent"),
+                };
+                return Err(self.stream_failure(endpoint, status));
+            }
+        };
+
+        // The deadline covers verification; the upload itself is paced by the caller
+        let result = tokio::time::timeout(self.policy.request_timeout, call)
             .await
-            .map_err(|e| ApiError::VerifierService(format!("gRPC error: {}", e)))?;
+            .unwrap_or_else(|_| Err(Status::deadline_exceeded("verifier call timed out")));
 
-        let inner = response.into_inner();
-        Ok(VerifyResponse {
+        match result {
+            Ok(response) => {
+                self.zisk_breaker.record_success();
+                Ok((Self::into_verify_response(response.into_inner()), fed))
+            }
+            Err(status) => Err(self.stream_failure(endpoint, status)),
+        }
+    }
+
+    fn stream_failure(&self, endpoint: &Endpoint, status: Status) -> ApiError {
+        if is_transient(&status) {
+            self.zisk_breaker.record_failure();
+            if status.code() == Code::Unavailable {
+                endpoint.eject_for(self.policy.breaker_cooldown);
+            }
+        } else {
+            // The verifier answered, so it counts as up even though the call failed
+            self.zisk_breaker.record_success();
+        }
+        ApiError::VerifierService(format!("gRPC error ({}): {}", endpoint.url, status))
+    }
+
+    fn no_endpoint(&self) -> ApiError {
+        ApiError::VerifierUnavailable(self.policy.health_interval.as_secs().max(1))
+    }
+
+    /// Exponential backoff with up to 25% jitter
+    fn backoff(&self, attempt: u32) -> Duration {
+        let base = self.policy.retry_backoff.saturating_mul(2u32.saturating_pow(attempt));
+        let jitter_ms = rand::rng().random_range(0..=base.as_millis() as u64 / 4);
+        base + Duration::from_millis(jitter_ms)
+    }
+
+    fn into_verify_response(inner: proto::VerifyResponse) -> VerifyResponse {
+        VerifyResponse {
             valid: inner.valid,
             prover_version: inner.prover_version,
             error: inner.error,
-        })
+            public_values: inner.public_values,
+            program_id: inner.program_id,
+            cycles: inner.cycles,
+            verifier_version: inner.verifier_version,
+            diagnostics: inner.diagnostics,
+        }
     }
 }
+
+/// Failures that count against the verifier: it was unreachable, overloaded or too slow
+fn is_transient(status: &Status) -> bool {
+    matches!(
+        status.code(),
+        Code::Unavailable | Code::DeadlineExceeded | Code::ResourceExhausted | Code::Aborted
+    )
+}
+
+/// Transient failures worth another attempt. A proof that ran out of time would only run
+/// out of time again, while the verifier keeps working on every copy sent.
+fn is_retryable(status: &Status) -> bool {
+    is_transient(status) && status.code() != Code::DeadlineExceeded
+}
@@ -1,9 +1,27 @@
 use crate::error::ApiError;
-use crate::models::{CreateVerificationKey, UpdateVerificationKey, VerificationKey};
+use crate::models::{
+    CreateVerificationKey, UpdateVerificationKey, VerificationKey, VkAliasChange, VkEvent,
+    VkListItem, VkStatus, VkStatusChange,
+};
+use crate::services::{Audience, enqueue_event, publish_event};
+use chrono::{DateTime, Utc};
 use sha2::{Digest, Sha256};
-use sqlx::PgPool;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::time::Duration;
 use uuid::Uuid;
 
+const VK_COLUMNS: &str = "id, prover, version, proof_system, proof_type, vk_hash, vk_data, \
+    alias, status, deprecation_reason, registered_by, created_at, active, valid_from, \
+    deprecate_at, revoke_at";
+
+const EVENT_COLUMNS: &str = "id, vk_id, action, actor, old_status, new_status, \
+    old_deprecation_reason, new_deprecation_reason, old_alias, new_alias, old_valid_from, \
+    new_valid_from, old_deprecate_at, new_deprecate_at, old_revoke_at, new_revoke_at, \
+    justification, created_at";
+
+/// VKs transitioned per scheduler run
+const SCHEDULE_BATCH_SIZE: i64 = 100;
+
 #[derive(Clone)]
 pub struct VkService {
     pool: PgPool,
@@ -45,12 +63,11 @@ impl VkService {
             format!("0x{}", hash)
         };
 
-        let vk = sqlx::query_as::<_, VerificationKey>(
-            r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                      alias, status, deprecation_reason, registered_by, created_at, active
+        let vk = sqlx::query_as::<_, VerificationKey>(&format!(
+            r#"SELECT {VK_COLUMNS}
                FROM verification_keys
-               WHERE vk_hash = $1"#,
-        )
+               WHERE vk_hash = $1"#
+        ))
         .bind(&normalized_hash)
         .fetch_optional(&self.pool)
         .await?;
@@ -64,12 +81,11 @@ impl VkService {
         prover: &str,
         alias: &str,
     ) -> Result<Option<VerificationKey>, ApiError> {
-        let vk = sqlx::query_as::<_, VerificationKey>(
-            r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                      alias, status, deprecation_reason, registered_by, created_at, active
+        let vk = sqlx::query_as::<_, VerificationKey>(&format!(
+            r#"SELECT {VK_COLUMNS}
                FROM verification_keys
-               WHERE prover = $1 AND alias = $2"#,
-        )
+               WHERE prover = $1 AND alias = $2"#
+        ))
         .bind(prover)
         .bind(alias)
         .fetch_optional(&self.pool)
@@ -80,12 +96,11 @@ impl VkService {
 
     /// Get VK by UUID
     pub async fn get_by_id(&self, id: Uuid) -> Result<Option<VerificationKey>, ApiError> {
-        let vk = sqlx::query_as::<_, VerificationKey>(
-            r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                      alias, status, deprecation_reason, registered_by, created_at, active
+        let vk = sqlx::query_as::<_, VerificationKey>(&format!(
+            r#"SELECT {VK_COLUMNS}
                FROM verification_keys
-               WHERE id = $1"#,
-        )
+               WHERE id = $1"#
+        ))
         .bind(id)
         .fetch_optional(&self.pool)
         .await?;
@@ -123,14 +138,13 @@ impl VkService {
     ) -> Result<(Vec<VerificationKey>, usize), ApiError> {
         let (vks, total): (Vec<VerificationKey>, i64) = match (prover, status) {
             (Some(p), Some(s)) => {
-                let vks = sqlx::query_as::<_, VerificationKey>(
-                    r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                              alias, status, deprecation_reason, registered_by, created_at, active
+                let vks = sqlx::query_as::<_, VerificationKey>(&format!(
+                    r#"SELECT {VK_COLUMNS}
                        FROM verification_keys
                        WHERE prover = $1 AND status = $2
                        ORDER BY created_at DESC
-                       LIMIT $3 OFFSET $4"#,
-                )
+                       LIMIT $3 OFFSET $4"#
+                ))
                 .bind(p)
                 .bind(s)
                 .bind(limit)
@@ -149,14 +163,13 @@ impl VkService {
                 (vks, total.0)
             }
             (Some(p), None) => {
-                let vks = sqlx::query_as::<_, VerificationKey>(
-                    r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                              alias, status, deprecation_reason, registered_by, created_at, active
+                let vks = sqlx::query_as::<_, VerificationKey>(&format!(
+                    r#"SELECT {VK_COLUMNS}
                        FROM verification_keys
                        WHERE prover = $1
                        ORDER BY created_at DESC
-                       LIMIT $2 OFFSET $3"#,
-                )
+                       LIMIT $2 OFFSET $3"#
+                ))
                 .bind(p)
                 .bind(limit)
                 .bind(offset)
@@ -173,14 +186,13 @@ impl VkService {
                 (vks, total.0)
             }
             (None, Some(s)) => {
-                let vks = sqlx::query_as::<_, VerificationKey>(
-                    r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                              alias, status, deprecation_reason, registered_by, created_at, active
+                let vks = sqlx::query_as::<_, VerificationKey>(&format!(
+                    r#"SELECT {VK_COLUMNS}
                        FROM verification_keys
                        WHERE status = $1
                        ORDER BY created_at DESC
-                       LIMIT $2 OFFSET $3"#,
-                )
+                       LIMIT $2 OFFSET $3"#
+                ))
                 .bind(s)
                 .bind(limit)
                 .bind(offset)
@@ -197,13 +209,12 @@ impl VkService {
                 (vks, total.0)
             }
             (None, None) => {
-                let vks = sqlx::query_as::<_, VerificationKey>(
-                    r#"SELECT id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                              alias, status, deprecation_reason, registered_by, created_at, active
+                let vks = sqlx::query_as::<_, VerificationKey>(&format!(
+                    r#"SELECT {VK_COLUMNS}
                        FROM verification_keys
                        ORDER BY created_at DESC
-                       LIMIT $1 OFFSET $2"#,
-                )
+                       LIMIT $1 OFFSET $2"#
+                ))
                 .bind(limit)
                 .bind(offset)
                 .fetch_all(&self.pool)
@@ -220,64 +231,263 @@ impl VkService {
         Ok((vks, total as usize))
     }
 
-    /// Create a new VK
+    /// Create a new VK, record it in the audit log and announce it on the event stream
     pub async fn create_vk(
         &self,
         request: CreateVerificationKey,
         registered_by: Uuid,
     ) -> Result<VerificationKey, ApiError> {
+        let state = VkState {
+            status: VkStatus::Active,
+            deprecation_reason: non_empty(request.deprecation_reason),
+            alias: request.alias,
+            valid_from: request.valid_from,
+            deprecate_at: request.deprecate_at,
+            revoke_at: request.revoke_at,
+        };
+        state.check()?;
+
         // Compute hash of VK data
         let vk_hash = Self::compute_vk_hash(&request.vk_data);
 
-        let vk = sqlx::query_as::<_, VerificationKey>(
+        let mut tx = self.pool.begin().await?;
+
+        let vk = sqlx::query_as::<_, VerificationKey>(&format!(
             r#"INSERT INTO verification_keys
-               (prover, version, proof_system, proof_type, vk_hash, vk_data, alias, status, registered_by, active)
-               VALUES ($1, $2, $3, $4, $5, $6, $7, 'active', $8, true)
-               RETURNING id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                         alias, status, deprecation_reason, registered_by, created_at, active"#,
-        )
+               (prover, version, proof_system, proof_type, vk_hash, vk_data, alias, status,
+                deprecation_reason, registered_by, active, valid_from, deprecate_at, revoke_at)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, 'active', $8, $9, true, $10, $11, $12)
+               RETURNING {VK_COLUMNS}"#
+        ))
         .bind(&request.prover)
         .bind(&request.version)
         .bind(&request.proof_system)
         .bind(&request.proof_type)
         .bind(&vk_hash)
         .bind(&request.vk_data)
-        .bind(&request.alias)
+        .bind(&state.alias)
+        .bind(&state.deprecation_reason)
         .bind(registered_by)
-        .fetch_one(&self.pool)
+        .bind(state.valid_from)
+        .bind(state.deprecate_at)
+        .bind(state.revoke_at)
+        .fetch_one(&mut *tx)
         .await?;
 
+        record_vk_event(&mut tx, "created", None, &vk, Some(registered_by), None).await?;
+        publish_event(&mut *tx, "vk.created", &vk.prover, &VkListItem::from(vk.clone())).await?;
+        tx.commit().await?;
+
         Ok(vk)
     }
 
-    /// Update VK status/alias
+    /// Update a VK on behalf of `actor`, following the status state machine: status only
+    /// moves forward unless the request carries a justification (admins only, checked by
+    /// the caller), and deprecating or revoking needs a reason, now or on schedule. Changes
+    /// are recorded in the audit log and announced on the event stream; deprecating or
+    /// revoking a VK also notifies webhooks.
     pub async fn update_vk(
         &self,
         id: Uuid,
         request: UpdateVerificationKey,
+        actor: Uuid,
     ) -> Result<VerificationKey, ApiError> {
-        let status = request.status.map(|s| s.as_str().to_string());
-
-        let vk = sqlx::query_as::<_, VerificationKey>(
-            r#"UPDATE verification_keys
-               SET status = COALESCE($2, status),
-                   deprecation_reason = COALESCE($3, deprecation_reason),
-                   alias = COALESCE($4, alias),
-                   active = CASE WHEN COALESCE($2, status) = 'active' THEN true ELSE false END
-               WHERE id = $1
-               RETURNING id, prover, version, proof_system, proof_type, vk_hash, vk_data,
-                         alias, status, deprecation_reason, registered_by, created_at, active"#,
-        )
+        let justification = non_empty(request.justification);
+
+        let mut tx = self.pool.begin().await?;
+
+        let previous = sqlx::query_as::<_, VerificationKey>(&format!(
+            "SELECT {VK_COLUMNS} FROM verification_keys WHERE id = $1 FOR UPDATE"
+        ))
         .bind(id)
-        .bind(&status)
-        .bind(&request.deprecation_reason)
-        .bind(&request.alias)
-        .fetch_one(&self.pool)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        let current = previous.status_enum();
+        let next = request.status.unwrap_or(current);
+        if !current.can_become(next) {
+            if justification.is_none() {
+                return Err(ApiError::InvalidTransition(format!(
+                    "a {} VK cannot become {}; an admin can override this with a justification",
+                    current, next
+                )));
+            }
+            tracing::warn!(vk_id = %id, %actor, from = %current, to = %next, "VK status override");
+        } else if justification.is_some() {
+            return Err(ApiError::BadRequest(
+                "justification is only accepted for transitions that need an override".to_string(),
+            ));
+        }
+
+        // Reactivating drops schedule dates that have passed, which would undo it
+        let now = Utc::now();
+        let reactivated = next == VkStatus::Active && current != VkStatus::Active;
+        let pending = |at: Option<DateTime<Utc>>| at.filter(|at| !reactivated || *at > now);
+        let deprecate_at = request
+            .deprecate_at
+            .unwrap_or_else(|| pending(previous.deprecate_at));
+        let revoke_at = request.revoke_at.unwrap_or_else(|| pending(previous.revoke_at));
+
+        let deprecation_reason = match request.deprecation_reason {
+            Some(reason) => reason.and_then(|r| non_empty(Some(r))),
+            // A new status needs its own reason, and an active VK only keeps one while a
+            // deprecation or revocation is scheduled
+            None if next != current => None,
+            None if next == VkStatus::Active && deprecate_at.is_none() && revoke_at.is_none() => {
+                None
+            }
+            None => previous.deprecation_reason.clone(),
+        };
+
+        let alias = match request.alias {
+            Some(Some(alias)) if alias.trim().is_empty() => {
+                return Err(ApiError::BadRequest(
+                    "alias must not be empty; send null to remove it".to_string(),
+                ));
+            }
+            Some(alias) => alias.map(|a| a.trim().to_string()),
+            None => previous.alias.clone(),
+        };
+
+        let state = VkState {
+            status: next,
+            deprecation_reason,
+            alias,
+            valid_from: request.valid_from.unwrap_or(previous.valid_from),
+            deprecate_at,
+            revoke_at,
+        };
+        state.check()?;
+
+        let vk = apply_change(
+            &mut tx,
+            &previous,
+            &state,
+            "updated",
+            Some(actor),
+            justification.as_deref(),
+        )
         .await?;
+        tx.commit().await?;
 
         Ok(vk)
     }
 
+    /// Apply due scheduled transitions every `interval` in the background; zero disables it
+    pub fn spawn_scheduler(&self, interval: Duration) {
+        if interval.is_zero() {
+            return;
+        }
+
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.apply_schedules().await {
+                    tracing::warn!(error = %e, "Failed to apply scheduled VK transitions");
+                }
+            }
+        });
+    }
+
+    /// Deprecate and revoke VKs whose scheduled time has passed, like a manual update
+    /// with no actor. VKs locked by another replica are left for the next run.
+    pub async fn apply_schedules(&self) -> Result<usize, ApiError> {
+        let now = Utc::now();
+        let mut tx = self.pool.begin().await?;
+
+        let due = sqlx::query_as::<_, VerificationKey>(&format!(
+            r#"SELECT {VK_COLUMNS} FROM verification_keys
+               WHERE (status = 'active' AND deprecate_at <= $1)
+                  OR (status <> 'revoked' AND revoke_at <= $1)
+               ORDER BY created_at
+               LIMIT $2
+               FOR UPDATE SKIP LOCKED"#
+        ))
+        .bind(now)
+        .bind(SCHEDULE_BATCH_SIZE)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for previous in &due {
+            let state = VkState {
+                status: previous.status_at(now),
+                ..VkState::from(previous)
+            };
+            let vk = apply_change(&mut tx, previous, &state, "scheduled", None, None).await?;
+            tracing::info!(
+                vk_id = %vk.id,
+                from = %previous.status,
+                to = %vk.status,
+                "Applied scheduled VK transition"
+            );
+        }
+        tx.commit().await?;
+
+        Ok(due.len())
+    }
+
+    /// Audit log of a VK, oldest first
+    pub async fn history(&self, vk_id: Uuid) -> Result<Vec<VkEvent>, ApiError> {
+        let events = sqlx::query_as::<_, VkEvent>(&format!(
+            r#"SELECT {EVENT_COLUMNS}
+               FROM vk_events
+               WHERE vk_id = $1
+               ORDER BY created_at, id"#
+        ))
+        .bind(vk_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(events)
+    }
+
+    /// The VK as its audit log recorded it at `at`: status, reason, alias and schedule
+    /// from the last change made by then. Before the VK was registered, its state at
+    /// registration applies, so `valid_from` still decides whether it was usable. VKs
+    /// registered before the audit log only have a backfilled state from when it started;
+    /// earlier times are unknown.
+    pub async fn state_at(
+        &self,
+        vk: &VerificationKey,
+        at: DateTime<Utc>,
+    ) -> Result<VerificationKey, ApiError> {
+        // Latest event at or before `at`, falling back to the first event
+        let event = sqlx::query_as::<_, VkEvent>(&format!(
+            r#"SELECT {EVENT_COLUMNS}
+               FROM vk_events
+               WHERE vk_id = $1
+               ORDER BY created_at <= $2 DESC,
+                        CASE WHEN created_at <= $2 THEN created_at END DESC,
+                        created_at
+               LIMIT 1"#
+        ))
+        .bind(vk.id)
+        .bind(at)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(event) = event else {
+            return Ok(vk.clone());
+        };
+        if event.action == "backfilled" && event.created_at > at {
+            return Err(ApiError::VkHistoryUnknown(format!(
+                "history of this VK starts at {}",
+                event.created_at.to_rfc3339()
+            )));
+        }
+        Ok(VerificationKey {
+            status: event.new_status,
+            deprecation_reason: event.new_deprecation_reason,
+            alias: event.new_alias,
+            valid_from: event.new_valid_from,
+            deprecate_at: event.new_deprecate_at,
+            revoke_at: event.new_revoke_at,
+            ..vk.clone()
+        })
+    }
+
     /// Compute SHA256 hash of VK data
     pub fn compute_vk_hash(vk_data: &serde_json::Value) -> String {
         let mut hasher = Sha256::new();
@@ -285,3 +495,188 @@ impl VkService {
         format!("0x{}", hex::encode(hasher.finalize()))
     }
 }
+
+/// Settable state of a VK
+struct VkState {
+    status: VkStatus,
+    deprecation_reason: Option<String>,
+    alias: Option<String>,
+    valid_from: Option<DateTime<Utc>>,
+    deprecate_at: Option<DateTime<Utc>>,
+    revoke_at: Option<DateTime<Utc>>,
+}
+
+impl From<&VerificationKey> for VkState {
+    fn from(vk: &VerificationKey) -> Self {
+        VkState {
+            status: vk.status_enum(),
+            deprecation_reason: vk.deprecation_reason.clone(),
+            alias: vk.alias.clone(),
+            valid_from: vk.valid_from,
+            deprecate_at: vk.deprecate_at,
+            revoke_at: vk.revoke_at,
+        }
+    }
+}
+
+impl VkState {
+    /// A deprecated or revoked VK needs a reason, and so does a scheduled deprecation or
+    /// revocation; scheduled dates must be in order
+    fn check(&self) -> Result<(), ApiError> {
+        if let (Some(valid_from), Some(revoke_at)) = (self.valid_from, self.revoke_at)
+            && valid_from >= revoke_at
+        {
+            return Err(ApiError::BadRequest(
+                "valid_from must be before revoke_at".to_string(),
+            ));
+        }
+        if let (Some(deprecate_at), Some(revoke_at)) = (self.deprecate_at, self.revoke_at)
+            && deprecate_at > revoke_at
+        {
+            return Err(ApiError::BadRequest(
+                "deprecate_at must not be after revoke_at".to_string(),
+            ));
+        }
+
+        let scheduled = self.deprecate_at.is_some() || self.revoke_at.is_some();
+        match (self.status, self.deprecation_reason.is_some()) {
+            (VkStatus::Active, true) if !scheduled => Err(ApiError::BadRequest(
+                "an active VK only has a deprecation_reason while a deprecation or revocation is scheduled"
+                    .to_string(),
+            )),
+            (VkStatus::Active, false) if scheduled => Err(ApiError::BadRequest(
+                "a deprecation_reason is required to schedule a deprecation or revocation"
+                    .to_string(),
+            )),
+            (VkStatus::Deprecated | VkStatus::Revoked, false) => Err(ApiError::BadRequest(
+                format!("a deprecation_reason is required for a {} VK", self.status),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+/// Write `state` over `previous`, then record the change in the audit log and announce
+/// status and alias changes
+async fn apply_change(
+    tx: &mut Transaction<'_, Postgres>,
+    previous: &VerificationKey,
+    state: &VkState,
+    action: &str,
+    actor: Option<Uuid>,
+    justification: Option<&str>,
+) -> Result<VerificationKey, ApiError> {
+    let vk = sqlx::query_as::<_, VerificationKey>(&format!(
+        r#"UPDATE verification_keys
+           SET status = $2,
+               deprecation_reason = $3,
+               alias = $4,
+               active = ($2 = 'active'),
+               valid_from = $5,
+               deprecate_at = $6,
+               revoke_at = $7
+           WHERE id = $1
+           RETURNING {VK_COLUMNS}"#
+    ))
+    .bind(previous.id)
+    .bind(state.status.as_str())
+    .bind(&state.deprecation_reason)
+    .bind(&state.alias)
+    .bind(state.valid_from)
+    .bind(state.deprecate_at)
+    .bind(state.revoke_at)
+    .fetch_one(&mut **tx)
+    .await?;
+
+    record_vk_event(tx, action, Some(previous), &vk, actor, justification).await?;
+
+    if vk.status != previous.status {
+        let change = VkStatusChange {
+            id: vk.id,
+            prover: vk.prover.clone(),
+            version: vk.version.clone(),
+            proof_system: vk.proof_system.clone(),
+            vk_hash: vk.vk_hash.clone(),
+            alias: vk.alias.clone(),
+            previous_status: previous.status.clone(),
+            status: vk.status.clone(),
+            reason: vk.deprecation_reason.clone(),
+        };
+        publish_event(&mut **tx, "vk.status_changed", &vk.prover, &change).await?;
+        let webhook_event = match vk.status.as_str() {
+            "deprecated" => Some("vk.deprecated"),
+            "revoked" => Some("vk.revoked"),
+            _ => None,
+        };
+        if let Some(event_type) = webhook_event {
+            enqueue_event(&mut **tx, event_type, &change, Audience::Everyone).await?;
+        }
+    }
+    if vk.alias != previous.alias {
+        let change = VkAliasChange {
+            id: vk.id,
+            prover: vk.prover.clone(),
+            version: vk.version.clone(),
+            vk_hash: vk.vk_hash.clone(),
+            previous_alias: previous.alias.clone(),
+            alias: vk.alias.clone(),
+        };
+        publish_event(&mut **tx, "vk.alias_changed", &vk.prover, &change).await?;
+    }
+
+    Ok(vk)
+}
+
+/// Append a VK change to the audit log. `previous` is `None` for a new VK; updates that
+/// change nothing are not recorded.
+async fn record_vk_event(
+    tx: &mut Transaction<'_, Postgres>,
+    action: &str,
+    previous: Option<&VerificationKey>,
+    vk: &VerificationKey,
+    actor: Option<Uuid>,
+    justification: Option<&str>,
+) -> Result<(), ApiError> {
+    if let Some(previous) = previous
+        && previous.status == vk.status
+        && previous.deprecation_reason == vk.deprecation_reason
+        && previous.alias == vk.alias
+        && previous.valid_from == vk.valid_from
+        && previous.deprecate_at == vk.deprecate_at
+        && previous.revoke_at == vk.revoke_at
+    {
+        return Ok(());
+    }
+
+    sqlx::query(
+        r#"INSERT INTO vk_events
+           (vk_id, action, actor, old_status, new_status, old_deprecation_reason,
+            new_deprecation_reason, old_alias, new_alias, justification, old_valid_from,
+            new_valid_from, old_deprecate_at, new_deprecate_at, old_revoke_at, new_revoke_at)
+           VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)"#,
+    )
+    .bind(vk.id)
+    .bind(action)
+    .bind(actor)
+    .bind(previous.map(|p| &p.status))
+    .bind(&vk.status)
+    .bind(previous.and_then(|p| p.deprecation_reason.as_ref()))
+    .bind(&vk.deprecation_reason)
+    .bind(previous.and_then(|p| p.alias.as_ref()))
+    .bind(&vk.alias)
+    .bind(justification)
+    .bind(previous.and_then(|p| p.valid_from))
+    .bind(vk.valid_from)
+    .bind(previous.and_then(|p| p.deprecate_at))
+    .bind(vk.deprecate_at)
+    .bind(previous.and_then(|p| p.revoke_at))
+    .bind(vk.revoke_at)
+    .execute(&mut **tx)
+    .await?;
+
+    Ok(())
+}
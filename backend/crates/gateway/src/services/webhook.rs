@@ -0,0 +1,613 @@
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use sqlx::{PgExecutor, PgPool};
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+use crate::error::ApiError;
+use crate::models::{CreateWebhook, WebhookAttempt, WebhookDelivery, WebhookSubscription};
+
+/// Events subscriptions can ask for
+pub const WEBHOOK_EVENT_TYPES: &[&str] = &[
+    "verification.completed",
+    "vk.deprecated",
+    "vk.revoked",
+    "anchor.confirmed",
+];
+
+const SUBSCRIPTION_COLUMNS: &str = "id, user_id, url, event_types, secret, description, created_at";
+
+const DELIVERY_COLUMNS: &str = "id, event_id, event_type, payload, status, attempt_count, \
+    next_attempt_at, delivered_at, created_at";
+
+/// Settings of webhook delivery
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// How often the dispatcher looks for due deliveries
+    pub poll_interval: Duration,
+    /// Deliveries sent per poll
+    pub batch_size: i64,
+    pub request_timeout: Duration,
+    /// Attempts before a delivery is given up
+    pub max_attempts: i32,
+    /// Delay after the first failed attempt, doubled after each further one
+    pub retry_base: Duration,
+    pub retry_max: Duration,
+    /// Accept plain HTTP endpoints
+    pub allow_http: bool,
+    /// Accept endpoints on loopback, private and other non-public addresses
+    pub allow_private_targets: bool,
+}
+
+/// Which subscriptions receive an event
+#[derive(Debug, Clone, Copy)]
+pub enum Audience<'a> {
+    /// Every subscription to the event type
+    Everyone,
+    /// Subscriptions of this user
+    User(Uuid),
+    /// Subscriptions of users who verified this proof
+    ProofVerifiers(&'a str),
+}
+
+/// Body of every webhook request
+#[derive(Debug, Serialize)]
+struct Envelope<'a, T: Serialize> {
+    id: Uuid,
+    #[serde(rename = "type")]
+    event_type: &'a str,
+    created_at: chrono::DateTime<Utc>,
+    data: &'a T,
+}
+
+/// Queue an event for every subscription in `audience` that asked for `event_type`.
+/// Runs on the caller's executor, so an event can commit together with the change it
+/// describes. Returns the number of deliveries queued.
+pub async fn enqueue_event<'e, E, T>(
+    executor: E,
+    event_type: &str,
+    data: &T,
+    audience: Audience<'_>,
+) -> Result<u64, ApiError>
+where
+    E: PgExecutor<'e>,
+    T: Serialize,
+{
+    let event_id = Uuid::new_v4();
+    let payload = serde_json::to_value(Envelope {
+        id: event_id,
+        event_type,
+        created_at: Utc::now(),
+        data,
+    })
+    .map_err(|e| {
+        tracing::error!(event_type, error = %e, "Failed to serialize webhook event");
+        ApiError::Internal
+    })?;
+
+    let (user_id, proof_hash) = match audience {
+        Audience::Everyone => (None, None),
+        Audience::User(user_id) => (Some(user_id), None),
+        Audience::ProofVerifiers(proof_hash) => (None, Some(proof_hash)),
+    };
+
+    let queued = sqlx::query(
+        r#"INSERT INTO webhook_deliveries (subscription_id, event_id, event_type, payload)
+           SELECT s.id, $1, $2, $3
+           FROM webhook_subscriptions s
+           WHERE $2 = ANY(s.event_types)
+             AND ($4::uuid IS NULL OR s.user_id = $4)
+             AND ($5::text IS NULL OR EXISTS (
+                 SELECT 1 FROM verifications v
+                 WHERE v.user_id = s.user_id AND v.proof_hash = $5
+             ))"#,
+    )
+    .bind(event_id)
+    .bind(event_type)
+    .bind(&payload)
+    .bind(user_id)
+    .bind(proof_hash)
+    .execute(executor)
+    .await?
+    .rows_affected();
+
+    Ok(queued)
+}
+
+/// A delivery claimed by the dispatcher, with what is needed to send it
+#[derive(sqlx::FromRow)]
+struct DueDelivery {
+    id: Uuid,
+    event_type: String,
+    payload: serde_json::Value,
+    attempt_count: i32,
+    url: String,
+    secret: String,
+}
+
+/// What came of one delivery attempt
+struct AttemptOutcome {
+    response_status: Option<i32>,
+    error: Option<String>,
+    duration: Duration,
+}
+
+impl AttemptOutcome {
+    fn succeeded(&self) -> bool {
+        self.response_status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+/// Webhook subscriptions, their delivery log and the dispatcher that sends deliveries
+#[derive(Clone)]
+pub struct WebhookService {
+    pool: PgPool,
+    client: reqwest::Client,
+    config: Arc<WebhookConfig>,
+}
+
+impl WebhookService {
+    pub fn new(pool: PgPool, config: WebhookConfig) -> Result<Self, reqwest::Error> {
+        let mut builder = reqwest::Client::builder()
+            .timeout(config.request_timeout)
+            .redirect(reqwest::redirect::Policy::none())
+            .user_agent(concat!("prova-webhooks/", env!("CARGO_PKG_VERSION")));
+        if !config.allow_private_targets {
+            // Checked when connecting, so a name cannot resolve differently after validation
+            builder = builder.dns_resolver(Arc::new(PublicResolver));
+        }
+        let client = builder.build()?;
+
+        Ok(Self {
+            pool,
+            client,
+            config: Arc::new(config),
+        })
+    }
+
+    pub async fn create(
+        &self,
+        user_id: Uuid,
+        request: CreateWebhook,
+    ) -> Result<WebhookSubscription, ApiError> {
+        self.validate_url(&request.url)?;
+
+        if request.event_types.is_empty() {
+            return Err(ApiError::BadRequest("event_types must not be empty".to_string()));
+        }
+        let mut event_types = request.event_types;
+        if let Some(unknown) = event_types
+            .iter()
+            .find(|t| !WEBHOOK_EVENT_TYPES.contains(&t.as_str()))
+        {
+            return Err(ApiError::BadRequest(format!(
+                "Unknown event type '{}', expected one of {}",
+                unknown,
+                WEBHOOK_EVENT_TYPES.join(", ")
+            )));
+        }
+        event_types.sort();
+        event_types.dedup();
+
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            r#"INSERT INTO webhook_subscriptions (user_id, url, event_types, secret, description)
+               VALUES ($1, $2, $3, $4, $5)
+               RETURNING {SUBSCRIPTION_COLUMNS}"#
+        ))
+        .bind(user_id)
+        .bind(&request.url)
+        .bind(&event_types)
+        .bind(generate_secret())
+        .bind(&request.description)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    pub async fn list(&self, user_id: Uuid) -> Result<Vec<WebhookSubscription>, ApiError> {
+        let subscriptions = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {SUBSCRIPTION_COLUMNS} FROM webhook_subscriptions WHERE user_id = $1 ORDER BY created_at DESC"
+        ))
+        .bind(user_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(subscriptions)
+    }
+
+    /// A subscription of `user_id`; other users' subscriptions are not found
+    pub async fn get(
+        &self,
+        user_id: Uuid,
+        id: Uuid,
+    ) -> Result<Option<WebhookSubscription>, ApiError> {
+        let subscription = sqlx::query_as::<_, WebhookSubscription>(&format!(
+            "SELECT {SUBSCRIPTION_COLUMNS} FROM webhook_subscriptions WHERE id = $1 AND user_id = $2"
+        ))
+        .bind(id)
+        .bind(user_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(subscription)
+    }
+
+    /// Remove a subscription and its delivery log; false when there was none
+    pub async fn delete(&self, user_id: Uuid, id: Uuid) -> Result<bool, ApiError> {
+        let deleted = sqlx::query("DELETE FROM webhook_subscriptions WHERE id = $1 AND user_id = $2")
+            .bind(id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await?
+            .rows_affected();
+
+        Ok(deleted > 0)
+    }
+
+    /// Deliveries of a subscription, newest first, with every attempt; returns (deliveries, total)
+    pub async fn deliveries(
+        &self,
+        subscription_id: Uuid,
+        status: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<(WebhookDelivery, Vec<WebhookAttempt>)>, i64), ApiError> {
+        let deliveries = sqlx::query_as::<_, WebhookDelivery>(&format!(
+            r#"SELECT {DELIVERY_COLUMNS} FROM webhook_deliveries
+               WHERE subscription_id = $1 AND ($2::text IS NULL OR status = $2)
+               ORDER BY created_at DESC, id
+               LIMIT $3 OFFSET $4"#
+        ))
+        .bind(subscription_id)
+        .bind(status)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let total: i64 = sqlx::query_scalar(
+            r#"SELECT COUNT(*) FROM webhook_deliveries
+               WHERE subscription_id = $1 AND ($2::text IS NULL OR status = $2)"#,
+        )
+        .bind(subscription_id)
+        .bind(status)
+        .fetch_one(&self.pool)
+        .await?;
+
+        let ids: Vec<Uuid> = deliveries.iter().map(|d| d.id).collect();
+        let attempts = sqlx::query_as::<_, WebhookAttempt>(
+            r#"SELECT delivery_id, attempt, response_status, error, duration_ms, attempted_at
+               FROM webhook_delivery_attempts
+               WHERE delivery_id = ANY($1)
+               ORDER BY attempt"#,
+        )
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let mut by_delivery: HashMap<Uuid, Vec<WebhookAttempt>> = HashMap::new();
+        for attempt in attempts {
+            by_delivery.entry(attempt.delivery_id).or_default().push(attempt);
+        }
+        let deliveries = deliveries
+            .into_iter()
+            .map(|delivery| {
+                let attempts = by_delivery.remove(&delivery.id).unwrap_or_default();
+                (delivery, attempts)
+            })
+            .collect();
+
+        Ok((deliveries, total))
+    }
+
+    pub fn spawn_dispatcher(&self) {
+        let service = self.clone();
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(service.config.poll_interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = service.dispatch_due().await {
+                    tracing::warn!(error = %e, "Webhook dispatch failed");
+                }
+            }
+        });
+    }
+
+    /// Send every delivery that is due. Deliveries are leased before sending, so several
+    /// gateway replicas never send the same attempt.
+    pub async fn dispatch_due(&self) -> Result<usize, ApiError> {
+        let lease = self.config.request_timeout + Duration::from_secs(30);
+        let due = sqlx::query_as::<_, DueDelivery>(
+            r#"UPDATE webhook_deliveries d
+               SET next_attempt_at = NOW() + make_interval(secs => $2)
+               FROM webhook_subscriptions s
+               WHERE d.id IN (
+                   SELECT id FROM webhook_deliveries
+                   WHERE status = 'pending' AND next_attempt_at <= NOW()
+                   ORDER BY next_attempt_at
+                   LIMIT $1
+                   FOR UPDATE SKIP LOCKED
+               )
+                 AND s.id = d.subscription_id
+               RETURNING d.id, d.event_type, d.payload, d.attempt_count, s.url, s.secret"#,
+        )
+        .bind(self.config.batch_size)
+        .bind(lease.as_secs_f64())
+        .fetch_all(&self.pool)
+        .await?;
+
+        let count = due.len();
+        let mut sends = tokio::task::JoinSet::new();
+        for delivery in due {
+            let service = self.clone();
+            sends.spawn(async move {
+                let outcome = service.send(&delivery).await;
+                if let Err(e) = service.record_attempt(&delivery, outcome).await {
+                    tracing::error!(delivery_id = %delivery.id, error = %e, "Failed to record webhook attempt");
+                }
+            });
+        }
+        while sends.join_next().await.is_some() {}
+
+        Ok(count)
+    }
+
+    async fn send(&self, delivery: &DueDelivery) -> AttemptOutcome {
+        let body = delivery.payload.to_string();
+        let timestamp = Utc::now().timestamp();
+        let signature = sign(&delivery.secret, timestamp, &body);
+
+        let started = Instant::now();
+        if !self.config.allow_private_targets
+            && let Err(e) = check_ip_literal(&delivery.url)
+        {
+            return AttemptOutcome {
+                response_status: None,
+                error: Some(e),
+                duration: started.elapsed(),
+            };
+        }
+
+        let response = self
+            .client
+            .post(&delivery.url)
+            .header("Content-Type", "application/json")
+            .header("X-Prova-Event", &delivery.event_type)
+            .header("X-Prova-Delivery", delivery.id.to_string())
+            .header("X-Prova-Timestamp", timestamp.to_string())
+            .header("X-Prova-Signature", format!("sha256={}", signature))
+            .body(body)
+            .send()
+            .await;
+
+        match response {
+            Ok(response) => AttemptOutcome {
+                response_status: Some(response.status().as_u16() as i32),
+                error: None,
+                duration: started.elapsed(),
+            },
+            Err(e) => AttemptOutcome {
+                response_status: None,
+                error: Some(error_chain(&e)),
+                duration: started.elapsed(),
+            },
+        }
+    }
+
+    /// Log the attempt and mark the delivery delivered, failed for good, or due again
+    async fn record_attempt(
+        &self,
+        delivery: &DueDelivery,
+        outcome: AttemptOutcome,
+    ) -> Result<(), ApiError> {
+        let attempt = delivery.attempt_count + 1;
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"INSERT INTO webhook_delivery_attempts
+               (delivery_id, attempt, response_status, error, duration_ms)
+               VALUES ($1, $2, $3, $4, $5)"#,
+        )
+        .bind(delivery.id)
+        .bind(attempt)
+        .bind(outcome.response_status)
+        .bind(&outcome.error)
+        .bind(outcome.duration.as_millis().min(i32::MAX as u128) as i32)
+        .execute(&mut *tx)
+        .await?;
+
+        if outcome.succeeded() {
+            sqlx::query(
+                r#"UPDATE webhook_deliveries
+                   SET status = 'delivered', attempt_count = $2, delivered_at = NOW()
+                   WHERE id = $1"#,
+            )
+            .bind(delivery.id)
+            .bind(attempt)
+            .execute(&mut *tx)
+            .await?;
+        } else if attempt >= self.config.max_attempts {
+            tracing::warn!(delivery_id = %delivery.id, url = %delivery.url, attempt, "Giving up webhook delivery");
+            sqlx::query(
+                "UPDATE webhook_deliveries SET status = 'failed', attempt_count = $2 WHERE id = $1",
+            )
+            .bind(delivery.id)
+            .bind(attempt)
+            .execute(&mut *tx)
+            .await?;
+        } else {
+            sqlx::query(
+                r#"UPDATE webhook_deliveries
+                   SET attempt_count = $2, next_attempt_at = NOW() + make_interval(secs => $3)
+                   WHERE id = $1"#,
+            )
+            .bind(delivery.id)
+            .bind(attempt)
+            .bind(self.retry_delay(attempt).as_secs_f64())
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// `retry_base * 2^(attempt - 1)`, capped at `retry_max`
+    fn retry_delay(&self, attempt: i32) -> Duration {
+        let exponent = (attempt - 1).clamp(0, 30) as u32;
+        self.config
+            .retry_base
+            .saturating_mul(2u32.saturating_pow(exponent))
+            .min(self.config.retry_max)
+    }
+
+    fn validate_url(&self, url: &str) -> Result<(), ApiError> {
+        let parsed = reqwest::Url::parse(url)
+            .map_err(|_| ApiError::BadRequest("url is not a valid URL".to_string()))?;
+
+        match parsed.scheme() {
+            "https" => {}
+            "http" if self.config.allow_http => {}
+            _ => return Err(ApiError::BadRequest("url must use https".to_string())),
+        }
+        if !self.config.allow_private_targets {
+            check_ip_literal(url).map_err(ApiError::BadRequest)?;
+        }
+        Ok(())
+    }
+}
+
+/// Resolves webhook hosts to their public addresses only, so endpoints cannot reach the
+/// gateway's own network
+struct PublicResolver;
+
+impl reqwest::dns::Resolve for PublicResolver {
+    fn resolve(&self, name: reqwest::dns::Name) -> reqwest::dns::Resolving {
+        Box::pin(async move {
+            let host = name.as_str();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host, 0)).await?.collect();
+            let public: Vec<SocketAddr> =
+                addrs.into_iter().filter(|a| is_public(a.ip())).collect();
+            if public.is_empty() {
+                return Err(format!("{} does not resolve to a public address", host).into());
+            }
+            Ok(Box::new(public.into_iter()) as reqwest::dns::Addrs)
+        })
+    }
+}
+
+/// Hosts written as IP addresses skip the resolver, so they are checked on their own
+fn check_ip_literal(url: &str) -> Result<(), String> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| "url is not a valid URL".to_string())?;
+    let host = parsed.host_str().unwrap_or_default();
+    let Ok(ip) = host.trim_start_matches('[').trim_end_matches(']').parse::<IpAddr>() else {
+        return Ok(());
+    };
+    if is_public(ip) {
+        Ok(())
+    } else {
+        Err(format!("url must not point at a non-public address ({})", ip))
+    }
+}
+
+/// Whether an address is reachable on the public internet: not loopback, private,
+/// link-local (which includes cloud metadata endpoints), shared, reserved or multicast
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || a == 0
+                || a >= 240
+                || (a == 100 && (64..128).contains(&b))
+                || (a == 198 && (18..20).contains(&b)))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(v4) = ip.to_ipv4_mapped() {
+                return is_public(IpAddr::V4(v4));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_multicast()
+                || (first & 0xfe00) == 0xfc00
+                || (first & 0xffc0) == 0xfe80
+                || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+        }
+    }
+}
+
+/// An error with every source it wraps, since reqwest hides the cause behind its own message
+fn error_chain(error: &dyn std::error::Error) -> String {
+    let mut message = error.to_string();
+    let mut source = error.source();
+    while let Some(cause) = source {
+        message.push_str(": ");
+        message.push_str(&cause.to_string());
+        source = cause.source();
+    }
+    message
+}
+
+/// Hex HMAC-SHA256 of `{timestamp}.{body}` under the subscription secret
+fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+fn generate_secret() -> String {
+    use rand::Rng;
+    let random_bytes: [u8; 32] = rand::rng().random();
+    format!("whsec_{}", hex::encode(random_bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn internal_addresses_are_not_public() {
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public(ip.parse().unwrap()), "{ip} counted as public");
+        }
+        for ip in ["93.184.216.34", "2606:2800:220:1::1"] {
+            assert!(is_public(ip.parse().unwrap()), "{ip} counted as non-public");
+        }
+    }
+
+    #[test]
+    fn ip_literal_urls_are_checked() {
+        assert!(check_ip_literal("http://127.0.0.1:8080/hook").is_err());
+        assert!(check_ip_literal("https://[::1]/hook").is_err());
+        assert!(check_ip_literal("https://93.184.216.34/hook").is_ok());
+        assert!(check_ip_literal("https://hooks.example.com/hook").is_ok());
+    }
+}
@@ -0,0 +1,433 @@
+//! Merkle trees over Prova verification results.
+//!
+//! Each leaf commits to one verification outcome: proof hash, VK hash and validity.
+//! Leaves and inner nodes are hashed with SHA-256 under distinct prefixes, so a leaf can
+//! never be passed off as an inner node. A node without a sibling is carried up to the
+//! next level unchanged instead of being paired with a copy of itself.
+//!
+//! Clients check an inclusion proof from the gateway with `InclusionProof::verify`, then
+//! compare its `merkle_root` with the root published in the anchor transaction.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+
+pub type Hash = [u8; 32];
+
+/// Prefix of leaf hashes
+pub const LEAF_PREFIX: u8 = 0x00;
+/// Prefix of inner node hashes
+pub const NODE_PREFIX: u8 = 0x01;
+
+#[derive(Debug, thiserror::Error)]
+pub enum MerkleError {
+    #[error("Not a 0x-prefixed 32-byte hex hash: {0}")]
+    InvalidHash(String),
+    #[error("Leaf encoding does not match the proof hash, VK hash and validity")]
+    LeafMismatch,
+    #[error("Path does not lead to the Merkle root")]
+    RootMismatch,
+}
+
+/// A verification outcome committed to by the tree
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Leaf {
+    pub proof_hash: Hash,
+    pub vk_hash: Hash,
+    pub valid: bool,
+}
+
+impl Leaf {
+    /// Build a leaf from the hex hashes used throughout the API
+    pub fn new(proof_hash: &str, vk_hash: &str, valid: bool) -> Result<Self, MerkleError> {
+        Ok(Self {
+            proof_hash: parse_hash(proof_hash)?,
+            vk_hash: parse_hash(vk_hash)?,
+            valid,
+        })
+    }
+
+    /// `proof_hash (32 bytes) || vk_hash (32 bytes) || valid (1 byte, 0x01 or 0x00)`
+    pub fn encode(&self) -> [u8; 65] {
+        let mut encoded = [0u8; 65];
+        encoded[..32].copy_from_slice(&self.proof_hash);
+        encoded[32..64].copy_from_slice(&self.vk_hash);
+        encoded[64] = self.valid as u8;
+        encoded
+    }
+
+    /// `sha256(0x00 || encode())`
+    pub fn hash(&self) -> Hash {
+        let mut hasher = Sha256::new();
+        hasher.update([LEAF_PREFIX]);
+        hasher.update(self.encode());
+        hasher.finalize().into()
+    }
+}
+
+/// `sha256(0x01 || left || right)`
+pub fn node_hash(left: &Hash, right: &Hash) -> Hash {
+    let mut hasher = Sha256::new();
+    hasher.update([NODE_PREFIX]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Which side of the running hash a sibling goes on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// One step of an inclusion proof, from the leaf towards the root
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sibling {
+    pub side: Side,
+    #[serde(serialize_with = "serialize_hash", deserialize_with = "deserialize_hash")]
+    pub hash: Hash,
+}
+
+/// All levels of a tree, leaves first
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    levels: Vec<Vec<Hash>>,
+}
+
+impl MerkleTree {
+    /// Build a tree over leaf hashes, in order
+    pub fn new(leaves: Vec<Hash>) -> Self {
+        let mut levels = vec![leaves];
+        while let Some(level) = levels.last().filter(|level| level.len() > 1) {
+            let next = level
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => node_hash(left, right),
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two nodes"),
+                })
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn len(&self) -> usize {
+        self.levels[0].len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// None for an empty tree
+    pub fn root(&self) -> Option<Hash> {
+        self.levels.last().and_then(|level| level.first()).copied()
+    }
+
+    /// Siblings needed to recompute the root from the leaf at `index`
+    pub fn proof(&self, index: usize) -> Option<Vec<Sibling>> {
+        if index >= self.len() {
+            return None;
+        }
+
+        let mut path = Vec::new();
+        let mut position = index;
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = position ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                path.push(Sibling {
+                    side: if sibling < position { Side::Left } else { Side::Right },
+                    hash: *hash,
+                });
+            }
+            position /= 2;
+        }
+        Some(path)
+    }
+}
+
+/// Recompute the root from a leaf hash and its path
+pub fn compute_root(leaf_hash: &Hash, path: &[Sibling]) -> Hash {
+    path.iter().fold(*leaf_hash, |running, sibling| match sibling.side {
+        Side::Left => node_hash(&sibling.hash, &running),
+        Side::Right => node_hash(&running, &sibling.hash),
+    })
+}
+
+/// Whether `leaf` is committed to by `root` through `path`
+pub fn verify_inclusion(leaf: &Leaf, path: &[Sibling], root: &Hash) -> bool {
+    compute_root(&leaf.hash(), path) == *root
+}
+
+/// Everything needed to check offline that a verification result is in an anchored root,
+/// as served by `GET /v1/proofs/{hash}/inclusion`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InclusionProof {
+    pub proof_hash: String,
+    pub vk_hash: String,
+    pub valid: bool,
+    /// `Leaf::encode`, 0x-prefixed hex
+    pub leaf_encoding: String,
+    #[serde(serialize_with = "serialize_hash", deserialize_with = "deserialize_hash")]
+    pub leaf_hash: Hash,
+    /// Siblings from the leaf up to the root
+    pub path: Vec<Sibling>,
+    #[serde(serialize_with = "serialize_hash", deserialize_with = "deserialize_hash")]
+    pub merkle_root: Hash,
+}
+
+impl InclusionProof {
+    pub fn new(leaf: &Leaf, path: Vec<Sibling>, merkle_root: Hash) -> Self {
+        Self {
+            proof_hash: to_hex(&leaf.proof_hash),
+            vk_hash: to_hex(&leaf.vk_hash),
+            valid: leaf.valid,
+            leaf_encoding: format!("0x{}", hex::encode(leaf.encode())),
+            leaf_hash: leaf.hash(),
+            path,
+            merkle_root,
+        }
+    }
+
+    /// Check that the encoding and leaf hash match the claimed result, and that the path
+    /// leads to `merkle_root`. Compare `merkle_root` with the value on chain separately.
+    pub fn verify(&self) -> Result<(), MerkleError> {
+        let leaf = Leaf::new(&self.proof_hash, &self.vk_hash, self.valid)?;
+        let encoding = format!("0x{}", hex::encode(leaf.encode()));
+        if !encoding.eq_ignore_ascii_case(&self.leaf_encoding) || leaf.hash() != self.leaf_hash {
+            return Err(MerkleError::LeafMismatch);
+        }
+        if !verify_inclusion(&leaf, &self.path, &self.merkle_root) {
+            return Err(MerkleError::RootMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// Parse a 0x-prefixed 32-byte hex hash
+pub fn parse_hash(value: &str) -> Result<Hash, MerkleError> {
+    let invalid = || MerkleError::InvalidHash(value.to_string());
+    let hex = value.strip_prefix("0x").ok_or_else(invalid)?;
+    let bytes = hex::decode(hex).map_err(|_| invalid())?;
+    bytes.try_into().map_err(|_| invalid())
+}
+
+/// Format a hash as 0x-prefixed lowercase hex
+pub fn to_hex(hash: &Hash) -> String {
+    format!("0x{}", hex::encode(hash))
+}
+
+fn serialize_hash<S: Serializer>(hash: &Hash, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&to_hex(hash))
+}
+
+fn deserialize_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Hash, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_hash(&value).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaf(i: u8) -> Leaf {
+        Leaf {
+            proof_hash: [i; 32],
+            vk_hash: [0xaa; 32],
+            valid: i.is_multiple_of(2),
+        }
+    }
+
+    fn tree_of(count: u8) -> (Vec<Leaf>, MerkleTree) {
+        let leaves: Vec<Leaf> = (0..count).map(leaf).collect();
+        let tree = MerkleTree::new(leaves.iter().map(Leaf::hash).collect());
+        (leaves, tree)
+    }
+
+    /// Every leaf's path leads to the root
+    fn assert_round_trip(count: u8) {
+        let (leaves, tree) = tree_of(count);
+        let root = tree.root().unwrap();
+        for (index, leaf) in leaves.iter().enumerate() {
+            let path = tree.proof(index).unwrap();
+            assert!(verify_inclusion(leaf, &path, &root), "leaf {index} of {count}");
+        }
+    }
+
+    #[test]
+    fn empty_tree_has_no_root() {
+        let tree = MerkleTree::new(Vec::new());
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), None);
+        assert_eq!(tree.proof(0), None);
+    }
+
+    #[test]
+    fn single_leaf_is_the_root() {
+        let (leaves, tree) = tree_of(1);
+        assert_eq!(tree.root(), Some(leaves[0].hash()));
+        assert_eq!(tree.proof(0), Some(Vec::new()));
+        assert_round_trip(1);
+    }
+
+    #[test]
+    fn two_leaves_hash_into_one_node() {
+        let (leaves, tree) = tree_of(2);
+        assert_eq!(tree.root(), Some(node_hash(&leaves[0].hash(), &leaves[1].hash())));
+        assert_eq!(
+            tree.proof(0).unwrap(),
+            vec![Sibling {
+                side: Side::Right,
+                hash: leaves[1].hash(),
+            }]
+        );
+        assert_round_trip(2);
+    }
+
+    #[test]
+    fn odd_node_is_carried_up_unpaired() {
+        let (leaves, tree) = tree_of(3);
+        let left = node_hash(&leaves[0].hash(), &leaves[1].hash());
+        assert_eq!(tree.root(), Some(node_hash(&left, &leaves[2].hash())));
+        assert_eq!(
+            tree.proof(2).unwrap(),
+            vec![Sibling {
+                side: Side::Left,
+                hash: left,
+            }]
+        );
+    }
+
+    #[test]
+    fn round_trips_for_odd_and_even_sizes() {
+        for count in [3, 4, 5, 7, 8, 13] {
+            assert_round_trip(count);
+        }
+    }
+
+    #[test]
+    fn proof_out_of_range_is_none() {
+        let (_, tree) = tree_of(3);
+        assert_eq!(tree.proof(3), None);
+    }
+
+    #[test]
+    fn tampered_sibling_fails() {
+        let (leaves, tree) = tree_of(5);
+        let root = tree.root().unwrap();
+        let mut path = tree.proof(1).unwrap();
+        path[0].hash[0] ^= 1;
+        assert!(!verify_inclusion(&leaves[1], &path, &root));
+    }
+
+    #[test]
+    fn swapped_sibling_side_fails() {
+        let (leaves, tree) = tree_of(4);
+        let root = tree.root().unwrap();
+        let mut path = tree.proof(0).unwrap();
+        path[0].side = Side::Left;
+        assert!(!verify_inclusion(&leaves[0], &path, &root));
+    }
+
+    #[test]
+    fn tampered_leaf_fails() {
+        let (leaves, tree) = tree_of(5);
+        let root = tree.root().unwrap();
+        let path = tree.proof(2).unwrap();
+
+        let flipped = Leaf {
+            valid: !leaves[2].valid,
+            ..leaves[2]
+        };
+        assert!(!verify_inclusion(&flipped, &path, &root));
+        assert!(!verify_inclusion(&leaves[3], &path, &root));
+    }
+
+    fn inclusion_proof(count: u8, index: usize) -> InclusionProof {
+        let (leaves, tree) = tree_of(count);
+        InclusionProof::new(&leaves[index], tree.proof(index).unwrap(), tree.root().unwrap())
+    }
+
+    #[test]
+    fn compute_root_follows_sides() {
+        let (leaves, tree) = tree_of(3);
+        let root = tree.root().unwrap();
+        assert_eq!(compute_root(&leaves[0].hash(), &[]), leaves[0].hash());
+        for (index, leaf) in leaves.iter().enumerate() {
+            assert_eq!(compute_root(&leaf.hash(), &tree.proof(index).unwrap()), root);
+        }
+    }
+
+    #[test]
+    fn inclusion_proof_verifies_for_every_leaf() {
+        for count in [1, 2, 3, 6, 7] {
+            for index in 0..count as usize {
+                assert!(inclusion_proof(count, index).verify().is_ok(), "leaf {index} of {count}");
+            }
+        }
+    }
+
+    #[test]
+    fn inclusion_proof_survives_json() {
+        let proof = inclusion_proof(5, 4);
+        let json = serde_json::to_string(&proof).unwrap();
+        let parsed: InclusionProof = serde_json::from_str(&json).unwrap();
+        assert!(parsed.verify().is_ok());
+        assert_eq!(parsed.merkle_root, proof.merkle_root);
+    }
+
+    #[test]
+    fn inclusion_proof_accepts_uppercase_encoding() {
+        let mut proof = inclusion_proof(2, 0);
+        proof.leaf_encoding = format!("0x{}", proof.leaf_encoding[2..].to_uppercase());
+        assert!(proof.verify().is_ok());
+    }
+
+    #[test]
+    fn claimed_result_must_match_leaf() {
+        let mut flipped = inclusion_proof(3, 1);
+        flipped.valid = !flipped.valid;
+        assert!(matches!(flipped.verify(), Err(MerkleError::LeafMismatch)));
+
+        let mut other_vk = inclusion_proof(3, 1);
+        other_vk.vk_hash = to_hex(&[0xbb; 32]);
+        assert!(matches!(other_vk.verify(), Err(MerkleError::LeafMismatch)));
+
+        let mut bad_hash = inclusion_proof(3, 1);
+        bad_hash.leaf_hash[31] ^= 1;
+        assert!(matches!(bad_hash.verify(), Err(MerkleError::LeafMismatch)));
+    }
+
+    #[test]
+    fn tampered_path_or_root_fails() {
+        let mut sibling = inclusion_proof(4, 2);
+        sibling.path[1].hash[0] ^= 1;
+        assert!(matches!(sibling.verify(), Err(MerkleError::RootMismatch)));
+
+        let mut dropped = inclusion_proof(4, 2);
+        dropped.path.pop();
+        assert!(matches!(dropped.verify(), Err(MerkleError::RootMismatch)));
+
+        let mut root = inclusion_proof(4, 2);
+        root.merkle_root[0] ^= 1;
+        assert!(matches!(root.verify(), Err(MerkleError::RootMismatch)));
+    }
+
+    #[test]
+    fn malformed_hashes_are_rejected() {
+        let mut proof = inclusion_proof(2, 0);
+        proof.proof_hash = "0x1234".to_string();
+        assert!(matches!(proof.verify(), Err(MerkleError::InvalidHash(_))));
+
+        assert!(parse_hash(&"ab".repeat(32)).is_err());
+        assert!(serde_json::from_str::<Sibling>(r#"{"side":"left","hash":"0xzz"}"#).is_err());
+    }
+
+    #[test]
+    fn leaf_cannot_pose_as_inner_node() {
+        let (leaves, _) = tree_of(2);
+        assert_ne!(leaves[0].hash(), node_hash(&leaves[0].proof_hash, &leaves[0].vk_hash));
+    }
+}
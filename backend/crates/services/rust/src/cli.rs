@@ -0,0 +1,396 @@
+//! Command line interface for prova-rust.
+//!
+//! Without a subcommand the gRPC server is started. The `manifest` subcommands
+//! help prover teams author and check an artifacts directory before deploying it.
+
+use clap::{Args, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+
+use crate::conformance::{self, ConformanceSuite};
+use crate::manifest::{self, Manifest, ManifestError};
+use crate::service;
+
+#[derive(Debug, Parser)]
+#[command(name = "prova-rust", about = "Generic Rust verifier runner for Prova")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Start the gRPC verifier service (default)
+    Serve,
+    /// Author and validate manifest.yaml files
+    #[command(subcommand)]
+    Manifest(ManifestCommand),
+    /// Run tests/conformance.yaml against the manifest versions
+    Conformance {
+        #[command(flatten)]
+        artifacts: ArtifactsArgs,
+        /// Only run against this version (defaults to all versions)
+        #[arg(long)]
+        version: Option<String>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ArtifactsArgs {
+    /// Artifacts directory containing manifest.yaml
+    #[arg(long, env = "ARTIFACTS_DIR", default_value = "/artifacts")]
+    pub artifacts_dir: String,
+}
+
+impl ArtifactsArgs {
+    fn manifest_path(&self) -> PathBuf {
+        Path::new(&self.artifacts_dir).join("manifest.yaml")
+    }
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ManifestCommand {
+    /// Scaffold a new manifest.yaml
+    Init {
+        #[command(flatten)]
+        artifacts: ArtifactsArgs,
+        /// Prover name (e.g. zisk, sp1)
+        #[arg(long)]
+        prover: String,
+        /// Initial version string
+        #[arg(long, default_value = "v0.1.0")]
+        version: String,
+        /// Verifier binary path, relative to the artifacts directory
+        #[arg(long, default_value = "bin/verifier")]
+        bin_path: String,
+        /// Overwrite an existing manifest.yaml
+        #[arg(long)]
+        force: bool,
+    },
+    /// Compute the sha256 of a version's binary and write it to the manifest
+    Checksum {
+        #[command(flatten)]
+        artifacts: ArtifactsArgs,
+        /// Version to update (defaults to the active version)
+        #[arg(long)]
+        version: Option<String>,
+        /// Print the checksum without modifying manifest.yaml
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Load the manifest and validate the binary of every version
+    Validate {
+        #[command(flatten)]
+        artifacts: ArtifactsArgs,
+    },
+    /// Verify a local proof with a manifest version, without starting gRPC
+    Test {
+        #[command(flatten)]
+        artifacts: ArtifactsArgs,
+        /// Proof file passed to the verifier as-is
+        #[arg(long)]
+        proof: PathBuf,
+        /// JSON file containing an array of public input strings
+        #[arg(long)]
+        inputs: Option<PathBuf>,
+        /// Version to run (defaults to the active version)
+        #[arg(long)]
+        version: Option<String>,
+    },
+}
+
+pub async fn run_manifest_command(command: ManifestCommand) -> Result<(), Box<dyn std::error::Error>> {
+    match command {
+        ManifestCommand::Init {
+            artifacts,
+            prover,
+            version,
+            bin_path,
+            force,
+        } => init(&artifacts, &prover, &version, &bin_path, force),
+        ManifestCommand::Checksum {
+            artifacts,
+            version,
+            dry_run,
+        } => checksum(&artifacts, version.as_deref(), dry_run),
+        ManifestCommand::Validate { artifacts } => validate(&artifacts),
+        ManifestCommand::Test {
+            artifacts,
+            proof,
+            inputs,
+            version,
+        } => test(&artifacts, &proof, inputs.as_deref(), version.as_deref()).await,
+    }
+}
+
+pub async fn run_conformance(
+    artifacts: ArtifactsArgs,
+    version: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::load(&artifacts.manifest_path().to_string_lossy())?;
+    let suite = ConformanceSuite::load(&artifacts.artifacts_dir)?;
+
+    let selected: Vec<&manifest::Version> = match version.as_deref() {
+        Some(name) => vec![select_version(&manifest, Some(name))?],
+        None => manifest.versions.iter().collect(),
+    };
+
+    // Never execute a binary that fails its integrity check
+    let mut runnable = Vec::new();
+    let mut broken = 0;
+    for version in selected {
+        match manifest::validate_binary(&artifacts.artifacts_dir, version) {
+            Ok(()) => runnable.push(version),
+            Err(e) => {
+                broken += 1;
+                println!("SKIP  {}  {}", version.version, e);
+            }
+        }
+    }
+
+    let report = conformance::run(&artifacts.artifacts_dir, &runnable, &suite).await;
+    report.print();
+
+    if report.failed() > 0 || broken > 0 {
+        return Err(format!(
+            "{} conformance cases failed, {} versions skipped",
+            report.failed(),
+            broken
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+fn init(
+    artifacts: &ArtifactsArgs,
+    prover: &str,
+    version: &str,
+    bin_path: &str,
+    force: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_path = artifacts.manifest_path();
+    if manifest_path.exists() && !force {
+        return Err(format!(
+            "{} already exists (use --force to overwrite)",
+            manifest_path.display()
+        )
+        .into());
+    }
+
+    // Fill in the checksum right away if the binary is already in place
+    let full_bin_path = Path::new(&artifacts.artifacts_dir).join(bin_path);
+    let sha256 = if full_bin_path.is_file() {
+        manifest::compute_binary_checksum(&full_bin_path)?
+    } else {
+        "0".repeat(64)
+    };
+
+    std::fs::create_dir_all(&artifacts.artifacts_dir)?;
+    std::fs::write(
+        &manifest_path,
+        scaffold_manifest(prover, version, bin_path, &sha256),
+    )?;
+
+    println!("wrote {}", manifest_path.display());
+    if !full_bin_path.is_file() {
+        println!(
+            "binary {} not found; run `prova-rust manifest checksum` once it is in place",
+            full_bin_path.display()
+        );
+    }
+
+    Ok(())
+}
+
+fn checksum(
+    artifacts: &ArtifactsArgs,
+    version: Option<&str>,
+    dry_run: bool,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_path = artifacts.manifest_path();
+    let manifest = Manifest::load(&manifest_path.to_string_lossy())?;
+    let selected = select_version(&manifest, version)?;
+
+    let bin_path = Path::new(&artifacts.artifacts_dir).join(&selected.bin_path);
+    let sha256 = manifest::compute_binary_checksum(&bin_path)?;
+
+    println!("{}  {}", sha256, bin_path.display());
+
+    if dry_run || selected.sha256.eq_ignore_ascii_case(&sha256) {
+        return Ok(());
+    }
+
+    // Edit the file textually so comments and formatting are preserved
+    let content = std::fs::read_to_string(&manifest_path)?;
+    let updated = set_version_checksum(&content, &selected.version, &sha256)?;
+    std::fs::write(&manifest_path, updated)?;
+
+    println!(
+        "updated sha256 for version {} in {}",
+        selected.version,
+        manifest_path.display()
+    );
+
+    Ok(())
+}
+
+fn validate(artifacts: &ArtifactsArgs) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest_path = artifacts.manifest_path();
+    let manifest = Manifest::load(&manifest_path.to_string_lossy())?;
+
+    match manifest.description.as_deref().filter(|d| !d.is_empty()) {
+        Some(description) => println!("manifest ok: {} ({})", manifest.prover, description),
+        None => println!("manifest ok: {}", manifest.prover),
+    }
+
+    let mut failures = 0;
+    for version in &manifest.versions {
+        let marker = if version.active { " (active)" } else { "" };
+        match manifest::validate_binary(&artifacts.artifacts_dir, version) {
+            Ok(()) => println!("  {}{}: ok", version.version, marker),
+            Err(e) => {
+                failures += 1;
+                println!("  {}{}: {}", version.version, marker, e);
+            }
+        }
+    }
+
+    if failures > 0 {
+        return Err(format!(
+            "{} of {} versions failed validation",
+            failures,
+            manifest.versions.len()
+        )
+        .into());
+    }
+
+    Ok(())
+}
+
+async fn test(
+    artifacts: &ArtifactsArgs,
+    proof: &Path,
+    inputs: Option<&Path>,
+    version: Option<&str>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let manifest = Manifest::load(&artifacts.manifest_path().to_string_lossy())?;
+    let selected = select_version(&manifest, version)?;
+    manifest::validate_binary(&artifacts.artifacts_dir, selected)?;
+
+    let proof_bytes = std::fs::read(proof)?;
+    let public_inputs: Vec<String> = match inputs {
+        Some(path) => serde_json::from_slice(&std::fs::read(path)?)?,
+        None => Vec::new(),
+    };
+
+    let started = std::time::Instant::now();
+    let outcome = service::execute_verifier(
+        &artifacts.artifacts_dir,
+        selected,
+        &proof_bytes,
+        &public_inputs,
+    )
+    .await
+    .map_err(|status| status.message().to_string())?;
+
+    println!("{}", serde_json::to_string_pretty(&outcome)?);
+    println!(
+        "version {} finished in {} ms",
+        selected.version,
+        started.elapsed().as_millis()
+    );
+
+    Ok(())
+}
+
+fn select_version<'a>(
+    manifest: &'a Manifest,
+    version: Option<&str>,
+) -> Result<&'a manifest::Version, ManifestError> {
+    match version {
+        Some(name) => manifest.versions.iter().find(|v| v.version == name),
+        None => manifest.versions.iter().find(|v| v.active),
+    }
+    .ok_or_else(|| {
+        ManifestError::ValidationError(format!(
+            "version {} not found in manifest",
+            version.unwrap_or("(active)")
+        ))
+    })
+}
+
+/// Replace the sha256 value of one version entry, keeping the rest of the file intact
+fn set_version_checksum(content: &str, version: &str, sha256: &str) -> Result<String, ManifestError> {
+    let mut in_version = false;
+    let mut replaced = false;
+    let mut lines = Vec::new();
+
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+
+        if let Some(value) = trimmed
+            .strip_prefix("- version:")
+            .or_else(|| trimmed.strip_prefix("version:"))
+        {
+            in_version = yaml_scalar(value) == version;
+        }
+
+        if in_version
+            && !replaced
+            && let Some(value) = trimmed.strip_prefix("sha256:")
+        {
+            let indent = &line[..line.len() - trimmed.len()];
+            let comment = value.find(" #").map(|i| &value[i..]).unwrap_or("");
+            lines.push(format!("{}sha256: \"{}\"{}", indent, sha256, comment));
+            replaced = true;
+            continue;
+        }
+
+        lines.push(line.to_string());
+    }
+
+    if !replaced {
+        return Err(ManifestError::ValidationError(format!(
+            "no sha256 entry found for version {}",
+            version
+        )));
+    }
+
+    let mut updated = lines.join("\n");
+    if content.ends_with('\n') {
+        updated.push('\n');
+    }
+    Ok(updated)
+}
+
+/// Plain YAML scalar value: strips trailing comments and surrounding quotes
+fn yaml_scalar(value: &str) -> &str {
+    let value = value.split(" #").next().unwrap_or("").trim();
+    value.trim_matches(|c| c == '"' || c == '\'')
+}
+
+fn scaffold_manifest(prover: &str, version: &str, bin_path: &str, sha256: &str) -> String {
+    format!(
+        r#"# Verifier manifest for {prover}
+# See manifest.example.yaml in prova-rust for all options.
+
+prover: "{prover}"
+description: ""
+
+versions:
+  - version: "{version}"
+    active: true # Only one version can be active at a time
+    bin_path: "{bin_path}" # Relative to ARTIFACTS_DIR
+    sha256: "{sha256}" # Update with `prova-rust manifest checksum`
+    interface:
+      type: "cli"
+      verify_command: "verify"
+      args:
+        proof: "--proof {{proof_file}}"
+        public_inputs: "--inputs {{inputs_file}}"
+      success_exit_code: 0
+      output_format: "exit_code_only" # "json", "regex" or "exit_code_only"
+"#
+    )
+}
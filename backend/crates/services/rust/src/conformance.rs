@@ -0,0 +1,250 @@
+//! Conformance tests for verifier artifacts.
+//!
+//! An artifacts directory may ship a `tests/conformance.yaml` next to its
+//! manifest.yaml, listing known-valid and known-invalid proofs together with
+//! the expected verifier results. Every case is run against every manifest
+//! version (or the versions the case is restricted to).
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::manifest::{ManifestError, Version};
+use crate::service;
+
+/// Default per-case timeout when the case does not set one
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Deserialize)]
+pub struct ConformanceSuite {
+    pub cases: Vec<ConformanceCase>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConformanceCase {
+    pub name: String,
+    /// Proof file, relative to the tests directory
+    pub proof: String,
+    /// JSON array of public inputs, relative to the tests directory
+    pub inputs: Option<String>,
+    /// Restrict the case to these versions (default: all versions)
+    pub versions: Option<Vec<String>>,
+    pub timeout_ms: Option<u64>,
+    pub expect: Expectation,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Expectation {
+    pub valid: bool,
+    pub public_values: Option<Vec<String>>,
+    pub program_id: Option<String>,
+    /// Substring that must appear in the reported error
+    pub error_contains: Option<String>,
+}
+
+#[derive(Debug)]
+pub struct CaseResult {
+    pub case: String,
+    pub version: String,
+    pub duration: Duration,
+    /// Mismatches against the expectation; the case passes when empty
+    pub failures: Vec<String>,
+    /// Output parsing issues that did not change the result
+    pub warnings: Vec<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ConformanceReport {
+    pub results: Vec<CaseResult>,
+}
+
+impl ConformanceReport {
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|r| !r.passed()).count()
+    }
+
+    /// Whether every case run against the given version passed
+    pub fn version_passed(&self, version: &str) -> bool {
+        self.results
+            .iter()
+            .filter(|r| r.version == version)
+            .all(CaseResult::passed)
+    }
+
+    pub fn print(&self) {
+        for result in &self.results {
+            println!(
+                "{}  {}  {}  {} ms",
+                if result.passed() { "PASS" } else { "FAIL" },
+                result.version,
+                result.case,
+                result.duration.as_millis()
+            );
+            for failure in &result.failures {
+                println!("      fail: {}", failure);
+            }
+            for warning in &result.warnings {
+                println!("      warn: {}", warning);
+            }
+        }
+        println!(
+            "{} passed, {} failed",
+            self.results.len() - self.failed(),
+            self.failed()
+        );
+    }
+}
+
+/// Path of the tests directory for an artifacts directory
+pub fn tests_dir(artifacts_dir: &str) -> PathBuf {
+    Path::new(artifacts_dir).join("tests")
+}
+
+impl ConformanceSuite {
+    pub fn load(artifacts_dir: &str) -> Result<Self, ManifestError> {
+        let path = tests_dir(artifacts_dir).join("conformance.yaml");
+        let content = std::fs::read_to_string(&path)?;
+        let suite: ConformanceSuite = serde_yml::from_str(&content)?;
+
+        if suite.cases.is_empty() {
+            return Err(ManifestError::ValidationError(format!(
+                "no conformance cases defined in {}",
+                path.display()
+            )));
+        }
+
+        Ok(suite)
+    }
+}
+
+/// Run the suite against the given versions.
+/// Callers must only pass versions whose binary passed `validate_binary`.
+pub async fn run(
+    artifacts_dir: &str,
+    versions: &[&Version],
+    suite: &ConformanceSuite,
+) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+
+    for version in versions {
+        for case in &suite.cases {
+            if let Some(restricted) = &case.versions
+                && !restricted.contains(&version.version)
+            {
+                continue;
+            }
+            report
+                .results
+                .push(run_case(artifacts_dir, version, case).await);
+        }
+    }
+
+    report
+}
+
+async fn run_case(artifacts_dir: &str, version: &Version, case: &ConformanceCase) -> CaseResult {
+    let mut result = CaseResult {
+        case: case.name.clone(),
+        version: version.version.clone(),
+        duration: Duration::ZERO,
+        failures: Vec::new(),
+        warnings: Vec::new(),
+    };
+
+    let (proof, public_inputs) = match load_case_inputs(artifacts_dir, case) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            result.failures.push(format!("failed to load case files: {}", e));
+            return result;
+        }
+    };
+
+    let timeout = case
+        .timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(DEFAULT_TIMEOUT);
+
+    let started = Instant::now();
+    let execution = tokio::time::timeout(
+        timeout,
+        service::execute_verifier(artifacts_dir, version, &proof, &public_inputs),
+    )
+    .await;
+    result.duration = started.elapsed();
+
+    let outcome = match execution {
+        Ok(Ok(outcome)) => outcome,
+        Ok(Err(status)) => {
+            result
+                .failures
+                .push(format!("verifier execution failed: {}", status.message()));
+            return result;
+        }
+        Err(_) => {
+            result
+                .failures
+                .push(format!("timed out after {} ms", timeout.as_millis()));
+            return result;
+        }
+    };
+
+    let expect = &case.expect;
+    if outcome.valid != expect.valid {
+        result.failures.push(format!(
+            "expected valid={}, got valid={}{}",
+            expect.valid,
+            outcome.valid,
+            outcome
+                .error
+                .as_deref()
+                .map(|e| format!(" ({})", e))
+                .unwrap_or_default()
+        ));
+    }
+    if let Some(values) = &expect.public_values
+        && *values != outcome.public_values
+    {
+        result.failures.push(format!(
+            "expected public_values {:?}, got {:?}",
+            values, outcome.public_values
+        ));
+    }
+    if let Some(program_id) = &expect.program_id
+        && outcome.program_id.as_ref() != Some(program_id)
+    {
+        result.failures.push(format!(
+            "expected program_id {}, got {:?}",
+            program_id, outcome.program_id
+        ));
+    }
+    if let Some(needle) = &expect.error_contains
+        && !outcome.error.as_deref().unwrap_or("").contains(needle.as_str())
+    {
+        result.failures.push(format!(
+            "expected error containing '{}', got {:?}",
+            needle, outcome.error
+        ));
+    }
+
+    result.warnings = outcome.parse_issues;
+    result
+}
+
+fn load_case_inputs(
+    artifacts_dir: &str,
+    case: &ConformanceCase,
+) -> Result<(Vec<u8>, Vec<String>), Box<dyn std::error::Error>> {
+    let dir = tests_dir(artifacts_dir);
+    let proof = std::fs::read(dir.join(&case.proof))?;
+    let public_inputs = match &case.inputs {
+        Some(path) => serde_json::from_slice(&std::fs::read(dir.join(path))?)?,
+        None => Vec::new(),
+    };
+    Ok((proof, public_inputs))
+}
@@ -0,0 +1,300 @@
+//! Health tracking for the verifier service.
+//!
+//! Keeps per-version availability (re-validated on a schedule), the result of
+//! the optional canary proof and a rolling window of recent verification
+//! outcomes. The same state feeds the custom Health RPC and grpc.health.v1.
+
+use std::collections::VecDeque;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant, SystemTime};
+use tonic_health::ServingStatus;
+use tonic_health::server::HealthReporter;
+
+use crate::manifest::{self, Canary, Manifest, Version};
+use crate::service;
+use crate::verifier::verifier_server::SERVICE_NAME;
+
+/// Number of recent verifications kept for the failure rate
+const WINDOW_SIZE: usize = 100;
+
+/// Minimum number of recent verifications before the failure rate affects health
+const MIN_SAMPLES: usize = 10;
+
+#[derive(Debug, Clone)]
+pub struct VersionHealth {
+    pub available: bool,
+    pub last_error: Option<String>,
+    pub last_checked_at: SystemTime,
+}
+
+impl VersionHealth {
+    pub fn from_check(result: Result<(), manifest::ManifestError>) -> Self {
+        Self {
+            available: result.is_ok(),
+            last_error: result.err().map(|e| e.to_string()),
+            last_checked_at: SystemTime::now(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CanaryHealth {
+    pub passed: bool,
+    pub error: Option<String>,
+    pub checked_at: SystemTime,
+    pub duration: Duration,
+}
+
+/// Result of a single Verify call, as seen by the health monitor.
+/// An invalid proof is a completed verification, not a failure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RequestOutcome {
+    Completed,
+    Failed,
+    TimedOut,
+}
+
+#[derive(Debug)]
+struct HealthState {
+    versions: Vec<VersionHealth>,
+    canary: Option<CanaryHealth>,
+    recent: VecDeque<RequestOutcome>,
+}
+
+#[derive(Debug)]
+pub struct HealthMonitor {
+    state: RwLock<HealthState>,
+    active_index: Option<usize>,
+    max_failure_rate: f64,
+}
+
+#[derive(Debug)]
+pub struct HealthSnapshot {
+    pub healthy: bool,
+    pub versions: Vec<VersionHealth>,
+    pub canary: Option<CanaryHealth>,
+    pub failure_rate: f64,
+    pub recent_requests: usize,
+    pub recent_timeouts: usize,
+}
+
+impl HealthMonitor {
+    pub fn new(versions: Vec<VersionHealth>, active_index: Option<usize>, max_failure_rate: f64) -> Self {
+        Self {
+            state: RwLock::new(HealthState {
+                versions,
+                canary: None,
+                recent: VecDeque::with_capacity(WINDOW_SIZE),
+            }),
+            active_index,
+            max_failure_rate,
+        }
+    }
+
+    pub fn is_available(&self, index: usize) -> bool {
+        self.read()
+            .versions
+            .get(index)
+            .is_some_and(|v| v.available)
+    }
+
+    pub fn set_version(&self, index: usize, health: VersionHealth) {
+        if let Some(slot) = self.write().versions.get_mut(index) {
+            *slot = health;
+        }
+    }
+
+    pub fn set_canary(&self, canary: CanaryHealth) {
+        self.write().canary = Some(canary);
+    }
+
+    pub fn record(&self, outcome: RequestOutcome) {
+        let mut state = self.write();
+        if state.recent.len() == WINDOW_SIZE {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(outcome);
+    }
+
+    pub fn snapshot(&self) -> HealthSnapshot {
+        let state = self.read();
+
+        let recent_requests = state.recent.len();
+        let failures = state
+            .recent
+            .iter()
+            .filter(|o| **o != RequestOutcome::Completed)
+            .count();
+        let recent_timeouts = state
+            .recent
+            .iter()
+            .filter(|o| **o == RequestOutcome::TimedOut)
+            .count();
+        let failure_rate = if recent_requests == 0 {
+            0.0
+        } else {
+            failures as f64 / recent_requests as f64
+        };
+
+        let active_available = self
+            .active_index
+            .and_then(|i| state.versions.get(i))
+            .is_some_and(|v| v.available);
+        let canary_ok = state.canary.as_ref().is_none_or(|c| c.passed);
+        let failure_rate_ok =
+            recent_requests < MIN_SAMPLES || failure_rate <= self.max_failure_rate;
+
+        HealthSnapshot {
+            healthy: active_available && canary_ok && failure_rate_ok,
+            versions: state.versions.clone(),
+            canary: state.canary.clone(),
+            failure_rate,
+            recent_requests,
+            recent_timeouts,
+        }
+    }
+
+    // A poisoned lock only means a panic happened mid-update; the state is still usable
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, HealthState> {
+        self.state.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, HealthState> {
+        self.state.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Periodically re-validate binaries, run the canary and publish grpc.health.v1 status
+pub fn spawn_checker(
+    artifacts_dir: String,
+    manifest: Arc<Manifest>,
+    monitor: Arc<HealthMonitor>,
+    reporter: HealthReporter,
+    interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        // The first tick completes immediately; startup already validated everything
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+            check(&artifacts_dir, &manifest, &monitor).await;
+            publish(&monitor, &reporter).await;
+        }
+    });
+}
+
+/// Publish the current health to grpc.health.v1, for the server and the Verifier service
+pub async fn publish(monitor: &HealthMonitor, reporter: &HealthReporter) {
+    let status = if monitor.snapshot().healthy {
+        ServingStatus::Serving
+    } else {
+        ServingStatus::NotServing
+    };
+    reporter.set_service_status("", status).await;
+    reporter.set_service_status(SERVICE_NAME, status).await;
+}
+
+async fn check(artifacts_dir: &str, manifest: &Manifest, monitor: &HealthMonitor) {
+    for (index, version) in manifest.versions.iter().enumerate() {
+        let artifacts_dir = artifacts_dir.to_string();
+        let owned = version.clone();
+
+        // Hashing large binaries is blocking work
+        let result = tokio::task::spawn_blocking(move || {
+            manifest::validate_binary(&artifacts_dir, &owned)
+        })
+        .await
+        .unwrap_or_else(|e| {
+            Err(manifest::ManifestError::ValidationError(format!(
+                "integrity check task failed: {}",
+                e
+            )))
+        });
+
+        let was_available = monitor.is_available(index);
+        let health = VersionHealth::from_check(result);
+        match (&health.last_error, was_available) {
+            (Some(error), true) => tracing::error!(
+                version = %version.version,
+                error = %error,
+                "version failed integrity re-check, marking unavailable"
+            ),
+            (None, false) => tracing::info!(
+                version = %version.version,
+                "version passed integrity re-check, marking available"
+            ),
+            _ => {}
+        }
+        monitor.set_version(index, health);
+    }
+
+    if let Some(canary) = &manifest.canary
+        && let Some((index, active)) = manifest.versions.iter().enumerate().find(|(_, v)| v.active)
+        && monitor.is_available(index)
+    {
+        let result = run_canary(artifacts_dir, active, canary).await;
+        if let Some(error) = &result.error {
+            tracing::warn!(version = %active.version, error = %error, "canary proof failed");
+        }
+        monitor.set_canary(result);
+    }
+}
+
+async fn run_canary(artifacts_dir: &str, version: &Version, canary: &Canary) -> CanaryHealth {
+    let started = Instant::now();
+    let error = canary_error(artifacts_dir, version, canary).await.err();
+
+    CanaryHealth {
+        passed: error.is_none(),
+        error,
+        checked_at: SystemTime::now(),
+        duration: started.elapsed(),
+    }
+}
+
+async fn canary_error(artifacts_dir: &str, version: &Version, canary: &Canary) -> Result<(), String> {
+    let proof = std::fs::read(Path::new(artifacts_dir).join(&canary.proof))
+        .map_err(|e| format!("failed to read canary proof: {}", e))?;
+    let public_inputs: Vec<String> = match &canary.inputs {
+        Some(path) => {
+            let bytes = std::fs::read(Path::new(artifacts_dir).join(path))
+                .map_err(|e| format!("failed to read canary inputs: {}", e))?;
+            serde_json::from_slice(&bytes)
+                .map_err(|e| format!("invalid canary inputs: {}", e))?
+        }
+        None => Vec::new(),
+    };
+
+    let timeout = version.interface.timeout();
+    let outcome = tokio::time::timeout(
+        timeout,
+        service::execute_verifier(artifacts_dir, version, &proof, &public_inputs),
+    )
+    .await
+    .map_err(|_| format!("canary timed out after {} s", timeout.as_secs()))?
+    .map_err(|status| status.message().to_string())?;
+
+    if outcome.valid != canary.expect_valid {
+        return Err(format!(
+            "canary expected valid={}, got valid={}{}",
+            canary.expect_valid,
+            outcome.valid,
+            outcome
+                .error
+                .map(|e| format!(" ({})", e))
+                .unwrap_or_default()
+        ));
+    }
+
+    Ok(())
+}
+
+/// Seconds since the Unix epoch, for the proto timestamp fields
+pub fn unix_seconds(time: SystemTime) -> i64 {
+    time.duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
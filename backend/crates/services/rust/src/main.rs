@@ -2,13 +2,19 @@ pub mod verifier {
     tonic::include_proto!("prova.verifier.v1");
 }
 
+mod cli;
+mod conformance;
+mod health;
 mod manifest;
+mod output;
 mod service;
 
-use tonic::transport::Server;
+use clap::Parser;
+use tonic::transport::{Certificate, Identity, Server, ServerTlsConfig};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 use verifier::verifier_server::VerifierServer;
 
+use cli::{Cli, Command};
 use service::RustVerifierService;
 
 /// Entry point for the generic Rust-based verifier service.
@@ -17,6 +23,14 @@ use service::RustVerifierService;
 /// Environment variables:
 /// - ARTIFACTS_DIR: Path to artifacts directory (required)
 /// - GRPC_PORT: Port to listen on (default: 50051)
+/// - STRICT_VALIDATION: Fail startup if any manifest version is broken (default: false)
+/// - HEALTH_CHECK_INTERVAL_SECS: Binary re-check and canary interval, 0 disables (default: 60)
+/// - HEALTH_MAX_FAILURE_RATE: Recent error/timeout share above which Health is unhealthy (default: 0.5)
+/// - MAX_PROOF_BYTES: Largest proof accepted by Verify and VerifyStream (default: 256 MiB)
+/// - TLS_CERT / TLS_KEY: PEM server certificate and key, enables TLS
+/// - TLS_CLIENT_CA: PEM CA for client certificates, requires mTLS from every caller
+///
+/// Run `prova-rust manifest --help` for the manifest authoring commands.
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -28,16 +42,31 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .with(tracing_subscriber::fmt::layer())
         .init();
 
+    match Cli::parse().command {
+        Some(Command::Manifest(command)) => cli::run_manifest_command(command).await,
+        Some(Command::Conformance { artifacts, version }) => {
+            cli::run_conformance(artifacts, version).await
+        }
+        Some(Command::Serve) | None => serve().await,
+    }
+}
+
+async fn serve() -> Result<(), Box<dyn std::error::Error>> {
     // Create the service
     let service = RustVerifierService::new()?;
     let prover_name = service.manifest().prover.clone();
 
+    if service.manifest().self_test {
+        service.self_test().await?;
+    }
+
     let port = std::env::var("GRPC_PORT").unwrap_or_else(|_| "50051".to_string());
     let addr = format!("0.0.0.0:{}", port).parse()?;
 
     tracing::info!(
         %addr,
         prover = %prover_name,
+        artifacts_dir = %service.artifacts_dir(),
         "rust verifier service starting"
     );
 
@@ -49,12 +78,61 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         )))
         .build_v1()?;
 
+    // Standard grpc.health.v1 service, fed by the same checks as the Health RPC
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    service.start_health_checks(health_reporter).await;
+
+    // Unary Verify carries the whole proof in one message; leave room for the other fields
+    let max_message_size = usize::try_from(service.max_proof_bytes())
+        .unwrap_or(usize::MAX)
+        .saturating_add(64 * 1024);
+
+    let mut server = Server::builder();
+    if let Some(tls) = server_tls()? {
+        server = server.tls_config(tls)?;
+    }
+
     // Start the server
-    Server::builder()
+    server
+        .add_service(health_service)
         .add_service(reflection_service)
-        .add_service(VerifierServer::new(service))
+        .add_service(VerifierServer::new(service).max_decoding_message_size(max_message_size))
         .serve(addr)
         .await?;
 
     Ok(())
 }
+
+/// TLS from TLS_CERT/TLS_KEY, with client certificates required when TLS_CLIENT_CA is set.
+/// Callers without a certificate signed by that CA fail the handshake.
+fn server_tls() -> Result<Option<ServerTlsConfig>, Box<dyn std::error::Error>> {
+    let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+    let read = |path: &str| {
+        std::fs::read(path).map_err(|e| format!("failed to read TLS file {}: {}", path, e))
+    };
+
+    let (cert, key) = match (var("TLS_CERT"), var("TLS_KEY")) {
+        (Some(cert), Some(key)) => (cert, key),
+        (None, None) => {
+            if var("TLS_CLIENT_CA").is_some() {
+                return Err("TLS_CLIENT_CA requires TLS_CERT and TLS_KEY".into());
+            }
+            tracing::warn!("TLS not configured, serving plaintext gRPC");
+            return Ok(None);
+        }
+        _ => return Err("TLS_CERT and TLS_KEY must be set together".into()),
+    };
+
+    let mut config = ServerTlsConfig::new().identity(Identity::from_pem(read(&cert)?, read(&key)?));
+    match var("TLS_CLIENT_CA") {
+        Some(ca) => {
+            config = config
+                .client_ca_root(Certificate::from_pem(read(&ca)?))
+                .client_auth_optional(false);
+            tracing::info!("mutual TLS enabled, client certificates required");
+        }
+        None => tracing::info!("TLS enabled without client authentication"),
+    }
+
+    Ok(Some(config))
+}
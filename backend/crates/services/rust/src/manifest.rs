@@ -3,14 +3,19 @@
 //! Each verifier artifact directory contains a manifest.yaml that describes
 //! available versions and how to invoke the verifier binary.
 
+use regex::{Regex, RegexBuilder};
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
 use std::fs::File;
 use std::io::Read;
 use std::os::unix::fs::PermissionsExt;
 use std::path::Path;
+use std::time::Duration;
 use thiserror::Error;
 
+/// Verifier run time limit when the interface does not set timeout_secs
+const DEFAULT_TIMEOUT_SECS: u64 = 300;
+
 #[derive(Debug, Error)]
 pub enum ManifestError {
     #[error("Failed to read manifest file: {0}")]
@@ -30,10 +35,29 @@ pub enum ManifestError {
 pub struct Manifest {
     pub prover: String,
     pub description: Option<String>,
+    /// Run tests/conformance.yaml at startup and refuse to serve if the active version fails
+    #[serde(default)]
+    pub self_test: bool,
+    /// Known proof run against the active version on every health check
+    pub canary: Option<Canary>,
     pub versions: Vec<Version>,
 }
 
 #[derive(Debug, Deserialize)]
+pub struct Canary {
+    /// Proof file, relative to ARTIFACTS_DIR
+    pub proof: String,
+    /// JSON array of public inputs, relative to ARTIFACTS_DIR
+    pub inputs: Option<String>,
+    #[serde(default = "default_true")]
+    pub expect_valid: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct Version {
     pub version: String,
     pub active: bool,
@@ -45,7 +69,7 @@ pub struct Version {
     pub interface: VerifierInterface,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub struct VerifierInterface {
     #[serde(rename = "type")]
     pub interface_type: String,
@@ -53,9 +77,41 @@ pub struct VerifierInterface {
     pub args: Option<InterfaceArgs>,
     pub success_exit_code: Option<i32>,
     pub output_format: Option<String>,
+    /// Maximum verifier run time in seconds (default: 300)
+    pub timeout_secs: Option<u64>,
+    /// Patterns used to extract results when output_format is "regex"
+    pub output_patterns: Option<OutputPatterns>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Regular expressions applied to verifier stdout for the "regex" output format.
+/// Every pattern except `valid` must contain one capture group.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OutputPatterns {
+    /// If set, a match means the proof is valid; otherwise the exit code decides
+    pub valid: Option<String>,
+    pub error: Option<String>,
+    /// Applied repeatedly, each match contributes one public value
+    pub public_values: Option<String>,
+    pub program_id: Option<String>,
+    pub cycles: Option<String>,
+    pub version: Option<String>,
+    /// The patterns above, compiled once when the manifest is loaded
+    #[serde(skip)]
+    pub regexes: OutputRegexes,
+}
+
+/// Compiled form of `OutputPatterns`
+#[derive(Debug, Clone, Default)]
+pub struct OutputRegexes {
+    pub valid: Option<Regex>,
+    pub error: Option<Regex>,
+    pub public_values: Option<Regex>,
+    pub program_id: Option<Regex>,
+    pub cycles: Option<Regex>,
+    pub version: Option<Regex>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct InterfaceArgs {
     pub proof: Option<String>,
     pub public_inputs: Option<String>,
@@ -66,12 +122,12 @@ pub struct InterfaceArgs {
 impl Manifest {
     pub fn load(path: &str) -> Result<Self, ManifestError> {
         let content = std::fs::read_to_string(path)?;
-        let manifest: Manifest = serde_yml::from_str(&content)?;
+        let mut manifest: Manifest = serde_yml::from_str(&content)?;
         manifest.validate()?;
         Ok(manifest)
     }
 
-    fn validate(&self) -> Result<(), ManifestError> {
+    fn validate(&mut self) -> Result<(), ManifestError> {
         if self.prover.is_empty() {
             return Err(ManifestError::ValidationError(
                 "prover name cannot be empty".to_string(),
@@ -96,16 +152,24 @@ impl Manifest {
             ));
         }
 
-        for version in &self.versions {
+        for version in &mut self.versions {
             version.validate()?;
         }
 
+        if let Some(canary) = &self.canary
+            && canary.proof.is_empty()
+        {
+            return Err(ManifestError::ValidationError(
+                "canary proof path cannot be empty".to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
 
 impl Version {
-    fn validate(&self) -> Result<(), ManifestError> {
+    fn validate(&mut self) -> Result<(), ManifestError> {
         if self.version.is_empty() {
             return Err(ManifestError::ValidationError(
                 "version string cannot be empty".to_string(),
@@ -126,7 +190,11 @@ impl Version {
 }
 
 impl VerifierInterface {
-    fn validate(&self, version: &str) -> Result<(), ManifestError> {
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS))
+    }
+
+    fn validate(&mut self, version: &str) -> Result<(), ManifestError> {
         let valid_types = ["cli"];
         if !valid_types.contains(&self.interface_type.as_str()) {
             return Err(ManifestError::ValidationError(format!(
@@ -135,20 +203,85 @@ impl VerifierInterface {
             )));
         }
 
-        let valid_formats = ["json", "exit_code_only"];
-        if let Some(format) = &self.output_format {
-            if !valid_formats.contains(&format.as_str()) {
-                return Err(ManifestError::ValidationError(format!(
-                    "unsupported output_format '{}' for version {} (supported: {:?})",
-                    format, version, valid_formats
-                )));
-            }
+        let valid_formats = ["json", "exit_code_only", "regex"];
+        if let Some(format) = &self.output_format
+            && !valid_formats.contains(&format.as_str())
+        {
+            return Err(ManifestError::ValidationError(format!(
+                "unsupported output_format '{}' for version {} (supported: {:?})",
+                format, version, valid_formats
+            )));
+        }
+
+        if self.output_format.as_deref() == Some("regex") {
+            let patterns = self.output_patterns.as_mut().ok_or_else(|| {
+                ManifestError::ValidationError(format!(
+                    "output_format 'regex' requires output_patterns for version {}",
+                    version
+                ))
+            })?;
+            patterns.compile(version)?;
         }
 
         Ok(())
     }
 }
 
+impl OutputPatterns {
+    /// Check every pattern and keep the compiled regexes for parsing output
+    fn compile(&mut self, version: &str) -> Result<(), ManifestError> {
+        let valid = self
+            .valid
+            .as_deref()
+            .map(|pattern| compile_pattern("valid", pattern, version))
+            .transpose()?;
+
+        self.regexes = OutputRegexes {
+            valid,
+            error: compile_capturing("error", &self.error, version)?,
+            public_values: compile_capturing("public_values", &self.public_values, version)?,
+            program_id: compile_capturing("program_id", &self.program_id, version)?,
+            cycles: compile_capturing("cycles", &self.cycles, version)?,
+            version: compile_capturing("version", &self.version, version)?,
+        };
+
+        Ok(())
+    }
+}
+
+/// Build an output pattern regex; `^` and `$` match at line boundaries
+fn output_regex(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).multi_line(true).build()
+}
+
+fn compile_pattern(name: &str, pattern: &str, version: &str) -> Result<Regex, ManifestError> {
+    output_regex(pattern).map_err(|e| {
+        ManifestError::ValidationError(format!(
+            "invalid output pattern '{}' for version {}: {}",
+            name, version, e
+        ))
+    })
+}
+
+/// Compile a pattern that must contain a capture group, if it is set
+fn compile_capturing(
+    name: &str,
+    pattern: &Option<String>,
+    version: &str,
+) -> Result<Option<Regex>, ManifestError> {
+    let Some(pattern) = pattern else {
+        return Ok(None);
+    };
+    let regex = compile_pattern(name, pattern, version)?;
+    if regex.captures_len() < 2 {
+        return Err(ManifestError::ValidationError(format!(
+            "output pattern '{}' for version {} must contain a capture group",
+            name, version
+        )));
+    }
+    Ok(Some(regex))
+}
+
 /// Validate that the binary exists, is executable, and matches checksum
 pub fn validate_binary(artifacts_dir: &str, version: &Version) -> Result<(), ManifestError> {
     let full_path = Path::new(artifacts_dir).join(&version.bin_path);
@@ -0,0 +1,213 @@
+//! Verifier output parsing.
+//!
+//! Turns the raw process output of a verifier binary into a structured
+//! outcome according to the `output_format` configured in the manifest.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::process::Output;
+
+use crate::manifest::{OutputRegexes, VerifierInterface};
+
+/// Maximum number of bytes of stderr kept as diagnostics
+const MAX_DIAGNOSTICS_LEN: usize = 4096;
+
+/// Structured result of a single verifier execution
+#[derive(Debug, Default, Serialize)]
+pub struct VerificationOutcome {
+    pub valid: bool,
+    pub error: Option<String>,
+    pub public_values: Vec<String>,
+    pub program_id: Option<String>,
+    pub cycles: Option<u64>,
+    pub verifier_version: Option<String>,
+    pub diagnostics: Option<String>,
+    /// Problems encountered while interpreting the output (not sent over gRPC)
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub parse_issues: Vec<String>,
+}
+
+/// JSON document printed on stdout by verifiers using output_format "json"
+#[derive(Debug, Deserialize)]
+struct VerifierOutput {
+    valid: bool,
+    error: Option<String>,
+    #[serde(default)]
+    public_values: Vec<serde_json::Value>,
+    #[serde(alias = "image_id")]
+    program_id: Option<String>,
+    cycles: Option<u64>,
+    #[serde(alias = "verifier_version")]
+    version: Option<String>,
+}
+
+/// Parse verifier process output based on the interface's output_format
+pub fn parse_output(interface: &VerifierInterface, output: &Output) -> VerificationOutcome {
+    let success_code = interface.success_exit_code.unwrap_or(0);
+    let exit_ok = output.status.code() == Some(success_code);
+
+    let mut outcome = match interface.output_format.as_deref() {
+        Some("json") => match serde_json::from_slice::<VerifierOutput>(&output.stdout) {
+            Ok(result) => {
+                let mut parse_issues = Vec::new();
+                if result.valid != exit_ok {
+                    parse_issues.push(format!(
+                        "json reports valid={} but exit code is {:?}",
+                        result.valid,
+                        output.status.code()
+                    ));
+                }
+                VerificationOutcome {
+                    valid: result.valid,
+                    error: result.error,
+                    public_values: result.public_values.into_iter().map(value_to_string).collect(),
+                    program_id: result.program_id,
+                    cycles: result.cycles,
+                    verifier_version: result.version,
+                    diagnostics: None,
+                    parse_issues,
+                }
+            }
+            Err(e) => {
+                let mut outcome = from_exit_code(exit_ok, output);
+                outcome.parse_issues.push(format!(
+                    "stdout is not valid verifier json, fell back to exit code: {}",
+                    e
+                ));
+                outcome
+            }
+        },
+        Some("regex") => match &interface.output_patterns {
+            Some(patterns) => from_patterns(&patterns.regexes, exit_ok, output),
+            None => from_exit_code(exit_ok, output),
+        },
+        _ => from_exit_code(exit_ok, output),
+    };
+
+    if !outcome.valid {
+        outcome.diagnostics = diagnostics(&output.stderr);
+    }
+
+    outcome
+}
+
+fn from_exit_code(exit_ok: bool, output: &Output) -> VerificationOutcome {
+    VerificationOutcome {
+        valid: exit_ok,
+        error: if exit_ok { None } else { Some(error_summary(output)) },
+        ..Default::default()
+    }
+}
+
+fn from_patterns(patterns: &OutputRegexes, exit_ok: bool, output: &Output) -> VerificationOutcome {
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut parse_issues = Vec::new();
+
+    let valid = match &patterns.valid {
+        Some(regex) => regex.is_match(&stdout),
+        None => exit_ok,
+    };
+    if valid != exit_ok {
+        parse_issues.push(format!(
+            "valid pattern gives valid={} but exit code is {:?}",
+            valid,
+            output.status.code()
+        ));
+    }
+
+    let error = capture(patterns.error.as_ref(), &stdout)
+        .or_else(|| (!valid).then(|| error_summary(output)));
+
+    let public_values = patterns
+        .public_values
+        .as_ref()
+        .map(|regex| {
+            regex
+                .captures_iter(&stdout)
+                .filter_map(|c| c.get(1).map(|m| m.as_str().to_string()))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let program_id = capture(patterns.program_id.as_ref(), &stdout);
+    let verifier_version = capture(patterns.version.as_ref(), &stdout);
+    let cycles_text = capture(patterns.cycles.as_ref(), &stdout);
+    let cycles = cycles_text
+        .as_deref()
+        .and_then(|c| c.replace([',', '_'], "").parse().ok());
+
+    if let Some(text) = &cycles_text
+        && cycles.is_none()
+    {
+        parse_issues.push(format!("cycles pattern captured non-numeric value '{}'", text));
+    }
+
+    // Missing fields are expected on failure, but a valid run should report them all
+    if valid {
+        let missing = [
+            ("program_id", &patterns.program_id, program_id.is_some()),
+            ("cycles", &patterns.cycles, cycles_text.is_some()),
+            ("version", &patterns.version, verifier_version.is_some()),
+        ];
+        for (name, pattern, found) in missing {
+            if pattern.is_some() && !found {
+                parse_issues.push(format!("{} pattern did not match stdout", name));
+            }
+        }
+    }
+
+    VerificationOutcome {
+        valid,
+        error: if valid { None } else { error },
+        public_values,
+        program_id,
+        cycles,
+        verifier_version,
+        diagnostics: None,
+        parse_issues,
+    }
+}
+
+fn capture(regex: Option<&Regex>, text: &str) -> Option<String> {
+    regex?
+        .captures(text)
+        .and_then(|c| c.get(1))
+        .map(|m| m.as_str().trim().to_string())
+}
+
+/// Short error message: the last non-empty stderr line, or the exit status
+fn error_summary(output: &Output) -> String {
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| match output.status.code() {
+            Some(code) => format!("verifier exited with code {}", code),
+            None => "verifier terminated by signal".to_string(),
+        })
+}
+
+/// Tail of stderr, truncated to MAX_DIAGNOSTICS_LEN bytes
+fn diagnostics(stderr: &[u8]) -> Option<String> {
+    let text = String::from_utf8_lossy(stderr);
+    let text = text.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    let mut start = text.len().saturating_sub(MAX_DIAGNOSTICS_LEN);
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    Some(text[start..].to_string())
+}
+
+fn value_to_string(value: serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s,
+        other => other.to_string(),
+    }
+}
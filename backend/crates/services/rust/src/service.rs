@@ -1,19 +1,35 @@
 use std::io::Write;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::NamedTempFile;
+use tokio::io::AsyncWriteExt;
 use tokio::process::Command;
-use tonic::{Request, Response, Status};
+use tonic::{Request, Response, Status, Streaming};
+use tonic_health::server::HealthReporter;
 
+use crate::conformance::{self, ConformanceSuite};
+use crate::health::{self, HealthMonitor, RequestOutcome, VersionHealth};
 use crate::manifest::{self as manifest, Manifest};
+use crate::output::{self, VerificationOutcome};
 use crate::verifier::{
-    HealthRequest, HealthResponse, VerifyRequest, VerifyResponse, verifier_server::Verifier,
+    CanaryStatus, DescribeRequest, DescribeResponse, HealthRequest, HealthResponse,
+    VerifyRequest, VerifyResponse, VerifyStreamRequest, VersionStatus,
+    verifier_server::Verifier, verify_stream_request::Payload,
 };
 
+/// Default upper bound for a single proof, in bytes
+const DEFAULT_MAX_PROOF_BYTES: u64 = 256 * 1024 * 1024;
+
 /// Generic Rust-based verifier service.
 /// Loads verifier binary from ARTIFACTS_DIR based on manifest.yaml.
 #[derive(Debug)]
 pub struct RustVerifierService {
     artifacts_dir: String,
-    manifest: Manifest,
+    manifest: Arc<Manifest>,
+    health: Arc<HealthMonitor>,
+    check_interval: Duration,
+    max_proof_bytes: u64,
 }
 
 impl RustVerifierService {
@@ -24,20 +40,49 @@ impl RustVerifierService {
         let manifest_path = format!("{}/manifest.yaml", artifacts_dir);
         let manifest = Manifest::load(&manifest_path)?;
 
-        // Validate active version binary: exists, executable, checksum (always required)
-        if let Some(version) = manifest.versions.iter().find(|v| v.active) {
-            manifest::validate_binary(&artifacts_dir, version)?;
+        // In strict mode any broken version fails startup, otherwise only the active one does
+        let strict = std::env::var("STRICT_VALIDATION")
+            .map(|v| v == "true" || v == "1")
+            .unwrap_or(false);
+
+        let check_interval = Duration::from_secs(env_number("HEALTH_CHECK_INTERVAL_SECS", 60)?);
+        let max_failure_rate = env_number("HEALTH_MAX_FAILURE_RATE", 0.5)?;
+        let max_proof_bytes = env_number("MAX_PROOF_BYTES", DEFAULT_MAX_PROOF_BYTES)?;
+
+        // Validate every version binary: exists, executable, checksum
+        let mut versions = Vec::with_capacity(manifest.versions.len());
+        for version in &manifest.versions {
+            match manifest::validate_binary(&artifacts_dir, version) {
+                Ok(()) => versions.push(VersionHealth::from_check(Ok(()))),
+                Err(e) if version.active || strict => {
+                    return Err(format!("version {}: {}", version.version, e).into());
+                }
+                Err(e) => {
+                    tracing::warn!(
+                        version = %version.version,
+                        error = %e,
+                        "version failed validation, marking unavailable"
+                    );
+                    versions.push(VersionHealth::from_check(Err(e)));
+                }
+            }
         }
 
         tracing::info!(
             prover = %manifest.prover,
             artifacts_dir = %artifacts_dir,
+            strict = strict,
             "loaded and validated manifest"
         );
 
+        let active_index = manifest.versions.iter().position(|v| v.active);
+
         Ok(Self {
             artifacts_dir,
-            manifest,
+            manifest: Arc::new(manifest),
+            health: Arc::new(HealthMonitor::new(versions, active_index, max_failure_rate)),
+            check_interval,
+            max_proof_bytes,
         })
     }
 
@@ -51,132 +96,293 @@ impl RustVerifierService {
         &self.manifest
     }
 
+    /// Largest proof accepted by Verify and VerifyStream
+    pub fn max_proof_bytes(&self) -> u64 {
+        self.max_proof_bytes
+    }
+
+    /// Publish the initial grpc.health.v1 status and start the periodic health checks.
+    /// A HEALTH_CHECK_INTERVAL_SECS of 0 disables the periodic checks.
+    pub async fn start_health_checks(&self, reporter: HealthReporter) {
+        health::publish(&self.health, &reporter).await;
+
+        if self.check_interval.is_zero() {
+            return;
+        }
+
+        health::spawn_checker(
+            self.artifacts_dir.clone(),
+            self.manifest.clone(),
+            self.health.clone(),
+            reporter,
+            self.check_interval,
+        );
+    }
+
+    /// Run the artifact's conformance suite against every available version.
+    /// Fails if the active version does not pass; other versions only log warnings.
+    pub async fn self_test(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let suite = ConformanceSuite::load(&self.artifacts_dir)?;
+        let available: Vec<&manifest::Version> = self
+            .manifest
+            .versions
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| self.health.is_available(*index))
+            .map(|(_, version)| version)
+            .collect();
+        let report = conformance::run(&self.artifacts_dir, &available, &suite).await;
+
+        for result in &report.results {
+            if result.passed() {
+                tracing::info!(
+                    case = %result.case,
+                    version = %result.version,
+                    duration_ms = result.duration.as_millis() as u64,
+                    warnings = ?result.warnings,
+                    "conformance case passed"
+                );
+            } else {
+                tracing::warn!(
+                    case = %result.case,
+                    version = %result.version,
+                    duration_ms = result.duration.as_millis() as u64,
+                    failures = ?result.failures,
+                    "conformance case failed"
+                );
+            }
+        }
+
+        if let Some(active) = self.get_active_version()
+            && !report.version_passed(&active.version)
+        {
+            return Err(format!(
+                "self-test failed for active version {}",
+                active.version
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
     fn get_active_version(&self) -> Option<&manifest::Version> {
         self.manifest.versions.iter().find(|v| v.active)
     }
 
-    async fn execute_verifier(
+    fn version_statuses(&self, versions: &[VersionHealth]) -> Vec<VersionStatus> {
+        self.manifest
+            .versions
+            .iter()
+            .zip(versions)
+            .map(|(version, health)| VersionStatus {
+                version: version.version.clone(),
+                active: version.active,
+                available: health.available,
+                error: health.last_error.clone(),
+                last_checked_at: health::unix_seconds(health.last_checked_at),
+            })
+            .collect()
+    }
+
+    async fn execute_active(
         &self,
-        proof: &[u8],
+        proof_path: &Path,
         public_inputs: &[String],
-    ) -> Result<(bool, Option<String>), Status> {
+    ) -> Result<VerificationOutcome, Status> {
+        let (index, version) = self
+            .manifest
+            .versions
+            .iter()
+            .enumerate()
+            .find(|(_, v)| v.active)
+            .ok_or_else(|| Status::internal("No active version configured"))?;
+
+        // The binary may have changed on disk since startup; never run it unverified
+        if !self.health.is_available(index) {
+            return Err(Status::unavailable(format!(
+                "Active version {} failed its integrity check",
+                version.version
+            )));
+        }
+
+        let timeout = version.interface.timeout();
+        let result = tokio::time::timeout(
+            timeout,
+            execute_verifier_file(&self.artifacts_dir, version, proof_path, public_inputs),
+        )
+        .await;
+
+        match result {
+            Ok(Ok(outcome)) => {
+                self.health.record(RequestOutcome::Completed);
+                Ok(outcome)
+            }
+            Ok(Err(status)) => {
+                self.health.record(RequestOutcome::Failed);
+                Err(status)
+            }
+            Err(_) => {
+                self.health.record(RequestOutcome::TimedOut);
+                Err(Status::deadline_exceeded(format!(
+                    "Verifier timed out after {} s",
+                    timeout.as_secs()
+                )))
+            }
+        }
+    }
+
+    fn verify_response(&self, outcome: VerificationOutcome) -> VerifyResponse {
         let version = self
             .get_active_version()
-            .ok_or_else(|| Status::internal("No active version configured"))?;
+            .map(|v| v.version.clone())
+            .unwrap_or_else(|| "unknown".to_string());
 
-        let interface = &version.interface;
+        tracing::info!(
+            prover = %self.manifest.prover,
+            valid = outcome.valid,
+            cycles = ?outcome.cycles,
+            "verification complete"
+        );
 
-        if interface.interface_type != "cli" {
-            return Err(Status::unimplemented(format!(
-                "Interface type '{}' not supported",
-                interface.interface_type
-            )));
+        for issue in &outcome.parse_issues {
+            tracing::warn!(issue = %issue, "verifier output parsing issue");
         }
 
-        // Create secure temp files with automatic cleanup
-        let mut proof_file = NamedTempFile::new()
-            .map_err(|e| Status::internal(format!("Failed to create temp file: {}", e)))?;
+        VerifyResponse {
+            valid: outcome.valid,
+            prover_version: version,
+            error: outcome.error,
+            public_values: outcome.public_values,
+            program_id: outcome.program_id,
+            cycles: outcome.cycles,
+            verifier_version: outcome.verifier_version,
+            diagnostics: outcome.diagnostics,
+        }
+    }
+}
 
-        let mut inputs_file = NamedTempFile::new()
-            .map_err(|e| Status::internal(format!("Failed to create temp file: {}", e)))?;
+fn proof_too_large(max_proof_bytes: u64) -> Status {
+    Status::invalid_argument(format!(
+        "Proof exceeds the maximum size of {} bytes",
+        max_proof_bytes
+    ))
+}
 
-        proof_file
-            .write_all(proof)
-            .map_err(|e| Status::internal(format!("Failed to write proof: {}", e)))?;
+fn temp_file_error(e: std::io::Error) -> Status {
+    Status::internal(format!("Failed to write proof: {}", e))
+}
 
-        let inputs_json = serde_json::to_vec(public_inputs)
-            .map_err(|e| Status::internal(format!("Failed to serialize inputs: {}", e)))?;
+fn env_number<T: std::str::FromStr>(name: &str, default: T) -> Result<T, String> {
+    match std::env::var(name) {
+        Ok(value) => value
+            .parse()
+            .map_err(|_| format!("invalid value for {}: must be a number", name)),
+        Err(_) => Ok(default),
+    }
+}
 
-        inputs_file
-            .write_all(&inputs_json)
-            .map_err(|e| Status::internal(format!("Failed to write inputs: {}", e)))?;
+/// Run a manifest version's verifier binary against a proof and parse its output.
+/// Used by the health canary and the conformance runner.
+pub async fn execute_verifier(
+    artifacts_dir: &str,
+    version: &manifest::Version,
+    proof: &[u8],
+    public_inputs: &[String],
+) -> Result<VerificationOutcome, Status> {
+    let proof_file = write_temp_proof(proof)?;
+    execute_verifier_file(artifacts_dir, version, proof_file.path(), public_inputs).await
+}
 
-        // Build command
-        let bin_path = format!("{}/{}", self.artifacts_dir, version.bin_path);
-        let mut cmd = Command::new(&bin_path);
+/// Write a proof to a secure temp file, removed when the handle is dropped
+fn write_temp_proof(proof: &[u8]) -> Result<NamedTempFile, Status> {
+    let mut proof_file = NamedTempFile::new()
+        .map_err(|e| Status::internal(format!("Failed to create temp file: {}", e)))?;
+    proof_file.write_all(proof).map_err(temp_file_error)?;
+    Ok(proof_file)
+}
 
-        if let Some(verify_cmd) = &interface.verify_command {
-            cmd.arg(verify_cmd);
-        }
+/// Same as `execute_verifier`, for a proof that is already on disk
+pub async fn execute_verifier_file(
+    artifacts_dir: &str,
+    version: &manifest::Version,
+    proof_path: &Path,
+    public_inputs: &[String],
+) -> Result<VerificationOutcome, Status> {
+    let interface = &version.interface;
+
+    if interface.interface_type != "cli" {
+        return Err(Status::unimplemented(format!(
+            "Interface type '{}' not supported",
+            interface.interface_type
+        )));
+    }
 
-        // Add configured arguments
-        if let Some(args) = &interface.args {
-            if let Some(proof_arg) = &args.proof {
-                let arg =
-                    proof_arg.replace("{proof_file}", proof_file.path().to_str().unwrap_or(""));
-                for part in arg.split_whitespace() {
-                    cmd.arg(part);
-                }
-            }
-            if let Some(inputs_arg) = &args.public_inputs {
-                let arg =
-                    inputs_arg.replace("{inputs_file}", inputs_file.path().to_str().unwrap_or(""));
-                for part in arg.split_whitespace() {
-                    cmd.arg(part);
-                }
-            }
-            if let Some(vk_arg) = &args.vk {
-                if let Some(vk_path) = &version.vk_path {
-                    let full_vk_path = format!("{}/{}", self.artifacts_dir, vk_path);
-                    let arg = vk_arg.replace("{vk_file}", &full_vk_path);
-                    for part in arg.split_whitespace() {
-                        cmd.arg(part);
-                    }
-                }
-            }
-        }
+    // Create secure temp files with automatic cleanup
+    let mut inputs_file = NamedTempFile::new()
+        .map_err(|e| Status::internal(format!("Failed to create temp file: {}", e)))?;
 
-        tracing::debug!(command = ?cmd, "executing verifier");
+    let inputs_json = serde_json::to_vec(public_inputs)
+        .map_err(|e| Status::internal(format!("Failed to serialize inputs: {}", e)))?;
 
-        // Execute asynchronously
-        let output = cmd
-            .output()
-            .await
-            .map_err(|e| Status::internal(format!("Failed to execute verifier: {}", e)))?;
+    inputs_file
+        .write_all(&inputs_json)
+        .map_err(|e| Status::internal(format!("Failed to write inputs: {}", e)))?;
 
-        tracing::debug!(
-            exit_code = ?output.status.code(),
-            stdout_len = output.stdout.len(),
-            stderr_len = output.stderr.len(),
-            "verifier execution complete"
-        );
+    // Build command
+    let bin_path = format!("{}/{}", artifacts_dir, version.bin_path);
+    let mut cmd = Command::new(&bin_path);
+    cmd.kill_on_drop(true);
 
-        // Temp files are automatically cleaned up when they go out of scope
-
-        // Parse result based on output_format
-        let success_code = interface.success_exit_code.unwrap_or(0);
-
-        match interface.output_format.as_deref() {
-            Some("json") => {
-                if let Ok(result) = serde_json::from_slice::<VerifierOutput>(&output.stdout) {
-                    Ok((result.valid, result.error))
-                } else {
-                    let valid = output.status.code() == Some(success_code);
-                    let error = if valid {
-                        None
-                    } else {
-                        Some(String::from_utf8_lossy(&output.stderr).to_string())
-                    };
-                    Ok((valid, error))
-                }
+    if let Some(verify_cmd) = &interface.verify_command {
+        cmd.arg(verify_cmd);
+    }
+
+    // Add configured arguments
+    if let Some(args) = &interface.args {
+        if let Some(proof_arg) = &args.proof {
+            let arg =
+                proof_arg.replace("{proof_file}", proof_path.to_str().unwrap_or(""));
+            for part in arg.split_whitespace() {
+                cmd.arg(part);
             }
-            _ => {
-                let valid = output.status.code() == Some(success_code);
-                let error = if valid {
-                    None
-                } else {
-                    Some(String::from_utf8_lossy(&output.stderr).to_string())
-                };
-                Ok((valid, error))
+        }
+        if let Some(inputs_arg) = &args.public_inputs {
+            let arg =
+                inputs_arg.replace("{inputs_file}", inputs_file.path().to_str().unwrap_or(""));
+            for part in arg.split_whitespace() {
+                cmd.arg(part);
+            }
+        }
+        if let Some(vk_arg) = &args.vk
+            && let Some(vk_path) = &version.vk_path
+        {
+            let full_vk_path = format!("{}/{}", artifacts_dir, vk_path);
+            let arg = vk_arg.replace("{vk_file}", &full_vk_path);
+            for part in arg.split_whitespace() {
+                cmd.arg(part);
             }
         }
     }
-}
 
-#[derive(Debug, serde::Deserialize)]
-struct VerifierOutput {
-    valid: bool,
-    error: Option<String>,
+    tracing::debug!(command = ?cmd, "executing verifier");
+
+    // Execute asynchronously
+    let output = cmd
+        .output()
+        .await
+        .map_err(|e| Status::internal(format!("Failed to execute verifier: {}", e)))?;
+
+    tracing::debug!(
+        exit_code = ?output.status.code(),
+        stdout_len = output.stdout.len(),
+        stderr_len = output.stderr.len(),
+        "verifier execution complete"
+    );
+
+    // Temp files are automatically cleaned up when they go out of scope
+
+    Ok(output::parse_output(interface, &output))
 }
 
 #[tonic::async_trait]
@@ -187,11 +393,6 @@ impl Verifier for RustVerifierService {
     ) -> Result<Response<VerifyResponse>, Status> {
         let req = request.into_inner();
 
-        let version = self
-            .get_active_version()
-            .map(|v| v.version.clone())
-            .unwrap_or_else(|| "unknown".to_string());
-
         tracing::info!(
             prover = %self.manifest.prover,
             proof_system = %req.proof_system,
@@ -199,21 +400,70 @@ impl Verifier for RustVerifierService {
             "verification request"
         );
 
-        let (valid, error) = self
-            .execute_verifier(&req.proof, &req.public_inputs)
+        if req.proof.len() as u64 > self.max_proof_bytes {
+            return Err(proof_too_large(self.max_proof_bytes));
+        }
+
+        let proof_file = write_temp_proof(&req.proof)?;
+        let outcome = self
+            .execute_active(proof_file.path(), &req.public_inputs)
             .await?;
 
+        Ok(Response::new(self.verify_response(outcome)))
+    }
+
+    async fn verify_stream(
+        &self,
+        request: Request<Streaming<VerifyStreamRequest>>,
+    ) -> Result<Response<VerifyResponse>, Status> {
+        let mut stream = request.into_inner();
+
+        let metadata = match stream.message().await?.and_then(|m| m.payload) {
+            Some(Payload::Metadata(metadata)) => metadata,
+            _ => {
+                return Err(Status::invalid_argument(
+                    "first VerifyStream message must carry the metadata",
+                ));
+            }
+        };
+
+        // Chunks go straight to disk so large proofs are never held in memory
+        let proof_file = NamedTempFile::new()
+            .map_err(|e| Status::internal(format!("Failed to create temp file: {}", e)))?;
+        let mut writer = tokio::fs::File::from_std(proof_file.reopen().map_err(temp_file_error)?);
+        let mut proof_len: u64 = 0;
+
+        // A cancelled upload surfaces as an error here, so a truncated proof is never verified
+        while let Some(message) = stream.message().await? {
+            match message.payload {
+                Some(Payload::Chunk(chunk)) => {
+                    proof_len += chunk.len() as u64;
+                    if proof_len > self.max_proof_bytes {
+                        return Err(proof_too_large(self.max_proof_bytes));
+                    }
+                    writer.write_all(&chunk).await.map_err(temp_file_error)?;
+                }
+                Some(Payload::Metadata(_)) => {
+                    return Err(Status::invalid_argument("metadata sent more than once"));
+                }
+                None => {}
+            }
+        }
+        writer.flush().await.map_err(temp_file_error)?;
+        drop(writer);
+
         tracing::info!(
             prover = %self.manifest.prover,
-            valid = valid,
-            "verification complete"
+            proof_system = %metadata.proof_system,
+            proof_len,
+            "streamed verification request"
         );
 
-        Ok(Response::new(VerifyResponse {
-            valid,
-            prover_version: version,
-            error,
-        }))
+        let outcome = self
+            .execute_active(proof_file.path(), &metadata.public_inputs)
+            .await?;
+
+        Ok(Response::new(self.verify_response(outcome)))
     }
 
     async fn health(
@@ -225,9 +475,32 @@ impl Verifier for RustVerifierService {
             .map(|v| v.version.clone())
             .unwrap_or_else(|| "unknown".to_string());
 
+        let snapshot = self.health.snapshot();
+
         Ok(Response::new(HealthResponse {
-            healthy: true,
+            healthy: snapshot.healthy,
             version,
+            versions: self.version_statuses(&snapshot.versions),
+            failure_rate: snapshot.failure_rate,
+            recent_requests: snapshot.recent_requests as u32,
+            recent_timeouts: snapshot.recent_timeouts as u32,
+            canary: snapshot.canary.map(|c| CanaryStatus {
+                passed: c.passed,
+                error: c.error,
+                checked_at: health::unix_seconds(c.checked_at),
+                duration_ms: c.duration.as_millis() as u64,
+            }),
+        }))
+    }
+
+    async fn describe(
+        &self,
+        _request: Request<DescribeRequest>,
+    ) -> Result<Response<DescribeResponse>, Status> {
+        Ok(Response::new(DescribeResponse {
+            prover: self.manifest.prover.clone(),
+            description: self.manifest.description.clone(),
+            versions: self.version_statuses(&self.health.snapshot().versions),
         }))
     }
 }
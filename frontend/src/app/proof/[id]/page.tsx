@@ -1,33 +1,168 @@
 'use client';
 
+import { useEffect, useState } from 'react';
 import Link from 'next/link';
 import { useParams } from 'next/navigation';
+import { getProof, type ProofDetail } from '@/lib/api';
+
+function StatusBadge({ status }: { status: string }) {
+  const colors = {
+    valid: 'bg-green-100 text-green-800',
+    invalid: 'bg-red-100 text-red-800',
+    active: 'bg-green-100 text-green-800',
+    deprecated: 'bg-yellow-100 text-yellow-800',
+    revoked: 'bg-red-100 text-red-800',
+  };
+
+  return (
+    <span
+      className={`inline-flex px-2 py-0.5 text-xs font-medium rounded ${colors[status as keyof typeof colors] || 'bg-gray-100 text-gray-800'}`}
+    >
+      {status}
+    </span>
+  );
+}
 
 export default function ProofPage() {
   const params = useParams();
   const proofId = params.id as string;
+  const [proof, setProof] = useState<ProofDetail | null>(null);
+  const [loading, setLoading] = useState(true);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    getProof(proofId)
+      .then(setProof)
+      .catch((err) => setError(err instanceof Error ? err.message : 'Failed to load proof'))
+      .finally(() => setLoading(false));
+  }, [proofId]);
+
+  if (loading) {
+    return (
+      <div className="max-w-3xl mx-auto">
+        <h1 className="text-3xl font-bold mb-8">Proof</h1>
+        <div className="text-gray-500">Loading...</div>
+      </div>
+    );
+  }
+
+  if (error || !proof) {
+    return (
+      <div className="max-w-3xl mx-auto">
+        <h1 className="text-3xl font-bold mb-8">Proof</h1>
+        <div className="p-6 bg-yellow-50 border border-yellow-200 rounded-lg">
+          <p className="text-yellow-800 mb-4">{error || 'Proof not found'}</p>
+          <p className="text-sm text-gray-600 mb-4">
+            Proof hash: <code className="bg-white px-2 py-1 rounded break-all">{proofId}</code>
+          </p>
+          <p className="text-sm text-gray-600">
+            Prova only knows proofs that were verified or anchored. To verify one, use the{' '}
+            <Link href="/verify" className="text-blue-600 hover:underline">
+              verification page
+            </Link>
+            .
+          </p>
+        </div>
+      </div>
+    );
+  }
 
   return (
     <div className="max-w-3xl mx-auto">
-      <h1 className="text-3xl font-bold mb-8">Proof Lookup Unavailable</h1>
-
-      <div className="p-6 bg-yellow-50 border border-yellow-200 rounded-lg">
-        <p className="text-yellow-800 mb-4">
-          Proof storage has been removed. Prova now performs stateless verification
-          and returns proof metadata directly in the verification response.
-        </p>
-        <p className="text-sm text-gray-600 mb-4">
-          Proof hash: <code className="bg-white px-2 py-1 rounded">{proofId}</code>
-        </p>
-        <p className="text-sm text-gray-600">
-          To verify a proof, use the{' '}
-          <Link href="/verify" className="text-blue-600 hover:underline">
-            verification page
-          </Link>{' '}
-          or the API directly. The proof hash is returned in the verification response
-          for your records.
-        </p>
+      <div className="flex items-center gap-3 mb-2">
+        <h1 className="text-3xl font-bold">Proof</h1>
+        {proof.valid !== null && <StatusBadge status={proof.valid ? 'valid' : 'invalid'} />}
       </div>
+      <p className="font-mono text-sm break-all text-gray-600 mb-8">{proof.proof_hash}</p>
+
+      <h2 className="text-xl font-semibold mb-4">Verifications</h2>
+      {proof.verifications.length === 0 ? (
+        <p className="text-gray-500 mb-8">This proof has not been verified by Prova.</p>
+      ) : (
+        <div className="space-y-4 mb-8">
+          {proof.verifications.map((v) => (
+            <div
+              key={`${v.prover}-${v.proof_system}-${v.prover_version}-${v.verifier_version}-${v.vk_hash}`}
+              className="p-4 border rounded-lg"
+            >
+              <div className="flex items-center gap-2 mb-3">
+                <StatusBadge status={v.valid ? 'valid' : 'invalid'} />
+                <span className="font-medium">
+                  {v.prover} {v.prover_version}
+                </span>
+                {v.verifier_version && (
+                  <span className="text-sm text-gray-500">verifier {v.verifier_version}</span>
+                )}
+              </div>
+              <div className="grid md:grid-cols-2 gap-2 text-sm">
+                <div>
+                  <span className="text-gray-500">Last verified: </span>
+                  {new Date(v.last_verified_at).toLocaleString()}
+                </div>
+                <div>
+                  <span className="text-gray-500">Verifications: </span>
+                  {v.verification_count}
+                </div>
+              </div>
+              {v.vk && (
+                <div className="mt-3 text-sm">
+                  <span className="text-gray-500">VK: </span>
+                  <span className="font-mono text-xs break-all">{v.vk.alias || v.vk.hash}</span>{' '}
+                  <StatusBadge status={v.vk.status} />
+                </div>
+              )}
+              {v.error && (
+                <div className="mt-3 p-2 bg-red-50 rounded text-red-700 text-sm">{v.error}</div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
+
+      <h2 className="text-xl font-semibold mb-4">Anchors</h2>
+      {proof.anchors.length === 0 ? (
+        <p className="text-gray-500">This proof has not been anchored on chain yet.</p>
+      ) : (
+        <div className="space-y-4">
+          {proof.anchors.map((anchor) => (
+            <div key={anchor.id} className="p-4 border rounded-lg text-sm">
+              <div className="flex items-center gap-2 mb-2">
+                <span className="font-medium">{anchor.chain.name}</span>
+                <StatusBadge status={anchor.valid ? 'valid' : 'invalid'} />
+              </div>
+              <div>
+                <span className="text-gray-500">Status: </span>
+                {anchor.confirmation.status}
+                {anchor.confirmation.confirmations !== null &&
+                  ` (${anchor.confirmation.confirmations} confirmations)`}
+              </div>
+              {anchor.chain.block_number !== null && (
+                <div>
+                  <span className="text-gray-500">Block: </span>
+                  {anchor.chain.block_number}
+                </div>
+              )}
+              {anchor.chain.tx_hash && (
+                <div className="break-all">
+                  <span className="text-gray-500">Transaction: </span>
+                  {anchor.chain.explorer_url ? (
+                    <a
+                      href={anchor.chain.explorer_url}
+                      target="_blank"
+                      rel="noopener noreferrer"
+                      className="font-mono text-xs text-blue-600 hover:underline"
+                    >
+                      {anchor.chain.tx_hash}
+                    </a>
+                  ) : (
+                    <span className="font-mono text-xs">{anchor.chain.tx_hash}</span>
+                  )}
+                </div>
+              )}
+            </div>
+          ))}
+        </div>
+      )}
     </div>
   );
 }
@@ -6,6 +6,16 @@ export interface VkInfo {
   alias: string | null;
   status: 'active' | 'deprecated' | 'revoked';
   deprecation_reason: string | null;
+  valid_from: string | null;
+  deprecate_at: string | null;
+  revoke_at: string | null;
+  historical?: HistoricalVkStatus;
+}
+
+export interface HistoricalVkStatus {
+  as_of: string;
+  status: 'active' | 'deprecated' | 'revoked';
+  deprecation_reason: string | null;
 }
 
 export interface VerifyResult {
@@ -31,7 +41,59 @@ export interface VerificationKey {
   alias: string | null;
   status: 'active' | 'deprecated' | 'revoked';
   deprecation_reason: string | null;
+  valid_from: string | null;
+  deprecate_at: string | null;
+  revoke_at: string | null;
+  created_at: string;
+}
+
+export interface VerificationOutcome {
+  prover: string;
+  proof_system: string;
+  prover_version: string;
+  verifier_version: string | null;
+  vk_hash: string | null;
+  valid: boolean;
+  error: string | null;
+  verification_count: number;
+  first_verified_at: string;
+  last_verified_at: string;
+  vk: VkInfo | null;
+}
+
+export interface Anchor {
+  id: string;
+  proof_hash: string;
+  vk_hash: string;
+  valid: boolean;
+  prover: string;
+  proof_system: string;
+  chain: {
+    name: string;
+    block_number: number | null;
+    block_hash: string | null;
+    block_timestamp: string | null;
+    tx_hash: string | null;
+    explorer_url: string | null;
+  };
+  integrity: {
+    status: 'verified' | 'unverified' | 'mismatch' | 'unchecked';
+    note: string | null;
+    verification_id: string | null;
+  };
+  confirmation: {
+    status: 'pending' | 'confirmed' | 'finalized' | 'orphaned';
+    confirmations: number | null;
+  };
   created_at: string;
+  updated_at: string;
+}
+
+export interface ProofDetail {
+  proof_hash: string;
+  valid: boolean | null;
+  verifications: VerificationOutcome[];
+  anchors: Anchor[];
 }
 
 export async function verifyProof(data: {
@@ -115,3 +177,14 @@ export async function getVk(id: string, prover?: string): Promise<VerificationKe
 
   return response.json();
 }
+
+export async function getProof(hash: string): Promise<ProofDetail> {
+  const response = await fetch(`${API_URL}/v1/proofs/${encodeURIComponent(hash)}`);
+
+  if (!response.ok) {
+    const error = await response.json();
+    throw new Error(error.error || 'Failed to fetch proof');
+  }
+
+  return response.json();
+}